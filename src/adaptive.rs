@@ -0,0 +1,93 @@
+//! Rate limiting with a delay chosen by each item.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::wait::{clock_setter, Due, Pull};
+use crate::Clock;
+
+/// Adaptive rate limits an Iterator with a delay chosen by the items
+/// themselves: after yielding an item, the next is returned no sooner than
+/// ````delay(&item)```` later, or ````default```` later when that is None.
+/// Create one with ````Ticker::adaptive````.
+///
+/// As with a Ticker, the first item comes ````default```` after the Adaptive
+/// is created. Each delay runs from when the item before it was returned;
+/// see the crate docs for how time spent away counts.
+pub struct Adaptive<I, F> {
+    pull: Pull<I>,
+    default: Duration,
+    delay: F,
+    due: Due,
+}
+
+impl<I, F> Adaptive<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> Option<Duration>,
+{
+    pub(crate) fn new(src: I, default: Duration, delay: F) -> Self {
+        let pull = Pull::new(src);
+        Adaptive {
+            due: Due::after(pull.clock(), default),
+            pull,
+            default,
+            delay,
+        }
+    }
+
+    clock_setter!(Adaptive);
+
+    /// set_clock moves onto ````clock````, starting the first delay over.
+    fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.pull.set_clock(clock);
+        self.due = Due::after(self.pull.clock(), self.default);
+    }
+}
+
+impl<I, F> Iterator for Adaptive<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> Option<Duration>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Adaptive { pull, default, delay, due } = self;
+        pull.next(|item, clock| {
+            due.wait(clock);
+            *due = Due::after(clock, delay(item).unwrap_or(*default));
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pull.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::JumpClock;
+    use crate::Ticker;
+
+    #[test]
+    fn gaps_track_the_items() {
+        let ms = Duration::from_millis;
+        let hints = [None, Some(ms(40)), Some(ms(0)), None, Some(ms(25)), None];
+        let clock = JumpClock::default();
+        let start = clock.now();
+        let arrivals: Vec<_> = Ticker::adaptive(hints.iter().copied(), ms(10), |hint| *hint)
+            .clock(clock.clone())
+            .map(|_| (clock.now() - start).as_millis())
+            .collect();
+        assert_eq!(arrivals, [10, 20, 60, 60, 70, 95]);
+    }
+
+    #[test]
+    fn enormous_delay_does_not_overflow() {
+        let mut iter = Ticker::adaptive(0.., Duration::ZERO, |_| Some(Duration::MAX));
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.size_hint(), (usize::MAX, None));
+    }
+}
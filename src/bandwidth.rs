@@ -0,0 +1,284 @@
+//! Limiting the bandwidth of readers and writers to a number of bytes per
+//! second.
+
+use std::io::{self, BufRead, Read, Write};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::clock::{self, Clock, SystemClock};
+use crate::wait::clock_setter;
+
+/// Budget paces bytes moved through a reader or writer. Each call moves at
+/// most one chunk, a tenth of a second's worth, and then sleeps until the
+/// bytes it moved are paid for. Time spent idle is not banked: the budget
+/// never allows a burst faster than the rate.
+struct Budget {
+    clock: Arc<dyn Clock>,
+    bytes_per_second: u64,
+    /// paid_until is when the bytes moved so far are paid for.
+    paid_until: Option<Instant>,
+}
+
+impl Budget {
+    fn new(bytes_per_second: u64) -> Self {
+        assert!(bytes_per_second > 0,
+                "bytes per second must be positive, got {}",
+                bytes_per_second);
+        Budget {
+            clock: Arc::new(SystemClock),
+            bytes_per_second,
+            paid_until: None,
+        }
+    }
+
+    /// set_clock moves onto ````clock````, with nothing spent yet.
+    fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+        self.paid_until = None;
+    }
+
+    /// chunk is how many of ````len```` bytes one call may move.
+    fn chunk(&self, len: usize) -> usize {
+        let chunk = (self.bytes_per_second / 10).max(1);
+        len.min(chunk.min(usize::MAX as u64) as usize)
+    }
+
+    /// spend pays for ````n```` bytes, sleeping until they are paid for.
+    fn spend(&mut self, n: usize) {
+        if n == 0 {
+            return;
+        }
+        let nanos = n as u128 * 1_000_000_000 / u128::from(self.bytes_per_second);
+        let cost = Duration::from_nanos(nanos.min(u128::from(u64::MAX)) as u64);
+        let now = self.clock.now();
+        let from = self.paid_until.filter(|&paid| paid > now).unwrap_or(now);
+        let paid = from + cost;
+        clock::sleep_until(&*self.clock, paid);
+        self.paid_until = Some(paid);
+    }
+}
+
+/// ThrottledWriter limits how fast bytes are written to ````W````. Each
+/// ````write()```` writes at most a tenth of a second's worth of bytes, then
+/// sleeps just long enough that the bytes written so far stay under
+/// ````bytes_per_second````. A large write is cut short rather than blowing
+/// the budget, so use ````write_all()```` to write a whole buffer.
+///
+/// ````no_run
+/// # use ticker::ThrottledWriter;
+/// # use std::fs::File;
+/// # use std::io::{self, Write};
+/// # fn main() -> io::Result<()> {
+/// let log = File::create("shipped.log")?;
+/// let mut log = ThrottledWriter::new(log, 64 * 1024);
+/// log.write_all(b"at most 64 KiB/s")?;
+/// # Ok(())
+/// # }
+/// ````
+pub struct ThrottledWriter<W> {
+    inner: W,
+    budget: Budget,
+}
+
+impl<W: Write> ThrottledWriter<W> {
+    /// new limits writes to ````inner```` to ````bytes_per_second````.
+    ///
+    /// Panics if ````bytes_per_second```` is 0.
+    pub fn new(inner: W, bytes_per_second: u64) -> Self {
+        ThrottledWriter {
+            inner,
+            budget: Budget::new(bytes_per_second),
+        }
+    }
+
+    clock_setter!(ThrottledWriter);
+
+    fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.budget.set_clock(clock);
+    }
+
+    /// get_ref returns the wrapped writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// get_mut returns the wrapped writer. Writing to it directly bypasses
+    /// the limit.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// into_inner returns the wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for ThrottledWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(&buf[..self.budget.chunk(buf.len())])?;
+        self.budget.spend(n);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// ThrottledReader limits how fast bytes are read from ````R````. Each
+/// ````read()```` reads at most a tenth of a second's worth of bytes, then
+/// sleeps just long enough that the bytes read so far stay under
+/// ````bytes_per_second````. Only bytes actually read count against the
+/// budget, so short reads cost less and reaching EOF returns at once.
+///
+/// If ````R```` is BufRead, so is the ThrottledReader: ````fill_buf()````
+/// offers at most one chunk, and ````consume()```` pays for what is consumed.
+///
+/// ````no_run
+/// # use ticker::ThrottledReader;
+/// # use std::io::{self, Read};
+/// # use std::net::TcpStream;
+/// # fn main() -> io::Result<()> {
+/// let download = TcpStream::connect("example.com:80")?;
+/// let mut body = Vec::new();
+/// ThrottledReader::new(download, 1024 * 1024).read_to_end(&mut body)?;
+/// # Ok(())
+/// # }
+/// ````
+pub struct ThrottledReader<R> {
+    inner: R,
+    budget: Budget,
+}
+
+impl<R: Read> ThrottledReader<R> {
+    /// new limits reads from ````inner```` to ````bytes_per_second````.
+    ///
+    /// Panics if ````bytes_per_second```` is 0.
+    pub fn new(inner: R, bytes_per_second: u64) -> Self {
+        ThrottledReader {
+            inner,
+            budget: Budget::new(bytes_per_second),
+        }
+    }
+
+    clock_setter!(ThrottledReader);
+
+    fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.budget.set_clock(clock);
+    }
+
+    /// get_ref returns the wrapped reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// get_mut returns the wrapped reader. Reading from it directly bypasses
+    /// the limit.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// into_inner returns the wrapped reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for ThrottledReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let chunk = self.budget.chunk(buf.len());
+        let n = self.inner.read(&mut buf[..chunk])?;
+        self.budget.spend(n);
+        Ok(n)
+    }
+}
+
+impl<R: BufRead> BufRead for ThrottledReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let buf = self.inner.fill_buf()?;
+        Ok(&buf[..self.budget.chunk(buf.len())])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.budget.spend(amt);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::JumpClock;
+    use std::io::Cursor;
+
+    #[test]
+    fn writes_at_the_byte_rate() {
+        let clock = JumpClock::default();
+        let start = clock.now();
+        let data: Vec<u8> = (0..4000u32).map(|i| i as u8).collect();
+        let mut writer = ThrottledWriter::new(Vec::new(), 20_000).clock(clock.clone());
+        writer.write_all(&data).unwrap();
+        assert_eq!(clock.now() - start, Duration::from_millis(200));
+        assert_eq!(writer.into_inner(), data);
+    }
+
+    #[test]
+    fn large_writes_are_split() {
+        let mut writer = ThrottledWriter::new(Vec::new(), 20_000).clock(JumpClock::default());
+        assert_eq!(writer.write(&[0; 10_000]).unwrap(), 2000);
+        assert_eq!(writer.write(&[]).unwrap(), 0);
+        assert_eq!(writer.get_ref().len(), 2000);
+        assert!(std::panic::catch_unwind(|| ThrottledWriter::new(Vec::new(), 0)).is_err());
+    }
+
+    #[test]
+    fn reads_at_the_byte_rate() {
+        let clock = JumpClock::default();
+        let start = clock.now();
+        let data: Vec<u8> = (0..4000u32).map(|i| (i * 7) as u8).collect();
+        let mut reader = ThrottledReader::new(Cursor::new(data.clone()), 20_000).clock(clock.clone());
+        let mut read = Vec::new();
+        reader.read_to_end(&mut read).unwrap();
+        assert_eq!(clock.now() - start, Duration::from_millis(200));
+        assert_eq!(read, data);
+
+        let start = clock.now();
+        assert_eq!(reader.read(&mut [0; 16]).unwrap(), 0);
+        assert_eq!(clock.now(), start);
+    }
+
+    #[test]
+    fn short_reads_cost_what_they_read() {
+        let clock = JumpClock::default();
+        let start = clock.now();
+        let mut reader = ThrottledReader::new(Cursor::new(vec![1; 10]), 100).clock(clock.clone());
+        let mut buf = [0; 100];
+        assert_eq!(reader.read(&mut buf).unwrap(), 10);
+        assert_eq!(reader.read(&mut buf).unwrap(), 0);
+        assert_eq!(clock.now() - start, Duration::from_millis(100));
+    }
+
+    #[test]
+    fn idle_time_is_not_banked() {
+        let clock = JumpClock::default();
+        let mut writer = ThrottledWriter::new(Vec::new(), 1000).clock(clock.clone());
+        writer.write_all(&[0; 100]).unwrap();
+        clock.0.advance(Duration::from_secs(5));
+        let start = clock.now();
+        writer.write_all(&[0; 200]).unwrap();
+        assert_eq!(clock.now() - start, Duration::from_millis(200));
+    }
+
+    #[test]
+    fn buf_read_passthrough() {
+        let clock = JumpClock::default();
+        let start = clock.now();
+        let text = "one\ntwo\nthree\n".repeat(100);
+        let reader = ThrottledReader::new(Cursor::new(text.clone()), 20_000).clock(clock.clone());
+        let lines: Vec<String> = reader.lines().map(Result::unwrap).collect();
+        assert_eq!(lines.len(), 300);
+        assert_eq!(lines.join("\n") + "\n", text);
+        assert_eq!(clock.now() - start, Duration::from_millis(70));
+    }
+}
@@ -0,0 +1,82 @@
+//! Grouping a source into batches, so a Ticker can release several items per
+//! tick.
+
+/// Batches groups the items of an Iterator into Vecs of up to ````size````
+/// items. The last batch holds whatever is left, and an exhausted source makes
+/// no empty batch. Paced by a Ticker, as from ````Ticker::batched````, each
+/// tick releases one batch.
+#[derive(Clone, Debug)]
+pub struct Batches<I> {
+    src: I,
+    size: usize,
+}
+
+impl<I: Iterator> Batches<I> {
+    /// new groups ````src```` into batches of ````size````.
+    ///
+    /// Panics if ````size```` is 0.
+    pub fn new(src: I, size: usize) -> Self {
+        assert!(size > 0, "batch size must be positive");
+        Batches { src, size }
+    }
+}
+
+impl<I: Iterator> Iterator for Batches<I> {
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Vec<I::Item>> {
+        let batch: Vec<I::Item> = self.src.by_ref().take(self.size).collect();
+        if batch.is_empty() {
+            None
+        } else {
+            Some(batch)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lo, hi) = self.src.size_hint();
+        let batches = |n: usize| n.div_ceil(self.size);
+        (batches(lo), hi.map(batches))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Ticker;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn batch_sizes() {
+        let batches: Vec<Vec<i32>> = Batches::new(0..7, 3).collect();
+        assert_eq!(batches, vec![vec![0, 1, 2], vec![3, 4, 5], vec![6]]);
+        assert_eq!(Batches::new(0..7, 3).size_hint(), (3, Some(3)));
+        assert_eq!(Batches::new(0..6, 3).count(), 2);
+        assert!(std::panic::catch_unwind(|| Batches::new(0..6, 0)).is_err());
+    }
+
+    #[test]
+    fn one_batch_per_tick() {
+        let interval = Duration::from_millis(20);
+        let start = Instant::now();
+        let mut arrivals = Vec::new();
+        for batch in Ticker::batched(0..50, interval, 20) {
+            arrivals.push((batch.len(), start.elapsed()));
+        }
+        let sizes: Vec<usize> = arrivals.iter().map(|&(len, _)| len).collect();
+        assert_eq!(sizes, [20, 20, 10]);
+        for (n, &(_, at)) in arrivals.iter().enumerate() {
+            let due = interval * (n as u32 + 1);
+            assert!(at + Duration::from_millis(1) >= due, "{:?}", arrivals);
+            assert!(at < due + interval / 2, "{:?}", arrivals);
+        }
+    }
+
+    #[test]
+    fn empty_source_does_not_wait() {
+        let start = Instant::now();
+        let mut ticker = Ticker::batched(0..0, Duration::from_secs(10), 5).pull_first(true);
+        assert_eq!(ticker.next(), None);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,291 @@
+//! Configuring many Tickers alike.
+
+use std::io;
+use std::iter;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::hooks::Hook;
+use crate::{Clock, Config, MissedTickBehavior, Pacing, Resume, Shared, SharedTicker, TickEdge, TickIter, Ticker};
+
+/// TickerBuilder holds a Ticker's settings apart from its source, so one
+/// configuration can rate limit any number of Iterators. Create one with
+/// ````Ticker::builder````. Each setting is the Ticker method of the same name,
+/// with the same default, so a builder with nothing set makes exactly the
+/// Ticker ````Ticker::new```` would.
+///
+/// ````no_run
+/// # use ticker::{MissedTickBehavior, Ticker};
+/// # use std::time::Duration;
+/// # fn fetch(_: &str) {}
+/// let polite = Ticker::builder(Duration::from_secs(1))
+///     .first_tick_immediately(true)
+///     .jitter(Duration::from_millis(100))
+///     .missed_tick_behavior(MissedTickBehavior::Skip);
+/// for url in polite.wrap(["a.example", "b.example"].iter()) {
+///     fetch(url)
+/// }
+/// for url in polite.wrap(["c.example"].iter()) {
+///     fetch(url)
+/// }
+/// ````
+#[derive(Clone, Debug)]
+pub struct TickerBuilder {
+    config: Config,
+    clock: Option<Arc<dyn Clock>>,
+}
+
+impl TickerBuilder {
+    pub(crate) fn new(interval: Duration) -> Self {
+        TickerBuilder {
+            config: Config::new(interval),
+            clock: None,
+        }
+    }
+
+    /// with_config is a builder making Tickers like one already configured
+    /// with ````config````, on ````clock````.
+    pub(crate) fn with_config(config: Config, clock: Arc<dyn Clock>) -> Self {
+        TickerBuilder {
+            config,
+            clock: Some(clock),
+        }
+    }
+
+    /// wrap creates a Ticker with these settings that rate limits ````src````.
+    /// Its schedule starts now, as with ````Ticker::new````.
+    pub fn wrap<I: Iterator>(&self, src: I) -> Ticker<I> {
+        let mut ticker = Ticker::new(src, self.config.interval);
+        ticker.config = self.config.clone();
+        if let Some(ref clock) = self.clock {
+            ticker.shared = Arc::new(Shared::with_clock(clock.clone()));
+            ticker.start = ticker.shared.clock.now();
+        }
+        ticker
+    }
+
+    /// try_wrap is ````wrap````, starting the Ticker's worker thread at once
+    /// as ````Ticker::try_new```` does, so failing to spawn it is an error.
+    pub fn try_wrap<I: Iterator>(&self, src: I) -> io::Result<Ticker<I>> {
+        let mut ticker = self.wrap(src);
+        ticker.start_backend()?;
+        Ok(ticker)
+    }
+
+    /// build_iter is ````wrap````, returning the TickIter that ````.ticked()````
+    /// does.
+    pub fn build_iter<I: Iterator>(&self, src: I) -> TickIter<I> {
+        TickIter { ticker: self.wrap(src) }
+    }
+
+    /// build_shared creates a SharedTicker with these settings, as
+    /// ````Ticker::shared```` does. It is always inline.
+    pub fn build_shared(&self) -> SharedTicker {
+        SharedTicker::new(self.wrap(iter::repeat(())).inline(true))
+    }
+
+    /// clock is ````Ticker::new_with_clock````'s clock. Every Ticker built
+    /// shares it.
+    pub fn clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+
+    /// aligned is whether ticks land on multiples of the interval since the
+    /// Unix epoch, as for ````Ticker::aligned````.
+    pub fn aligned(mut self, aligned: bool) -> Self {
+        self.config.aligned = aligned;
+        self
+    }
+
+    /// edge is ````Ticker::edge````. Each Ticker built starts its schedule
+    /// when its first item is asked for.
+    pub fn edge(mut self, edge: TickEdge) -> Self {
+        self.config.edge = edge;
+        self
+    }
+
+    /// first_tick_immediately is ````Ticker::first_tick_immediately````.
+    pub fn first_tick_immediately(mut self, immediate: bool) -> Self {
+        self.config.first_tick_immediately = immediate;
+        self
+    }
+
+    /// for_duration is ````Ticker::for_duration````. Each Ticker built runs
+    /// for ````total```` from when it is built.
+    pub fn for_duration(mut self, total: Duration) -> Self {
+        self.config.run_for = Some(total);
+        self
+    }
+
+    /// initial_delay is ````Ticker::initial_delay````.
+    pub fn initial_delay(mut self, delay: Duration) -> Self {
+        self.config.initial_delay = Some(delay);
+        self
+    }
+
+    /// initial_burst is ````Ticker::initial_burst````. Each Ticker built gets
+    /// its own burst.
+    pub fn initial_burst(mut self, burst: usize) -> Self {
+        self.config.initial_burst = burst;
+        self
+    }
+
+    /// missed_tick_behavior is ````Ticker::missed_tick_behavior````.
+    pub fn missed_tick_behavior(mut self, behavior: MissedTickBehavior) -> Self {
+        self.config.missed_tick_behavior = behavior;
+        self
+    }
+
+    /// pacing is ````Ticker::pacing````.
+    pub fn pacing(mut self, pacing: Pacing) -> Self {
+        self.config.pacing = pacing;
+        self
+    }
+
+    /// max_ticks is ````Ticker::max_ticks````.
+    pub fn max_ticks(mut self, max_ticks: usize) -> Self {
+        self.config.max_ticks = Some(max_ticks);
+        self
+    }
+
+    /// max_pending is ````Ticker::max_pending````.
+    ///
+    /// Panics if ````max_pending```` is 0.
+    pub fn max_pending(mut self, max_pending: usize) -> Self {
+        assert!(max_pending > 0, "max_pending must be at least 1");
+        self.config.max_pending = max_pending;
+        self
+    }
+
+    /// resume_policy is ````Ticker::resume_policy````.
+    pub fn resume_policy(mut self, after: u32, behavior: MissedTickBehavior) -> Self {
+        self.config.resume = Resume { after, behavior };
+        self
+    }
+
+    /// inline is ````Ticker::inline````.
+    pub fn inline(mut self, inline: bool) -> Self {
+        self.config.inline = inline;
+        self
+    }
+
+    /// start_full is ````Ticker::start_full````.
+    pub fn start_full(mut self, full: bool) -> Self {
+        self.config.start_full = full;
+        self
+    }
+
+    /// pull_first is ````Ticker::pull_first````.
+    pub fn pull_first(mut self, first: bool) -> Self {
+        self.config.pull_first = first;
+        self
+    }
+
+    /// jitter is ````Ticker::jitter````.
+    pub fn jitter(mut self, jitter: Duration) -> Self {
+        self.config.jitter = jitter;
+        self
+    }
+
+    /// jitter_seed is ````Ticker::jitter_seed````. Every Ticker built gets
+    /// the same offsets.
+    pub fn jitter_seed(mut self, seed: u64) -> Self {
+        self.config.jitter_seed = Some(seed);
+        self
+    }
+
+    /// spin_threshold is ````Ticker::spin_threshold````.
+    pub fn spin_threshold(mut self, threshold: Duration) -> Self {
+        self.config.spin = threshold;
+        self
+    }
+
+    /// on_tick is ````Ticker::on_tick````. Every Ticker built runs the same
+    /// ````f````.
+    pub fn on_tick<F>(mut self, f: F) -> Self
+    where
+        F: Fn(Instant) + Send + Sync + 'static,
+    {
+        self.config.hooks.on_tick = Hook::new(f);
+        self
+    }
+
+    /// on_missed_tick is ````Ticker::on_missed_tick````.
+    pub fn on_missed_tick<F>(mut self, f: F) -> Self
+    where
+        F: Fn(u64) + Send + Sync + 'static,
+    {
+        self.config.hooks.on_missed = Hook::new(f);
+        self
+    }
+
+    /// on_resume is ````Ticker::on_resume````.
+    pub fn on_resume<F>(mut self, f: F) -> Self
+    where
+        F: Fn(Duration) + Send + Sync + 'static,
+    {
+        self.config.hooks.on_resume = Hook::new(f);
+        self
+    }
+
+    /// thread_name is ````Ticker::thread_name````. Every Ticker built names
+    /// its thread the same.
+    pub fn thread_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.config.thread_name = Some(name.into());
+        self
+    }
+
+    /// stack_size is ````Ticker::stack_size````.
+    pub fn stack_size(mut self, bytes: usize) -> Self {
+        self.config.stack_size = Some(bytes);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ManualClock;
+    use std::thread;
+    use std::time::Instant;
+
+    #[test]
+    fn defaults_match_new() {
+        let built = format!("{:?}", Ticker::builder(Duration::from_secs(3)).config);
+        let new = format!("{:?}", Ticker::new(0..1, Duration::from_secs(3)).config);
+        assert_eq!(built, new);
+    }
+
+    #[test]
+    fn options_combine() {
+        let interval = Duration::from_millis(20);
+        let builder = Ticker::builder(interval)
+            .first_tick_immediately(true)
+            .inline(true)
+            .max_pending(3);
+        for _ in 0..2 {
+            let start = Instant::now();
+            let mut ticker = builder.wrap(0..4);
+            assert_eq!(ticker.next(), Some(0));
+            assert!(start.elapsed() < interval / 2, "{:?}", start.elapsed());
+            thread::sleep(interval * 3);
+            let start = Instant::now();
+            assert_eq!(ticker.by_ref().take(2).collect::<Vec<_>>(), [1, 2]);
+            assert!(start.elapsed() < interval / 2, "{:?}", start.elapsed());
+        }
+        assert!(std::panic::catch_unwind(|| Ticker::builder(interval).max_pending(0)).is_err());
+    }
+
+    #[test]
+    fn built_tickers_share_clock() {
+        let clock = ManualClock::new();
+        let builder = Ticker::builder(Duration::from_secs(60)).clock(clock.clone()).inline(true);
+        let mut a = builder.wrap(0..);
+        let mut b = builder.build_iter(10..);
+        assert_eq!(a.try_next(), Err(crate::NotDue));
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(a.next(), Some(0));
+        assert_eq!(b.next(), Some(10));
+    }
+}
@@ -0,0 +1,125 @@
+//! Rate limiting each class of item at its own rate.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::wait::{clock_setter, Due, Pull};
+use crate::Clock;
+
+/// Classified rate limits an Iterator by class of item, each class with its
+/// own interval: after yielding an item of class ````k````, the next item of
+/// class ````k```` is returned no sooner than ````rates[k]```` later, or the
+/// default interval for classes without a rate. Create one with
+/// ````Ticker::classified````.
+///
+/// The guarantee is a minimum gap per class, not a rate for the items in
+/// between. Since an Iterator is sequential, an item waiting for its class
+/// holds up everything behind it: the items of other classes only flow
+/// freely until the next item of a busier class comes along. The first item
+/// of each class is returned at once. Time spent away is not banked, as the
+/// crate docs describe.
+pub struct Classified<I, F, K> {
+    pull: Pull<I>,
+    classify: F,
+    rates: HashMap<K, Duration>,
+    default: Duration,
+    /// due is when the next item of each class seen so far may be returned.
+    due: HashMap<K, Due>,
+}
+
+impl<I, F, K> Classified<I, F, K>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> K,
+    K: Eq + Hash,
+{
+    pub(crate) fn new(src: I, classify: F, rates: HashMap<K, Duration>, default: Duration) -> Self {
+        Classified {
+            pull: Pull::new(src),
+            classify,
+            rates,
+            default,
+            due: HashMap::new(),
+        }
+    }
+
+    clock_setter!(Classified);
+
+    /// set_clock moves onto ````clock````, with every class due at once.
+    fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.pull.set_clock(clock);
+        self.due.clear();
+    }
+}
+
+impl<I, F, K> Iterator for Classified<I, F, K>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> K,
+    K: Eq + Hash,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Classified { pull, classify, rates, default, due } = self;
+        pull.next(|item, clock| {
+            let class = classify(item);
+            if let Some(next) = due.get(&class) {
+                next.wait(clock);
+            }
+            let interval = rates.get(&class).copied().unwrap_or(*default);
+            due.insert(class, Due::after(clock, interval));
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pull.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::JumpClock;
+    use crate::Ticker;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum Op {
+        Read,
+        Write,
+        Delete,
+    }
+
+    #[test]
+    fn classes_keep_their_own_gaps() {
+        let ms = Duration::from_millis;
+        let rates: HashMap<_, _> = vec![(Op::Read, ms(10)), (Op::Write, ms(50))].into_iter().collect();
+        let ops = vec![Op::Write, Op::Read, Op::Read, Op::Read, Op::Write, Op::Read, Op::Write];
+        let clock = JumpClock::default();
+        let start = clock.now();
+        let arrivals: Vec<_> = Ticker::classified(ops.into_iter(), |op| *op, rates.clone(), ms(30))
+            .clock(clock.clone())
+            .map(|op| (op, (clock.now() - start).as_millis()))
+            .collect();
+        // Reads went at their own rate between the writes, the second waiting
+        // on nothing but the write ahead of it.
+        assert_eq!(arrivals,
+                   [(Op::Write, 0),
+                    (Op::Read, 0),
+                    (Op::Read, 10),
+                    (Op::Read, 20),
+                    (Op::Write, 50),
+                    (Op::Read, 50),
+                    (Op::Write, 100)]);
+
+        let start = clock.now();
+        let deletes = vec![Op::Delete; 3];
+        let arrivals: Vec<_> = Ticker::classified(deletes.into_iter(), |op| *op, rates, ms(20))
+            .clock(clock.clone())
+            .map(|_| (clock.now() - start).as_millis())
+            .collect();
+        assert_eq!(arrivals, [0, 20, 40]);
+    }
+}
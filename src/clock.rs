@@ -0,0 +1,150 @@
+//! Clocks that pace a Ticker, so tests can run without really waiting.
+
+use std::fmt::Debug;
+use std::hint;
+#[cfg(any(test, feature = "test-util"))]
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::pace::Sleeper;
+
+/// Clock is the time source a Ticker schedules against. Waiting still happens
+/// by blocking the thread, so a Clock also says how long to block before
+/// looking at it again.
+pub trait Clock: Debug + Send + Sync {
+    /// now is the current time on this clock.
+    fn now(&self) -> Instant;
+
+    /// timeout is how long a thread waiting for ````deadline```` should block
+    /// before checking ````now()```` again. It may be shorter than the time
+    /// left; the default is exactly the time left, for clocks that keep pace
+    /// with real time.
+    fn timeout(&self, deadline: Instant) -> Duration {
+        deadline.saturating_duration_since(self.now())
+    }
+}
+
+/// sleep_until blocks the thread until ````clock```` reaches ````deadline````,
+/// spinning instead where threads cannot sleep.
+pub(crate) fn sleep_until(clock: &dyn Clock, deadline: Instant) {
+    loop {
+        let timeout = clock.timeout(deadline);
+        if timeout == Duration::ZERO {
+            return;
+        }
+        if crate::THREADS {
+            thread::sleep(timeout);
+        } else {
+            hint::spin_loop();
+        }
+    }
+}
+
+/// SystemClock is the monotonic system clock, ````Instant::now()````. Tickers
+/// use it unless created with ````Ticker::new_with_clock```` or given another
+/// Clock.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Every Clock is a Sleeper, so a Paced Iterator can run on one, sleeping the
+/// thread between items.
+impl<C: Clock> Sleeper for C {
+    type Instant = Instant;
+
+    fn now(&mut self) -> Instant {
+        Clock::now(self)
+    }
+
+    fn sleep_until(&mut self, deadline: Instant) {
+        sleep_until(self, deadline)
+    }
+}
+
+/// ManualClock is a Clock that only moves when ````advance()```` is called,
+/// for testing code built on a Ticker without sleeping through its intervals.
+/// Clones share one time, so keep a clone to advance the Ticker's clock.
+///
+/// Advancing by several intervals at once fires the missed ticks as the
+/// Ticker's ````MissedTickBehavior```` says. Threads waiting on a ManualClock
+/// check it every millisecond of real time, so ticks fire at most that long
+/// after the clock is advanced past them.
+///
+/// ````no_run
+/// # use std::time::Duration;
+/// use ticker::{ManualClock, Ticker};
+///
+/// let clock = ManualClock::new();
+/// let mut iter = Ticker::new_with_clock((0..), Duration::from_secs(60), clock.clone())
+///     .inline(true);
+/// clock.advance(Duration::from_secs(60));
+/// assert_eq!(iter.next(), Some(0));
+/// ````
+#[cfg(any(test, feature = "test-util"))]
+#[derive(Clone, Debug)]
+pub struct ManualClock {
+    now: Arc<Mutex<Instant>>,
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl ManualClock {
+    /// POLL is how often a thread waiting on a ManualClock checks it.
+    const POLL: Duration = Duration::from_millis(1);
+
+    /// new creates a ManualClock, starting at the current system time.
+    pub fn new() -> Self {
+        ManualClock {
+            now: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// advance moves this clock, and every clone of it, forward by ````by````.
+    pub fn advance(&self, by: Duration) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) += by;
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl Default for ManualClock {
+    fn default() -> Self {
+        ManualClock::new()
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn timeout(&self, deadline: Instant) -> Duration {
+        deadline.saturating_duration_since(self.now()).min(ManualClock::POLL)
+    }
+}
+
+/// JumpClock is a ManualClock that moves straight to any deadline waited for,
+/// so a test on one thread sees exactly when each item was due without
+/// waiting for it.
+#[cfg(test)]
+#[derive(Clone, Debug, Default)]
+pub(crate) struct JumpClock(pub(crate) ManualClock);
+
+#[cfg(test)]
+impl Clock for JumpClock {
+    fn now(&self) -> Instant {
+        self.0.now()
+    }
+
+    fn timeout(&self, deadline: Instant) -> Duration {
+        if let Some(left) = deadline.checked_duration_since(self.now()) {
+            self.0.advance(left);
+        }
+        Duration::ZERO
+    }
+}
@@ -0,0 +1,386 @@
+//! Ticking at wall clock times matched by a cron expression.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, TimeDelta, TimeZone, Timelike, Utc};
+
+use crate::wait::Pull;
+use crate::Ticker;
+
+/// MAX_WAKE bounds each sleep while waiting for an occurrence, so that waits
+/// follow changes to the system clock.
+const MAX_WAKE: Duration = Duration::from_secs(60);
+
+/// SEARCH_YEARS bounds the search for an occurrence, long enough for a
+/// February 29th on a given weekday to come round.
+const SEARCH_YEARS: i32 = 30;
+
+/// CronSchedule is a parsed five field cron expression:
+/// ````minute hour day-of-month month day-of-week````.
+///
+/// Each field is ````*````, a value, a range ````a-b````, or a list of them
+/// separated by commas, and any but a single value may take a step, as in
+/// ````*/15```` or ````1-5/2````. Months and weekdays may be given by their
+/// first three letters, and Sunday is either 0 or 7. As in other crons, when
+/// both the day of month and the day of week are restricted, a day that
+/// matches either one matches. The shorthands ````@yearly````,
+/// ````@annually````, ````@monthly````, ````@weekly````, ````@daily````,
+/// ````@midnight```` and ````@hourly```` are accepted too.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: Field,
+    hours: Field,
+    days: Field,
+    months: Field,
+    weekdays: Field,
+}
+
+/// Field is the set of values a cron field matches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Field {
+    bits: u64,
+    /// restricted is whether the field was not given as ````*````, which
+    /// decides how days of month and of week combine.
+    restricted: bool,
+}
+
+impl Field {
+    fn has(self, value: u32) -> bool {
+        self.bits & (1 << value) != 0
+    }
+}
+
+const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+const WEEKDAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+impl CronSchedule {
+    /// parse parses a cron expression.
+    pub fn parse(expr: &str) -> Result<Self, CronError> {
+        let error = |reason| CronError {
+            expr: expr.to_string(),
+            reason,
+        };
+        let trimmed = expr.trim();
+        let expanded = match trimmed {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            _ if trimmed.starts_with('@') => return Err(error("unknown shorthand")),
+            _ => trimmed,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(error("expected 5 fields"));
+        }
+        let mut weekdays = field(fields[4], 0, 7, &WEEKDAYS, 0).map_err(error)?;
+        if weekdays.has(7) {
+            weekdays.bits = (weekdays.bits | 1) & !(1 << 7);
+        }
+        Ok(CronSchedule {
+            minutes: field(fields[0], 0, 59, &[], 0).map_err(error)?,
+            hours: field(fields[1], 0, 23, &[], 0).map_err(error)?,
+            days: field(fields[2], 1, 31, &[], 0).map_err(error)?,
+            months: field(fields[3], 1, 12, &MONTHS, 1).map_err(error)?,
+            weekdays,
+        })
+    }
+
+    /// next_after is the first time strictly after ````after```` that this
+    /// schedule matches, in ````after````'s time zone, or None if it never
+    /// matches, as for February 30th. Local times skipped by a daylight
+    /// saving change are skipped, and local times repeated by one match only
+    /// the first time.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = after.timezone();
+        let mut local = after.naive_local();
+        loop {
+            local = self.next_local(local)?;
+            match tz.from_local_datetime(&local).earliest() {
+                Some(next) if next > *after => return Some(next),
+                _ => {}
+            }
+        }
+    }
+
+    /// next_local is the first minute strictly after ````after```` that this
+    /// schedule matches, ignoring time zones.
+    fn next_local(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = after.with_second(0)?.with_nanosecond(0)? + TimeDelta::minutes(1);
+        let last_year = start.year() + SEARCH_YEARS;
+        let mut t = start;
+        while t.year() <= last_year {
+            let date = t.date();
+            if !self.months.has(t.month()) {
+                let (year, month) = if t.month() == 12 { (t.year() + 1, 1) } else { (t.year(), t.month() + 1) };
+                t = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.day_matches(date) {
+                t = date.succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if !self.hours.has(t.hour()) {
+                t = date.and_hms_opt(t.hour(), 0, 0)? + TimeDelta::hours(1);
+            } else if !self.minutes.has(t.minute()) {
+                t += TimeDelta::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let day = self.days.has(date.day());
+        let weekday = self.weekdays.has(date.weekday().num_days_from_sunday());
+        if self.days.restricted && self.weekdays.restricted {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = CronError;
+
+    fn from_str(expr: &str) -> Result<Self, CronError> {
+        CronSchedule::parse(expr)
+    }
+}
+
+/// field parses one field of values from ````min```` to ````max````, which
+/// may be named by ````names````, the first of them being ````base````.
+fn field(s: &str, min: u32, max: u32, names: &[&str], base: u32) -> Result<Field, &'static str> {
+    let value = |v: &str| -> Result<u32, &'static str> {
+        let n = match names.iter().position(|name| name.eq_ignore_ascii_case(v)) {
+            Some(i) => i as u32 + base,
+            None => v.parse().map_err(|_| "invalid value")?,
+        };
+        if n < min || n > max {
+            return Err("value out of range");
+        }
+        Ok(n)
+    };
+    let mut bits = 0;
+    for part in s.split(',') {
+        let (range, step) = match part.find('/') {
+            Some(i) => {
+                let step: u32 = part[i + 1..].parse().map_err(|_| "invalid step")?;
+                if step == 0 {
+                    return Err("invalid step");
+                }
+                (&part[..i], Some(step))
+            }
+            None => (part, None),
+        };
+        let (lo, hi) = match (range, range.find('-')) {
+            ("*", _) => (min, max),
+            (_, Some(i)) => (value(&range[..i])?, value(&range[i + 1..])?),
+            (_, None) if step.is_some() => (value(range)?, max),
+            (_, None) => {
+                let v = value(range)?;
+                (v, v)
+            }
+        };
+        if lo > hi {
+            return Err("invalid range");
+        }
+        for v in (lo..=hi).step_by(step.unwrap_or(1) as usize) {
+            bits |= 1 << v;
+        }
+    }
+    Ok(Field {
+        bits,
+        restricted: !s.starts_with('*'),
+    })
+}
+
+/// CronError is returned by ````Ticker::try_cron```` and
+/// ````CronSchedule::parse```` for an expression that is not valid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CronError {
+    expr: String,
+    reason: &'static str,
+}
+
+impl fmt::Display for CronError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid cron expression {:?}: {}", self.expr, self.reason)
+    }
+}
+
+impl Error for CronError {}
+
+/// Cron rate limits an Iterator to the wall clock times a cron expression
+/// matches, in local time by default. Create one with ````Ticker::cron````.
+///
+/// Each item is returned at the next matching minute after the one before
+/// it, or after the Cron was created for the first. A consumer that is busy
+/// through a matching time misses it: the next item comes at the next match.
+/// Iteration ends if the schedule never matches again.
+///
+/// Unlike the other rate limiters, a Cron waits on the system's wall clock
+/// rather than a Clock, since that is what its expression is matched against.
+pub struct Cron<I> {
+    pull: Pull<I>,
+    schedule: CronSchedule,
+    utc: bool,
+    /// last is the last occurrence returned, for ticks after it.
+    last: DateTime<Utc>,
+}
+
+impl<I: Iterator> Ticker<I> {
+    /// cron rate limits ````src```` to the times ````expr```` matches. See
+    /// Cron and CronSchedule.
+    ///
+    /// Panics if ````expr```` is not a valid cron expression; see
+    /// ````try_cron()````.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # fn standup(_: i32) {}
+    /// // Every weekday at 09:30.
+    /// for day in Ticker::cron((0..), "30 9 * * mon-fri") {
+    ///     standup(day)
+    /// }
+    /// ````
+    pub fn cron(src: I, expr: &str) -> Cron<I> {
+        match Ticker::try_cron(src, expr) {
+            Ok(cron) => cron,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// try_cron is ````cron()````, returning an error for an invalid
+    /// expression.
+    pub fn try_cron(src: I, expr: &str) -> Result<Cron<I>, CronError> {
+        Ok(Cron {
+            pull: Pull::new(src),
+            schedule: CronSchedule::parse(expr)?,
+            utc: false,
+            last: Utc::now(),
+        })
+    }
+}
+
+impl<I> Cron<I> {
+    /// utc sets whether the expression is matched against UTC instead of
+    /// local time. Defaults to false.
+    pub fn utc(mut self, utc: bool) -> Self {
+        self.utc = utc;
+        self
+    }
+}
+
+/// next_due is the next occurrence of ````schedule```` after both now and
+/// ````last````.
+fn next_due(schedule: &CronSchedule, utc: bool, last: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let after = Utc::now().max(last);
+    if utc {
+        schedule.next_after(&after)
+    } else {
+        schedule
+            .next_after(&after.with_timezone(&Local))
+            .map(|due| due.with_timezone(&Utc))
+    }
+}
+
+impl<I: Iterator> Iterator for Cron<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Cron { pull, schedule, utc, last } = self;
+        pull.try_next(|item, _| {
+            let due = next_due(schedule, *utc, *last)?;
+            loop {
+                let left = match (due - Utc::now()).to_std() {
+                    Ok(left) if left > Duration::ZERO => left,
+                    _ => break,
+                };
+                thread::sleep(left.min(MAX_WAKE));
+            }
+            *last = due;
+            Some(item)
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.pull.size_hint().1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn next(expr: &str, after: &str) -> Option<DateTime<Utc>> {
+        CronSchedule::parse(expr).unwrap().next_after(&at(after))
+    }
+
+    #[test]
+    fn weekdays_at_half_nine() {
+        let expr = "30 9 * * 1-5";
+        // Friday after the time, to Monday.
+        assert_eq!(next(expr, "2024-03-08T10:00:00Z"), Some(at("2024-03-11T09:30:00Z")));
+        assert_eq!(next(expr, "2024-03-11T09:29:59Z"), Some(at("2024-03-11T09:30:00Z")));
+        // Strictly after.
+        assert_eq!(next(expr, "2024-03-11T09:30:00Z"), Some(at("2024-03-12T09:30:00Z")));
+        assert_eq!(next("30 9 * * MON-fri", "2024-03-08T10:00:00Z"), Some(at("2024-03-11T09:30:00Z")));
+    }
+
+    #[test]
+    fn steps_lists_and_shorthands() {
+        assert_eq!(next("*/15 * * * *", "2024-03-08T10:07:30Z"), Some(at("2024-03-08T10:15:00Z")));
+        assert_eq!(next("0 8-20/6 * * *", "2024-03-08T14:00:00Z"), Some(at("2024-03-08T20:00:00Z")));
+        assert_eq!(next("0 12 * jan,jul sun", "2024-03-08T00:00:00Z"), Some(at("2024-07-07T12:00:00Z")));
+        assert_eq!(next("0 0 * * 7", "2024-03-08T00:00:00Z"), Some(at("2024-03-10T00:00:00Z")));
+        assert_eq!(next("@daily", "2024-12-31T23:59:00Z"), Some(at("2025-01-01T00:00:00Z")));
+        assert_eq!(next("@hourly", "2024-12-31T23:59:00Z"), Some(at("2025-01-01T00:00:00Z")));
+    }
+
+    #[test]
+    fn days_of_month_and_week() {
+        assert_eq!(next("0 0 29 2 *", "2024-03-01T00:00:00Z"), Some(at("2028-02-29T00:00:00Z")));
+        assert_eq!(next("0 0 30 2 *", "2024-03-01T00:00:00Z"), None);
+        // The 13th or any Friday.
+        assert_eq!(next("0 0 13 * 5", "2024-09-01T00:00:00Z"), Some(at("2024-09-06T00:00:00Z")));
+        // Only Fridays, since a day of month starting with * is no restriction.
+        assert_eq!(next("0 0 */1 * 5", "2024-09-01T00:00:00Z"), Some(at("2024-09-06T00:00:00Z")));
+    }
+
+    #[test]
+    fn local_time_zone() {
+        let tz = FixedOffset::east_opt(2 * 3600).unwrap();
+        let after = tz.with_ymd_and_hms(2024, 1, 1, 10, 0, 0).unwrap();
+        let due = CronSchedule::parse("0 9 * * *").unwrap().next_after(&after).unwrap();
+        assert_eq!(due, tz.with_ymd_and_hms(2024, 1, 2, 9, 0, 0).unwrap());
+        assert_eq!(due.with_timezone(&Utc), at("2024-01-02T07:00:00Z"));
+    }
+
+    #[test]
+    fn invalid_expressions() {
+        for expr in &["", "* * * *", "* * * * * *", "60 * * * *", "*/0 * * * *", "5-1 * * * *", "* * * foo *", "@often"] {
+            assert!(CronSchedule::parse(expr).is_err(), "{:?}", expr);
+            assert!(Ticker::try_cron(0..1, expr).is_err(), "{:?}", expr);
+        }
+        let e = Ticker::try_cron(0..1, "* * 0 * *").err().unwrap();
+        assert_eq!(e.to_string(), "invalid cron expression \"* * 0 * *\": value out of range");
+        assert!(std::panic::catch_unwind(|| Ticker::cron(0..1, "nope")).is_err());
+        assert_eq!("1 2 3 4 5".parse::<CronSchedule>(), CronSchedule::parse("1 2 3 4 fri"));
+    }
+
+    #[test]
+    fn exhausted_source_does_not_wait() {
+        let mut cron = Ticker::cron(0..0, "0 0 1 1 *").utc(true);
+        assert_eq!(cron.next(), None);
+        assert_eq!(cron.size_hint(), (0, Some(0)));
+    }
+}
@@ -0,0 +1,177 @@
+//! Rate limiting calls, for code with no Iterator to wrap.
+
+use std::time::Duration;
+
+use crate::{MissedTickBehavior, SharedTicker, Ticker, Timeout};
+
+/// RateGate paces calls made anywhere in a program: ````wait()```` before each
+/// one blocks until the next permitted slot, one every ````interval````. The
+/// first call passes immediately, and time spent not calling is not saved
+/// up, so calls through a gate are always at least ````interval```` apart.
+///
+/// A RateGate is Send and Sync. Share one behind an Arc, or clone it, and
+/// calls from every thread together go no faster than ````interval````,
+/// taking turns in the order they started waiting.
+///
+/// ````no_run
+/// # use ticker::RateGate;
+/// # use std::sync::Arc;
+/// # use std::thread;
+/// # use std::time::Duration;
+/// # fn fetch(_: &str) {}
+/// let gate = Arc::new(RateGate::new(Duration::from_millis(200)));
+/// for url in ["a.example", "b.example"].iter() {
+///     let gate = gate.clone();
+///     thread::spawn(move || {
+///         gate.wait();
+///         fetch(url)
+///     });
+/// }
+/// ````
+#[derive(Clone)]
+pub struct RateGate {
+    ticks: SharedTicker,
+}
+
+impl RateGate {
+    /// new creates a RateGate that lets one call through every
+    /// ````interval````.
+    pub fn new(interval: Duration) -> Self {
+        let ticks = Ticker::builder(interval)
+            .first_tick_immediately(true)
+            .missed_tick_behavior(MissedTickBehavior::Delay)
+            .build_shared();
+        RateGate { ticks }
+    }
+
+    /// wait blocks until the caller may pass, and uses up that slot.
+    pub fn wait(&self) {
+        self.ticks.tick();
+    }
+
+    /// acquire is ````wait()````, by the name a RateLimiter's callers use.
+    pub fn acquire(&self) {
+        self.wait()
+    }
+
+    /// acquire_timeout is ````wait()````, giving up with ````Err(Timeout)````
+    /// if no slot comes within ````timeout````. Giving up takes no slot, and
+    /// the callers waiting behind keep their turns.
+    pub fn acquire_timeout(&self, timeout: Duration) -> Result<(), Timeout> {
+        self.ticks.tick_timeout(timeout).map(|_| ())
+    }
+
+    /// try_pass uses up a slot and returns true if one is free now, without
+    /// blocking. It returns false if the next slot is not due yet, or if
+    /// another caller is already waiting for it.
+    pub fn try_pass(&self) -> bool {
+        self.ticks.try_tick()
+    }
+
+    /// try_acquire is ````try_pass()````, by the name a RateLimiter's callers
+    /// use.
+    pub fn try_acquire(&self) -> bool {
+        self.try_pass()
+    }
+}
+
+/// RateLimiter is a RateGate, by the name for one rate budget shared by
+/// any number of handles: each ````acquire()````, from any clone on any
+/// thread, takes the next slot, and slots are granted at most once every
+/// ````interval```` across them all.
+///
+/// ````no_run
+/// # use ticker::RateLimiter;
+/// # use std::thread;
+/// # use std::time::Duration;
+/// # fn call_api(_: usize) {}
+/// // 20 calls a second, however many threads make them.
+/// let quota = RateLimiter::new(Duration::from_millis(50));
+/// for worker in 0..8 {
+///     let quota = quota.clone();
+///     thread::spawn(move || loop {
+///         quota.acquire();
+///         call_api(worker)
+///     });
+/// }
+/// ````
+pub type RateLimiter = RateGate;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Instant;
+
+    #[test]
+    fn try_pass_does_not_exceed_the_rate() {
+        let interval = Duration::from_millis(10);
+        let gate = RateGate::new(interval);
+        let start = Instant::now();
+        let callers: Vec<_> = (0..3)
+            .map(|_| {
+                let gate = gate.clone();
+                thread::spawn(move || {
+                    let mut passed = 0;
+                    while start.elapsed() < interval * 10 {
+                        passed += gate.try_pass() as usize;
+                        thread::yield_now();
+                    }
+                    passed
+                })
+            })
+            .collect();
+        let passed: usize = callers.into_iter().map(|c| c.join().unwrap()).sum();
+        assert!((5..=11).contains(&passed), "{}", passed);
+
+        let gate = RateGate::new(Duration::from_secs(3600));
+        assert!(gate.try_pass());
+        assert!(!gate.try_pass());
+    }
+
+    #[test]
+    fn threads_share_the_rate() {
+        let interval = Duration::from_millis(10);
+        let limiter = RateLimiter::new(interval);
+        let start = Instant::now();
+        let run_for = interval * 20;
+        let callers: Vec<_> = (0..8)
+            .map(|_| {
+                let limiter = limiter.clone();
+                thread::spawn(move || {
+                    let mut acquired = 0;
+                    loop {
+                        limiter.acquire();
+                        if start.elapsed() >= run_for {
+                            return acquired;
+                        }
+                        acquired += 1;
+                    }
+                })
+            })
+            .collect();
+        let acquired: usize = callers.into_iter().map(|c| c.join().unwrap()).sum();
+        // One at once and one per interval after, whatever the thread count.
+        assert!((10..=21).contains(&acquired), "{}", acquired);
+    }
+
+    #[test]
+    fn acquire_timeout_takes_no_slot() {
+        let gate = RateGate::new(Duration::from_secs(3600));
+        gate.wait();
+        let waiter = {
+            let gate = gate.clone();
+            thread::spawn(move || gate.acquire_timeout(Duration::from_millis(30)))
+        };
+        let start = Instant::now();
+        assert_eq!(gate.acquire_timeout(Duration::from_millis(10)), Err(Timeout));
+        assert!(start.elapsed() + Duration::from_millis(1) >= Duration::from_millis(10));
+        assert_eq!(waiter.join().unwrap(), Err(Timeout));
+        assert!(!gate.try_pass());
+
+        let gate = RateGate::new(Duration::from_millis(20));
+        gate.wait();
+        assert_eq!(gate.acquire_timeout(Duration::from_millis(5)), Err(Timeout));
+        assert_eq!(gate.acquire_timeout(Duration::from_millis(100)), Ok(()));
+    }
+}
@@ -0,0 +1,140 @@
+//! Callbacks run as a Ticker fires and misses ticks, for instrumentation.
+
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::instrument::Span;
+
+/// Hook is a callback the Ticker runs with a ````T````. One that panics is
+/// turned off, so a broken callback cannot stop the ticks.
+pub(crate) struct Hook<T>(Option<Arc<dyn Fn(T) + Send + Sync>>);
+
+impl<T> Hook<T> {
+    pub(crate) fn new<F: Fn(T) + Send + Sync + 'static>(f: F) -> Self {
+        Hook(Some(Arc::new(f)))
+    }
+
+    pub(crate) fn call(&mut self, arg: T) {
+        if let Some(ref f) = self.0 {
+            if panic::catch_unwind(AssertUnwindSafe(|| f(arg))).is_err() {
+                self.0 = None;
+            }
+        }
+    }
+}
+
+impl<T> Default for Hook<T> {
+    fn default() -> Self {
+        Hook(None)
+    }
+}
+
+impl<T> Clone for Hook<T> {
+    fn clone(&self) -> Self {
+        Hook(self.0.clone())
+    }
+}
+
+impl<T> fmt::Debug for Hook<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(if self.0.is_some() { "Some(..)" } else { "None" })
+    }
+}
+
+/// Hooks are the callbacks set with ````on_tick()````,
+/// ````on_missed_tick()```` and ````on_resume()````.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Hooks {
+    pub(crate) on_tick: Hook<Instant>,
+    pub(crate) on_missed: Hook<u64>,
+    pub(crate) on_resume: Hook<Duration>,
+    /// span records the same events for the ````tracing```` feature.
+    pub(crate) span: Span,
+}
+
+impl Hooks {
+    /// fired runs ````on_tick```` for a tick that fired at ````at````.
+    pub(crate) fn fired(&mut self, at: Instant) {
+        self.span.tick();
+        self.on_tick.call(at)
+    }
+
+    /// missed runs ````on_missed_tick```` for ````n```` ticks, if any.
+    pub(crate) fn missed(&mut self, n: u64) {
+        if n > 0 {
+            self.span.missed(n);
+            self.on_missed.call(n)
+        }
+    }
+
+    /// resumed runs ````on_resume```` for a wake ````gap```` late.
+    pub(crate) fn resumed(&mut self, gap: Duration) {
+        self.span.resumed(gap);
+        self.on_resume.call(gap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MissedTickBehavior, Ticker};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    /// counted runs a Ticker under Skip, sleeping ````work```` after each of
+    /// its first 4 items, and returns how many ticks fired and were missed
+    /// by its hooks, and for how many intervals it ran.
+    fn counted(inline: bool, work: Duration) -> (u64, u64, u64) {
+        let interval = Duration::from_millis(10);
+        let (fired, missed) = (Arc::new(AtomicU64::new(0)), Arc::new(AtomicU64::new(0)));
+        let (f, m) = (fired.clone(), missed.clone());
+        let start = Instant::now();
+        let ticker = Ticker::builder(interval)
+            .inline(inline)
+            .missed_tick_behavior(MissedTickBehavior::Skip)
+            .on_tick(move |_| { f.fetch_add(1, Ordering::SeqCst); })
+            .on_missed_tick(move |n| { m.fetch_add(n, Ordering::SeqCst); })
+            .wrap(0..4);
+        for _ in ticker {
+            thread::sleep(work);
+        }
+        let intervals = (start.elapsed().as_nanos() / interval.as_nanos()) as u64;
+        (fired.load(Ordering::SeqCst), missed.load(Ordering::SeqCst), intervals)
+    }
+
+    #[test]
+    fn hooks_count_ticks_and_misses() {
+        for &inline in &[false, true] {
+            let (fired, missed, _) = counted(inline, Duration::ZERO);
+            assert!((4..=5).contains(&fired), "inline: {}, {} fired", inline, fired);
+            assert_eq!(missed, 0, "inline: {}", inline);
+
+            let (fired, missed, intervals) = counted(inline, Duration::from_millis(35));
+            assert!(fired >= 4, "inline: {}, {} fired", inline, fired);
+            assert!(missed >= 6, "inline: {}, {} missed", inline, missed);
+            // Every deadline that passed either fired or was missed, bar
+            // the ones since the last item was taken.
+            assert!(fired + missed <= intervals + 1, "inline: {}, {} + {} of {}", inline, fired, missed, intervals);
+            assert!(fired + missed + 4 >= intervals, "inline: {}, {} + {} of {}", inline, fired, missed, intervals);
+        }
+    }
+
+    #[test]
+    fn panicking_hook_is_turned_off() {
+        for &inline in &[false, true] {
+            let calls = Arc::new(AtomicU64::new(0));
+            let counter = calls.clone();
+            let ticker = Ticker::new(0..3, Duration::from_millis(5))
+                .inline(inline)
+                .on_tick(move |_| {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    panic!("hook failed");
+                });
+            assert_eq!(ticker.collect::<Vec<_>>(), [0, 1, 2], "inline: {}", inline);
+            assert_eq!(calls.load(Ordering::SeqCst), 1, "inline: {}", inline);
+        }
+    }
+}
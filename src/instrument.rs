@@ -0,0 +1,237 @@
+//! Structured events for the ````tracing```` feature. Without it, everything
+//! here does nothing and compiles away.
+
+use std::time::Duration;
+#[cfg(feature = "tracing")]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "tracing")]
+use std::sync::Arc;
+
+/// WARN_MISSED is how many ticks must be missed at once for the event to be
+/// a warning rather than a debug message.
+#[cfg(feature = "tracing")]
+const WARN_MISSED: u64 = 10;
+
+/// Exit is why a worker thread stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Exit {
+    /// Killed is the Ticker stopping the worker: dropped, stopped, or done.
+    Killed,
+    /// Exhausted is the Ticker stopping the worker because its source ran
+    /// out.
+    #[cfg(feature = "tracing")]
+    Exhausted,
+    /// SenderDropped is every sender of Controls going away.
+    SenderDropped,
+    /// ReceiverDropped is the Ticker no longer receiving ticks.
+    ReceiverDropped,
+}
+
+#[cfg(feature = "tracing")]
+impl Exit {
+    fn reason(self) -> &'static str {
+        match self {
+            Exit::Killed => "killed",
+            Exit::Exhausted => "source exhausted",
+            Exit::SenderDropped => "sender dropped",
+            Exit::ReceiverDropped => "receiver dropped",
+        }
+    }
+}
+
+/// Span is a Ticker's ````ticker```` span, which its events are recorded in,
+/// along with the subscriber that was current where it started.
+#[cfg(feature = "tracing")]
+#[derive(Clone, Debug)]
+pub(crate) struct Span {
+    span: tracing::Span,
+    dispatch: tracing::Dispatch,
+    /// seq counts the ticks fired, for ````tick_seq````.
+    seq: Arc<AtomicU64>,
+}
+
+#[cfg(feature = "tracing")]
+impl Default for Span {
+    fn default() -> Self {
+        Span {
+            span: tracing::Span::none(),
+            dispatch: tracing::Dispatch::none(),
+            seq: Arc::new(AtomicU64::new(0)),
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Span {
+    /// new opens the span for a Ticker starting at ````interval````.
+    pub(crate) fn new(interval: Duration) -> Self {
+        Span {
+            span: tracing::info_span!("ticker", interval_ms = interval.as_millis() as u64),
+            dispatch: tracing::dispatcher::get_default(|dispatch| dispatch.clone()),
+            seq: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// run runs a worker thread's ````work````, recording to the subscriber
+    /// the Ticker started under, and says when it starts and stops.
+    pub(crate) fn run<F: FnOnce() -> Exit>(&self, work: F) {
+        tracing::dispatcher::with_default(&self.dispatch, || {
+            tracing::debug!(parent: &self.span, "worker started");
+            let exit = work();
+            tracing::debug!(parent: &self.span, reason = exit.reason(), "worker stopped");
+        })
+    }
+
+    pub(crate) fn tick(&self) {
+        let tick_seq = self.seq.fetch_add(1, Ordering::Relaxed) + 1;
+        tracing::trace!(parent: &self.span, tick_seq, "tick");
+    }
+
+    pub(crate) fn missed(&self, missed: u64) {
+        if missed >= WARN_MISSED {
+            tracing::warn!(parent: &self.span, missed, "missed ticks");
+        } else {
+            tracing::debug!(parent: &self.span, missed, "missed ticks");
+        }
+    }
+
+    pub(crate) fn resumed(&self, gap: Duration) {
+        tracing::warn!(parent: &self.span, gap_ms = gap.as_millis() as u64, "woke late");
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Span;
+
+#[cfg(not(feature = "tracing"))]
+impl Span {
+    #[inline(always)]
+    pub(crate) fn new(_: Duration) -> Self {
+        Span
+    }
+
+    #[inline(always)]
+    pub(crate) fn run<F: FnOnce() -> Exit>(&self, work: F) {
+        work();
+    }
+
+    #[inline(always)]
+    pub(crate) fn tick(&self) {}
+
+    #[inline(always)]
+    pub(crate) fn missed(&self, _: u64) {}
+
+    #[inline(always)]
+    pub(crate) fn resumed(&self, _: Duration) {}
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use crate::{ManualClock, MissedTickBehavior, Ticker};
+    use std::fmt;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use tracing::field::{Field, Visit};
+    use tracing::span::Attributes;
+    use tracing::{Event, Id, Level, Subscriber};
+    use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+    use tracing_subscriber::Registry;
+
+    /// Record is an event or span's name or message, and its fields.
+    #[derive(Debug, Default)]
+    struct Record {
+        level: Option<Level>,
+        message: String,
+        fields: Vec<(&'static str, String)>,
+    }
+
+    impl Record {
+        fn field(&self, name: &str) -> Option<&str> {
+            self.fields.iter().find(|(n, _)| *n == name).map(|(_, v)| v.as_str())
+        }
+    }
+
+    impl Visit for Record {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            if field.name() == "message" {
+                self.message = format!("{:?}", value);
+            } else {
+                self.fields.push((field.name(), format!("{:?}", value)));
+            }
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.fields.push((field.name(), value.to_string()));
+        }
+    }
+
+    /// Capture keeps every span opened and event recorded.
+    #[derive(Clone, Default)]
+    struct Capture {
+        spans: Arc<Mutex<Vec<Record>>>,
+        events: Arc<Mutex<Vec<Record>>>,
+    }
+
+    impl<S: Subscriber> Layer<S> for Capture {
+        fn on_new_span(&self, attrs: &Attributes, _: &Id, _: Context<S>) {
+            let mut record = Record {
+                message: attrs.metadata().name().to_string(),
+                ..Record::default()
+            };
+            attrs.record(&mut record);
+            self.spans.lock().unwrap().push(record);
+        }
+
+        fn on_event(&self, event: &Event, _: Context<S>) {
+            let mut record = Record {
+                level: Some(*event.metadata().level()),
+                ..Record::default()
+            };
+            event.record(&mut record);
+            self.events.lock().unwrap().push(record);
+        }
+    }
+
+    #[test]
+    fn traces_a_tickers_life() {
+        let capture = Capture::default();
+        let subscriber = Registry::default().with(capture.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            let ticker = Ticker::new(0..3, Duration::from_millis(10));
+            assert_eq!(ticker.collect::<Vec<_>>(), [0, 1, 2]);
+            // The worker stops soon after the source runs out.
+            let start = Instant::now();
+            while capture.events.lock().unwrap().iter().all(|e| e.message != "worker stopped") {
+                assert!(start.elapsed() < Duration::from_secs(5));
+                thread::sleep(Duration::from_millis(1));
+            }
+
+            let clock = ManualClock::new();
+            let mut late = Ticker::new_with_clock(0.., Duration::from_secs(1), clock.clone())
+                .inline(true)
+                .missed_tick_behavior(MissedTickBehavior::Skip);
+            clock.advance(Duration::from_secs(20));
+            assert_eq!(late.next(), Some(0));
+        });
+
+        let spans = capture.spans.lock().unwrap();
+        assert_eq!(spans.len(), 2);
+        assert_eq!((spans[0].message.as_str(), spans[0].field("interval_ms")), ("ticker", Some("10")));
+        assert_eq!(spans[1].field("interval_ms"), Some("1000"));
+
+        let events = capture.events.lock().unwrap();
+        assert_eq!(events[0].message, "worker started");
+        let stopped = events.iter().position(|e| e.message == "worker stopped").unwrap();
+        assert_eq!(events[stopped].field("reason"), Some("source exhausted"));
+        let ticks: Vec<_> = events[1..stopped].iter().filter(|e| e.message == "tick").collect();
+        assert!(ticks.len() >= 3, "{:?}", events);
+        for (i, tick) in ticks.iter().enumerate() {
+            assert_eq!((tick.level, tick.field("tick_seq")), (Some(Level::TRACE), Some((i + 1).to_string().as_str())));
+        }
+
+        let rest: Vec<_> = events[stopped + 1..].iter().map(|e| (e.level, e.message.as_str(), e.field("missed"))).collect();
+        assert_eq!(rest, [(Some(Level::WARN), "missed ticks", Some("19")), (Some(Level::TRACE), "tick", None)]);
+    }
+}
@@ -0,0 +1,147 @@
+//! Sampling the latest value of a live source once per tick.
+
+use std::iter::{FusedIterator, Repeat};
+use std::sync::mpsc::TryRecvError;
+
+use crate::{Ticker, TickerHandle};
+
+/// Latest yields the most recent value of a live source once per tick,
+/// discarding any older values that arrived since the last tick. Create one
+/// with ````Ticker::sample_latest````.
+///
+/// The source is polled with a closure that must not block: it returns
+/// ````Err(TryRecvError::Empty)```` when nothing more is available right now,
+/// and ````Err(TryRecvError::Disconnected)```` once it has ended, just like
+/// ````Receiver::try_recv````. A tick that finds nothing new yields nothing,
+/// and Latest waits for the next one. When the source ends, the tick that
+/// sees it still yields the last value it drained, and iteration ends after
+/// that.
+pub struct Latest<F> {
+    ticker: Ticker<Repeat<()>>,
+    poll: F,
+    done: bool,
+}
+
+impl<F> Latest<F> {
+    pub(crate) fn new(ticker: Ticker<Repeat<()>>, poll: F) -> Self {
+        Latest {
+            ticker,
+            poll,
+            done: false,
+        }
+    }
+
+    /// handle returns a TickerHandle to adjust this Latest from another
+    /// thread.
+    pub fn handle(&self) -> TickerHandle {
+        self.ticker.handle()
+    }
+}
+
+impl<F, T> Iterator for Latest<F>
+where
+    F: FnMut() -> Result<T, TryRecvError>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        while !self.done {
+            self.ticker.next_tick()?;
+            let mut latest = None;
+            loop {
+                match (self.poll)() {
+                    Ok(value) => latest = Some(value),
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        self.done = true;
+                        break;
+                    }
+                }
+            }
+            if latest.is_some() {
+                return latest;
+            }
+        }
+        None
+    }
+}
+
+impl<F, T> FusedIterator for Latest<F> where F: FnMut() -> Result<T, TryRecvError> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Clock, ManualClock};
+    use std::iter;
+    use std::sync::mpsc::channel;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn skips_stale_values() {
+        let (send, recv) = channel();
+        thread::spawn(move || {
+            for i in 0.. {
+                if send.send(i).is_err() {
+                    return;
+                }
+                thread::sleep(Duration::from_millis(1));
+            }
+        });
+        let samples: Vec<i32> = Ticker::sample_latest(move || recv.try_recv(), Duration::from_millis(20))
+            .take(3)
+            .collect();
+        for pair in samples.windows(2) {
+            assert!(pair[1] > pair[0] + 1, "{:?}", samples);
+        }
+    }
+
+    #[test]
+    fn flushes_last_value_when_source_ends() {
+        let (send, recv) = channel();
+        for i in 0..10 {
+            send.send(i).unwrap();
+        }
+        drop(send);
+        let samples: Vec<i32> = Ticker::sample_latest(move || recv.try_recv(), Duration::from_millis(5)).collect();
+        assert_eq!(samples, [9]);
+    }
+
+    #[test]
+    fn idle_ticks_yield_nothing() {
+        let interval = Duration::from_millis(10);
+        let clock = ManualClock::new();
+        let start = clock.now();
+        let (send, recv) = channel();
+        // empties says each time a tick found nothing new.
+        let (empty, empties) = channel();
+        let ticker = Ticker::new_with_clock(iter::repeat(()), interval, clock.clone()).inline(true);
+        let sampler = Latest::new(ticker, move || {
+            let polled = recv.try_recv();
+            if polled == Err(TryRecvError::Empty) {
+                let _ = empty.send(());
+            }
+            polled
+        });
+        let (out, samples) = channel();
+        let at = clock.clone();
+        thread::spawn(move || {
+            for value in sampler {
+                let _ = out.send((value, at.now()));
+            }
+        });
+
+        for _ in 0..2 {
+            clock.advance(interval);
+            empties.recv().unwrap();
+        }
+        send.send(1).unwrap();
+        assert!(samples.recv_timeout(Duration::from_millis(20)).is_err());
+        // The value sent after the second tick is held for the third.
+        clock.advance(interval);
+        assert_eq!(samples.recv().unwrap(), (1, start + interval * 3));
+        drop(send);
+        clock.advance(interval);
+        assert!(samples.recv().is_err());
+    }
+}
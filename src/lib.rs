@@ -2,23 +2,488 @@
 //!
 //! E.g. print 0-9, one number per second:
 //!
-//! ````
+#![cfg_attr(feature = "std", doc = "````no_run")]
+#![cfg_attr(not(feature = "std"), doc = "````ignore")]
+//! # use ticker::Ticker;
+//! # use std::time::Duration;
 //! let ticker = Ticker::new((0..10), Duration::from_secs(1));
 //! for i in ticker {
 //!     println!("{:?}", i)
 //! }
 //! ````
+//!
+//! Or rate limit the middle of an adapter chain with ````.ticked()````:
+//!
+#![cfg_attr(feature = "std", doc = "````no_run")]
+#![cfg_attr(not(feature = "std"), doc = "````ignore")]
+//! # use std::time::Duration;
+//! use ticker::TickedIterator;
+//!
+//! for i in (0..).map(|i| i * 2).ticked(Duration::from_secs(1)).take(10) {
+//!     println!("{:?}", i)
+//! }
+//! ````
+//!
+//! A Ticker waits for each tick before pulling the item it returns, so an
+//! item from a live source is fresh when it comes back, and once the source
+//! is exhausted the last ````.next()```` returns None after one more tick.
+//! With ````pull_first(true)```` it pulls each item before waiting instead,
+//! and returns None without waiting. Every other rate limiter here, from a
+//! Weighted to a Paced, always pulls first. Either way, once the source
+//! returns None it is never polled again.
+//!
+//! # Pacing on the consumer's thread
+//!
+//! Weighted, Adaptive, Windowed, Classified, Ramp, ThrottleWhile, Merge and
+//! Scheduled have no worker: each waits inside ````.next()````, on the consumer's
+//! thread. Each waits on the system clock unless given another with
+//! ````.clock()````, which starts its schedule over on the new Clock. Time
+//! the consumer spends away between items counts towards the next wait, but
+//! is never banked: a consumer that falls behind gets its next item at once,
+//! and the schedule carries on from then rather than catching up with a
+//! burst.
+//!
+//! Without the default ````std```` feature only Paced is available, which
+//! paces an Iterator on any Sleeper using nothing but ````core````, for
+//! targets without threads or an allocator.
+
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+
+#[cfg(feature = "std")]
+mod adaptive;
+#[cfg(feature = "std")]
+mod bandwidth;
+#[cfg(feature = "std")]
+mod batches;
+#[cfg(feature = "std")]
+mod builder;
+#[cfg(feature = "std")]
+mod classified;
+#[cfg(feature = "std")]
+mod clock;
+#[cfg(feature = "cron")]
+mod cron;
+#[cfg(feature = "std")]
+mod gate;
+#[cfg(feature = "std")]
+mod hooks;
+#[cfg(feature = "std")]
+mod instrument;
+#[cfg(feature = "std")]
+mod latest;
+#[cfg(feature = "std")]
+mod merge;
+mod pace;
+#[cfg(feature = "std")]
+mod pace_if;
+#[cfg(feature = "std")]
+mod pacer;
+#[cfg(feature = "std")]
+mod ramp;
+#[cfg(feature = "stream")]
+mod stream;
+#[cfg(feature = "crossbeam")]
+mod ticks;
+#[cfg(feature = "tokio")]
+mod tokio_ticker;
+#[cfg(feature = "std")]
+mod retry;
+#[cfg(feature = "std")]
+mod scheduled;
+#[cfg(feature = "std")]
+mod sender;
+#[cfg(feature = "std")]
+mod shared;
+#[cfg(feature = "std")]
+mod stats;
+#[cfg(feature = "std")]
+mod throttle_while;
+#[cfg(feature = "std")]
+mod tick_source;
+#[cfg(feature = "std")]
+mod wait;
+#[cfg(feature = "std")]
+mod weighted;
+#[cfg(feature = "std")]
+mod windowed;
+
+#[cfg(feature = "std")]
+pub use crate::adaptive::Adaptive;
+#[cfg(feature = "std")]
+pub use crate::bandwidth::{ThrottledReader, ThrottledWriter};
+#[cfg(feature = "std")]
+pub use crate::batches::Batches;
+#[cfg(feature = "std")]
+pub use crate::builder::TickerBuilder;
+#[cfg(feature = "std")]
+pub use crate::classified::Classified;
+#[cfg(feature = "std")]
+pub use crate::clock::{Clock, SystemClock};
+#[cfg(feature = "cron")]
+pub use crate::cron::{Cron, CronError, CronSchedule};
+#[cfg(feature = "std")]
+pub use crate::gate::{RateGate, RateLimiter};
+#[cfg(feature = "std")]
+pub use crate::latest::Latest;
+#[cfg(feature = "std")]
+pub use crate::merge::{Merge, Merged};
+pub use crate::pace::{Paced, Sleeper};
+#[cfg(feature = "std")]
+pub use crate::pace_if::{OnlyOk, OnlySome, PaceIf};
+#[cfg(feature = "std")]
+pub use crate::pacer::{FixedInterval, Gcra, Pacer, TokenBucket};
+#[cfg(feature = "std")]
+pub use crate::ramp::{Easing, Ramp};
+#[cfg(all(feature = "std", any(test, feature = "test-util")))]
+pub use crate::clock::ManualClock;
+#[cfg(feature = "stream")]
+pub use crate::stream::{AsyncTimer, TickStream};
+#[cfg(feature = "tokio")]
+pub use crate::tokio_ticker::TokioTicker;
+#[cfg(feature = "std")]
+pub use crate::retry::{retry, retry_if, RetryError, RetryPolicy};
+#[cfg(feature = "std")]
+pub use crate::scheduled::{Backoff, Scheduled};
+#[cfg(feature = "std")]
+pub use crate::sender::{ThrottledSender, TrySendError};
+#[cfg(feature = "std")]
+pub use crate::shared::{SharedIter, SharedTicker};
+#[cfg(feature = "std")]
+pub use crate::stats::TickerStats;
+#[cfg(feature = "std")]
+pub use crate::throttle_while::ThrottleWhile;
+#[cfg(feature = "std")]
+pub use crate::tick_source::{FromTicks, TickSource};
+#[cfg(feature = "std")]
+pub use crate::weighted::Weighted;
+#[cfg(feature = "std")]
+pub use crate::windowed::Windowed;
+
+#[cfg(feature = "std")]
+use std::{
+    collections::HashMap,
+    collections::hash_map::RandomState,
+    error::Error,
+    fmt,
+    hash::{BuildHasher, Hash, Hasher},
+    hint,
+    io,
+    iter::{self, FusedIterator},
+    ops::ControlFlow,
+    option,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+    thread,
+    sync::{Arc, Mutex},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    sync::mpsc::{self, Receiver, Sender, channel, RecvTimeoutError, TryRecvError},
+};
+
+#[cfg(feature = "std")]
+use crate::hooks::{Hook, Hooks};
+#[cfg(feature = "std")]
+use crate::instrument::{Exit, Span};
+#[cfg(feature = "std")]
+use crate::pacer::SharedPacer;
+#[cfg(feature = "std")]
+use crate::stats::Stats;
+#[cfg(feature = "std")]
+use crate::wait::Due;
+
+/// MissedTickBehavior decides what a Ticker does with ticks that come due while
+/// the consumer is still busy with a previous item.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissedTickBehavior {
+    /// Burst queues missed ticks, up to the Ticker's ````max_pending````, so a
+    /// late consumer gets items back to back until it has caught up with the
+    /// original schedule. This is the default.
+    #[default]
+    Burst,
+    /// Delay returns the late item right away and schedules the following one a
+    /// full interval after the consumer caught up.
+    Delay,
+    /// Skip returns the late item right away and drops any other missed ticks,
+    /// resuming on the original schedule.
+    Skip,
+}
+
+/// Pacing decides what a Ticker's interval is measured between.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Pacing {
+    /// Fixed ticks like a metronome, once every interval however long the
+    /// consumer takes over each item. A consumer slower than the interval
+    /// gets its next item as soon as it asks, and ticks it missed are handled
+    /// by the Ticker's ````MissedTickBehavior````, so gaps between items are
+    /// about the longer of the two, with bursts after a stall. This is the
+    /// default.
+    #[default]
+    Fixed,
+    /// MinGap waits a full interval each time the consumer comes back for an
+    /// item, so at least ````interval```` always passes between handing one
+    /// item over and the next: a loop body that takes ````body```` gives gaps
+    /// of ````body + interval````. Ticks never queue up, so the
+    /// ````MissedTickBehavior```` does not apply, and the Ticker is always
+    /// inline.
+    MinGap,
+}
+
+/// TickEdge decides which side of each interval a Ticker's items fall on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TickEdge {
+    /// Leading waits out an interval before each item, so the first item
+    /// comes one ````interval```` after the Ticker is created. This is the
+    /// default.
+    #[default]
+    Leading,
+    /// Trailing returns each item first and waits out the interval after it,
+    /// as a cool-down: the first item comes as soon as it is asked for, the
+    /// schedule starts from then, and nothing waits after the last item.
+    /// It takes the place of ````first_tick_immediately````,
+    /// ````initial_delay````, ````aligned```` and ````start_full````.
+    Trailing,
+}
+
+/// Control is sent to whatever is pacing a Ticker: its worker thread, or the
+/// Ticker itself when inline.
+#[cfg(feature = "std")]
+enum Control {
+    /// Kill stops the worker, and ends an inline Ticker's wait.
+    Kill,
+    /// Delay reschedules the next tick one interval after the given Instant,
+    /// which is when a late consumer caught up.
+    Delay(Instant),
+    /// SetInterval changes the interval, starting with the pending tick.
+    SetInterval(Duration),
+    /// Pause stops ticking until the next Resume.
+    Pause,
+    /// Resume restarts ticking, one interval after the given Instant.
+    Resume(Instant),
+    /// Tick releases one item now, from ````TickHandle::tick````.
+    Tick,
+    /// Hangup says the last TickHandle is gone, which ends a Ticker that only
+    /// ticks by hand once the ticks already sent are taken.
+    Hangup,
+    /// Taken says a tick from a worker asking a Pacer was taken, so there is
+    /// room to ask for another.
+    Taken,
+}
+
+/// Shared is the state a Ticker, its handles and its worker thread all see.
+#[cfg(feature = "std")]
+struct Shared {
+    /// clock is the time source the Ticker is scheduled against.
+    clock: Arc<dyn Clock>,
+    /// pending counts ticks the worker has sent in the current epoch that have
+    /// not been received yet.
+    pending: AtomicUsize,
+    /// epoch is bumped by every pause and resume, so it is odd while paused.
+    /// Ticks sent in an earlier epoch are stale and dropped when received.
+    epoch: AtomicUsize,
+    /// stopped is set by TickerHandle::stop, after which the Ticker only
+    /// returns None.
+    stopped: AtomicBool,
+    /// unlimited is set while a threaded Ticker's interval is zero, when its
+    /// items are let through without waiting for ticks.
+    unlimited: AtomicBool,
+    /// interval is the last interval given to TickerHandle::set_interval, if
+    /// any, which the worker has taken over from the Ticker's Config.
+    interval: Mutex<Option<Duration>>,
+    /// exhausted is set when the source runs out, so the worker can say that
+    /// is why it was stopped.
+    #[cfg(feature = "tracing")]
+    exhausted: AtomicBool,
+    stats: Stats,
+}
+
+#[cfg(feature = "std")]
+impl Default for Shared {
+    fn default() -> Self {
+        Shared::with_clock(Arc::new(SystemClock))
+    }
+}
+
+#[cfg(feature = "std")]
+impl Shared {
+    fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        Shared {
+            stats: Stats::new(clock.now()),
+            clock,
+            pending: AtomicUsize::new(0),
+            epoch: AtomicUsize::new(0),
+            stopped: AtomicBool::new(false),
+            unlimited: AtomicBool::new(false),
+            interval: Mutex::new(None),
+            #[cfg(feature = "tracing")]
+            exhausted: AtomicBool::new(false),
+        }
+    }
+
+    fn paused(epoch: usize) -> bool {
+        epoch % 2 == 1
+    }
+}
+
+/// Cost is what an item costs a Ticker's Pacer.
+#[cfg(feature = "std")]
+type Cost<T> = Arc<dyn Fn(&T) -> u32 + Send + Sync>;
+
+/// Tick is sent from a worker thread to its Ticker.
+#[cfg(feature = "std")]
+struct Tick {
+    fired: Instant,
+    epoch: usize,
+}
+
+/// rebase moves a deadline that was ````old```` after the previous tick to
+/// ````new```` after it, or to never if that is past what an Instant can
+/// hold.
+#[cfg(feature = "std")]
+fn rebase(deadline: Instant, old: Duration, new: Duration) -> Option<Instant> {
+    match deadline.checked_sub(old) {
+        Some(previous) => previous.checked_add(new),
+        None => Some(deadline),
+    }
+}
+
+/// TickerHandle adjusts a Ticker while it runs, from any thread. Get one with
+/// ````Ticker::handle()```` or ````TickIter::handle()````; clones control the
+/// same Ticker.
+///
+/// ````no_run
+/// # use ticker::Ticker;
+/// # use std::time::Duration;
+/// # use std::thread;
+/// # fn poll() {}
+/// let ticker = Ticker::new(0.., Duration::from_secs(1));
+/// let handle = ticker.handle();
+/// thread::spawn(move || {
+///     thread::sleep(Duration::from_secs(60));
+///     handle.set_interval(Duration::from_secs(10));
+/// });
+/// for _ in ticker {
+///     poll()
+/// }
+/// ````
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct TickerHandle {
+    ctrl: Sender<Control>,
+    shared: Arc<Shared>,
+}
+
+#[cfg(feature = "std")]
+impl TickerHandle {
+    /// set_interval changes how often the Ticker ticks. The pending tick is
+    /// moved to the new interval after the previous one, which is right away if
+    /// that has already passed. Setting the interval the Ticker already has
+    /// does nothing, and leaves the pending tick where it was. A zero interval
+    /// lifts the limit until the interval is set again.
+    ///
+    /// An interval too long to represent as an Instant, such as
+    /// ````Duration::MAX````, stops the ticks until a shorter one is set,
+    /// which is then measured from when it is set.
+    pub fn set_interval(&self, interval: Duration) {
+        self.shared.unlimited.store(interval == Duration::ZERO, Ordering::SeqCst);
+        *self.shared.interval.lock().unwrap_or_else(|e| e.into_inner()) = Some(interval);
+        let _ = self.ctrl.send(Control::SetInterval(interval));
+    }
+
+    /// pause stops the Ticker from ticking, so ````.next()```` blocks until
+    /// ````resume()```` is called. No ticks accumulate while paused, and a tick
+    /// that was already waiting is discarded. Pausing a paused Ticker does
+    /// nothing.
+    pub fn pause(&self) {
+        let paused = self.shared
+            .epoch
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst,
+                          |epoch| if Shared::paused(epoch) { None } else { Some(epoch + 1) });
+        if paused.is_ok() {
+            let _ = self.ctrl.send(Control::Pause);
+        }
+    }
+
+    /// resume restarts a paused Ticker. The next tick comes one full interval
+    /// after ````resume()```` is called. Resuming a running Ticker does nothing.
+    pub fn resume(&self) {
+        let resumed = self.shared
+            .epoch
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst,
+                          |epoch| if Shared::paused(epoch) { Some(epoch + 1) } else { None });
+        if resumed.is_ok() {
+            let _ = self.ctrl.send(Control::Resume(self.shared.clock.now()));
+        }
+    }
+
+    /// stats returns what the Ticker has done so far. See TickerStats.
+    pub fn stats(&self) -> TickerStats {
+        self.shared.stats.snapshot()
+    }
+
+    /// stop ends iteration: ````.next()```` returns None from now on, even if
+    /// the source has more items. A ````.next()```` that is already waiting for
+    /// a tick returns None right away, without waiting out the interval.
+    pub fn stop(&self) {
+        self.shared.stopped.store(true, Ordering::SeqCst);
+        let _ = self.ctrl.send(Control::Kill);
+    }
+}
+
+/// TickHandle ticks a Ticker by hand: each ````tick()```` releases one item
+/// right away, whatever its timer says. Get one with ````Ticker::manual````,
+/// for a Ticker with no timer at all, or ````Ticker::manual_ticks```` to tick
+/// by hand as well as on time. Clones tick the same Ticker, from any thread.
+///
+/// ````no_run
+/// # use ticker::Ticker;
+/// # use std::thread;
+/// # fn handle(_: i32) {}
+/// let (events, ticks) = Ticker::manual(0..);
+/// thread::spawn(move || {
+///     for event in events {
+///         handle(event)
+///     }
+/// });
+/// ticks.tick();
+/// ticks.tick();
+/// ````
+///
+/// Ticks sent while the consumer is busy are saved up, so every call releases
+/// exactly one item. Once every TickHandle of a Ticker from
+/// ````Ticker::manual```` has been dropped, nothing can tick it again, so it
+/// ends as soon as the ticks already sent are taken.
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct TickHandle {
+    sender: Arc<TickSender>,
+}
 
-use std::time::Duration;
-use std::thread;
-use std::sync::mpsc::{Receiver, Sender, channel, RecvTimeoutError};
+/// TickSender is what all clones of a TickHandle share, to hang up when the
+/// last is dropped.
+#[cfg(feature = "std")]
+struct TickSender(Sender<Control>);
+
+#[cfg(feature = "std")]
+impl TickHandle {
+    /// tick releases the Ticker's next item now.
+    pub fn tick(&self) {
+        let _ = self.sender.0.send(Control::Tick);
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for TickSender {
+    fn drop(&mut self) {
+        let _ = self.0.send(Control::Hangup);
+    }
+}
 
 /// Ticker rate limits an Iterator. A ticking Iterator unblocks at most once per
 /// interval.
 ///
 /// Print 0-9, one number per second:
 ///
-/// ````
+/// ````no_run
+/// # use ticker::Ticker;
+/// # use std::time::Duration;
 /// let ticker = Ticker::new((0..10), Duration::from_secs(1));
 /// for i in ticker {
 ///     println!("{:?}", i)
@@ -27,78 +492,4603 @@ use std::sync::mpsc::{Receiver, Sender, channel, RecvTimeoutError};
 ///
 /// Run some function every second infinitely:
 ///
+/// ````no_run
+/// # use ticker::Ticker;
+/// # use std::ops::ControlFlow;
+/// # use std::time::Duration;
+/// # fn somefunc() {}
+/// Ticker::run_every(Duration::from_secs(1), || {
+///     somefunc();
+///     ControlFlow::Continue(())
+/// });
 /// ````
-/// for _ in Ticker::new((0..), Duration::from_secs(1)) {
-///     somefunc()
+///
+/// A Ticker is itself an Iterator. Its schedule starts when it is created, and
+/// by default the first item is returned one ````interval```` after that.
+/// Ticks are scheduled at absolute deadlines, the nth one at
+/// ````start + n * interval````, so the schedule does not drift however long
+/// the Ticker runs; this is ````Pacing::Fixed````, and ````.pacing()```` can
+/// measure each interval from when the consumer comes back instead. Settings
+/// take effect at the first call to ````.next()````.
+///
+/// Iterate ````&mut ticker```` to take a few items and keep the Ticker for
+/// later; its schedule carries on across the break:
+///
+/// ````no_run
+/// # use ticker::Ticker;
+/// # use std::time::Duration;
+/// let mut ticker = Ticker::new(0.., Duration::from_secs(1));
+/// for i in &mut ticker {
+///     if i == 2 {
+///         break;
+///     }
 /// }
+/// assert_eq!(ticker.next(), Some(3));
 /// ````
-pub struct Ticker<I: Iterator> {
+///
+/// Each interval is a FixedInterval's; ````Ticker::with_pacer```` makes a
+/// Ticker that leaves when each item is due to another Pacer instead. Such a
+/// Ticker is the same in every other way, and the settings about its
+/// schedule are the Pacer's to decide: see ````with_pacer()````.
+///
+/// A Ticker pulls its items and ends as the crate docs describe, and its
+/// worker thread exits once the source first returns None rather than when
+/// the Ticker is dropped. If the worker thread exits unexpectedly, iteration
+/// ends the same way rather than panicking.
+///
+/// A Ticker is Send whenever its source and items are, so it can be created
+/// on one thread and iterated on another, even once it has started; its
+/// worker keeps the schedule, and stops when the Ticker is dropped, wherever
+/// that happens. It is not Sync, since only one thread at a time can wait for
+/// its ticks. To share one rate among threads use a SharedTicker, and to
+/// adjust a Ticker from another thread use its TickerHandle.
+///
+/// On targets without threads, such as ````wasm32-unknown-unknown````, every
+/// Ticker is inline whatever its settings, and waits by spinning on its Clock
+/// instead of sleeping; the API is the same everywhere. Only ````split()````
+/// and ````ticks()````, which hand ticks to a thread of their own, cannot
+/// work there.
+#[cfg(feature = "std")]
+pub struct Ticker<I: Iterator, P = FixedInterval> {
     src: I,
-    recv: Receiver<()>,
-    kill: Sender<()>,
+    done: bool,
+    config: Config,
+    /// pacer says when each tick is due, unless it has a fixed interval,
+    /// which the backend keeps to its config's interval by itself.
+    pacer: Arc<Mutex<P>>,
+    /// cost is what each item costs the pacer, if not 1.
+    cost: Option<Cost<I::Item>>,
+    ctrl: Sender<Control>,
+    /// ctrl_recv is handed to the backend when ticking starts.
+    ctrl_recv: Option<Receiver<Control>>,
+    shared: Arc<Shared>,
+    /// start is when the schedule started; the backend pacing it is only
+    /// created by the first ````.next()````.
+    start: Instant,
+    backend: Option<Backend>,
+    /// item is an item taken from the source by ````peek()````, or for a tick
+    /// that a bounded wait gave up on; it goes with the next tick.
+    item: Option<I::Item>,
+    /// ticked counts the ticks items have been returned for.
+    ticked: usize,
+    /// burst counts the items ````initial_burst()```` let through unpaced.
+    burst: usize,
+    /// burst_end is when the last of them was, which the schedule then
+    /// starts from instead of ````start````.
+    burst_end: Option<Instant>,
+}
+
+#[cfg(feature = "std")]
+impl Ticker<iter::Repeat<()>> {
+    /// builder starts a TickerBuilder, to configure Tickers ticking every
+    /// ````interval```` once and then make any number of them.
+    pub fn builder(interval: Duration) -> TickerBuilder {
+        TickerBuilder::new(interval)
+    }
+
+    /// every creates a heartbeat with no source: an endless Iterator yielding
+    /// the Instant each tick fired, once every ````interval````.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// for t in Ticker::every(Duration::from_secs(1)).take(3) {
+    ///     println!("tick at {:?}", t)
+    /// }
+    /// ````
+    pub fn every(interval: Duration) -> Every {
+        Every {
+            ticker: Ticker::new(iter::repeat(()), interval),
+        }
+    }
+
+    /// run_every calls ````f```` once every ````interval````, starting one
+    /// ````interval```` from now, until it returns ````ControlFlow::Break````.
+    /// Each call is made once its tick has fired.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::ops::ControlFlow;
+    /// # use std::time::Duration;
+    /// # fn healthy() -> bool { true }
+    /// Ticker::run_every(Duration::from_secs(10), || {
+    ///     if healthy() {
+    ///         ControlFlow::Continue(())
+    ///     } else {
+    ///         ControlFlow::Break(())
+    ///     }
+    /// });
+    /// ````
+    pub fn run_every<F>(interval: Duration, mut f: F)
+    where
+        F: FnMut() -> ControlFlow<()>,
+    {
+        for _ in Ticker::every(interval) {
+            if f().is_break() {
+                return;
+            }
+        }
+    }
+
+    /// merge rate limits two sources, each at its own interval, as one
+    /// Iterator yielding whichever side ticks first. See Merge.
+    ///
+    /// A side whose next tick is too far off to represent as an Instant, such
+    /// as one with an interval of ````Duration::MAX````, never ticks. The
+    /// other side carries on alone, and once it ends ````.next()```` blocks
+    /// forever.
+    ///
+    /// ````no_run
+    /// # use ticker::{Merged, Ticker};
+    /// # use std::iter;
+    /// # use std::time::Duration;
+    /// # fn read_metrics() -> u64 { 0 }
+    /// # fn read_config() -> String { String::new() }
+    /// let metrics = (iter::repeat_with(read_metrics), Duration::from_millis(100));
+    /// let config = (iter::repeat_with(read_config), Duration::from_secs(30));
+    /// for polled in Ticker::merge(metrics, config) {
+    ///     match polled {
+    ///         Merged::Left(metric) => println!("metric {}", metric),
+    ///         Merged::Right(config) => println!("config {}", config),
+    ///     }
+    /// }
+    /// ````
+    pub fn merge<A, B>(left: (A, Duration), right: (B, Duration)) -> Merge<A, B>
+    where
+        A: Iterator,
+        B: Iterator,
+    {
+        Merge::new(left, right)
+    }
+
+    /// shared creates a SharedTicker, one tick every ````interval```` shared
+    /// by every source it wraps, on any number of threads. Its ticks are
+    /// paced inline by whichever consumer's turn it is, so no worker thread
+    /// is started.
+    pub fn shared(interval: Duration) -> SharedTicker {
+        SharedTicker::new(Ticker::new(iter::repeat(()), interval).inline(true))
+    }
+
+    /// sample_latest rate limits a live source by sampling it: once every
+    /// ````interval```` it drains ````poll```` without blocking and yields only
+    /// the most recent value, dropping the rest. See Latest.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::sync::mpsc::channel;
+    /// # use std::time::Duration;
+    /// # fn display(_: f64) {}
+    /// let (readings, recv) = channel::<f64>();
+    /// # drop(readings);
+    /// for reading in Ticker::sample_latest(move || recv.try_recv(), Duration::from_secs(1)) {
+    ///     display(reading)
+    /// }
+    /// ````
+    pub fn sample_latest<F, T>(poll: F, interval: Duration) -> Latest<F>
+    where
+        F: FnMut() -> Result<T, TryRecvError>,
+    {
+        Latest::new(Ticker::new(iter::repeat(()), interval), poll)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T, F> Ticker<iter::FromFn<F>>
+where
+    F: FnMut() -> Option<T>,
+{
+    /// from_fn creates a Ticker over the values ````f```` returns, at most one
+    /// every ````interval````, ending when it returns None. ````f```` is called
+    /// once each tick has come, so every value is fresh when it is yielded;
+    /// to run something at each tick without yielding, use ````run_every()````.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn read_sensor() -> Option<f64> { None }
+    /// for reading in Ticker::from_fn(Duration::from_secs(1), read_sensor) {
+    ///     println!("{}", reading)
+    /// }
+    /// ````
+    pub fn from_fn(interval: Duration, f: F) -> Self {
+        Ticker::new(iter::from_fn(f), interval)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Ticker<mpsc::IntoIter<T>> {
+    /// from_receiver creates a Ticker that returns at most one message from
+    /// ````recv```` every ````interval````. Each ````.next()```` returns once
+    /// both a message has arrived and its tick has fired, whichever is later:
+    /// each message is received before waiting for its tick, as under
+    /// ````pull_first(true)````. Ticks are not saved up while the channel is
+    /// empty: the Ticker uses
+    /// ````MissedTickBehavior::Delay````, so consecutive messages are always at
+    /// least ````interval```` apart. Iteration ends as soon as every Sender is
+    /// gone and the channel is drained.
+    ///
+    /// A ````.next()```` waiting for a message is not interrupted by
+    /// ````TickerHandle::stop()````; the stop takes effect once a message
+    /// arrives or the channel disconnects.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::sync::mpsc::channel;
+    /// # use std::time::Duration;
+    /// # fn process(_: String) {}
+    /// let (send, recv) = channel::<String>();
+    /// # drop(send);
+    /// for job in Ticker::from_receiver(recv, Duration::from_millis(100)) {
+    ///     process(job)
+    /// }
+    /// ````
+    pub fn from_receiver(recv: Receiver<T>, interval: Duration) -> Self {
+        Ticker::new(recv.into_iter(), interval)
+            .missed_tick_behavior(MissedTickBehavior::Delay)
+            .pull_first(true)
+    }
 }
 
+#[cfg(feature = "std")]
 impl<I: Iterator> Ticker<I> {
     /// new creates a Ticker which will rate limit returns from ````src````,
     /// returning from ````.next()```` at most once every ````interval````.
+    /// A zero interval is no limit at all: items pass straight through, and
+    /// no worker thread is started.
+    ///
+    /// A tick too far off to represent as an Instant never comes, so with an
+    /// interval such as ````Duration::MAX````, ````.next()```` blocks forever
+    /// for every tick but an immediate first one.
     pub fn new(src: I, interval: Duration) -> Self {
-        let (send, recv) = channel::<()>();
-        let (kill, kill_recv) = channel::<()>();
-        thread::spawn(move || loop {
-                          match kill_recv.recv_timeout(interval) {
-                              Err(RecvTimeoutError::Timeout) => {
-                                  if let Err(_) = send.send(()) {
-                                      return;
-                                  }
-                              }
-                              _ => return,
-                          }
-                      });
+        Ticker::with_pacer(src, FixedInterval::new(interval))
+    }
+
+    /// manual creates a Ticker with no timer, which releases an item from
+    /// ````src```` each time the returned TickHandle is ticked. It is inline,
+    /// and ends once every TickHandle is dropped. See TickHandle.
+    pub fn manual(src: I) -> (TickIter<I>, TickHandle) {
+        let mut ticker = Ticker::new(src, Duration::ZERO);
+        ticker.config.manual = Manual::Only;
+        ticker.tick_handle()
+    }
+
+    /// try_new creates a Ticker like ````new()````, but starts its worker
+    /// thread right away so that failing to spawn it is an error rather than
+    /// a panic in the first ````.next()````. Since settings take effect when
+    /// ticking starts, configure a Ticker that must be created this way with
+    /// ````TickerBuilder::try_wrap```` instead; setters called on it later
+    /// have no effect. Inline Tickers, zero intervals and targets without
+    /// threads spawn no thread, and never fail.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::io;
+    /// # use std::time::Duration;
+    /// # fn main() -> io::Result<()> {
+    /// for i in Ticker::try_new((0..10), Duration::from_secs(1))? {
+    ///     println!("{}", i)
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ````
+    pub fn try_new(src: I, interval: Duration) -> io::Result<Self> {
+        let mut ticker = Ticker::new(src, interval);
+        ticker.start_backend()?;
+        Ok(ticker)
+    }
+
+    /// new_with_clock creates a Ticker like ````new()```` that is scheduled
+    /// against ````clock```` instead of the system clock. With a ManualClock
+    /// (behind the ````test-util```` feature) tests can advance time
+    /// explicitly rather than sleeping.
+    pub fn new_with_clock<C: Clock + 'static>(src: I, interval: Duration, clock: C) -> Self {
+        Ticker::new(src, interval).clock(clock)
+    }
+
+    /// with_delay creates a Ticker whose first tick comes ````initial````
+    /// after it is created, and every ````interval```` after that. See
+    /// ````initial_delay()````.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn check_health(_: i32) {}
+    /// let checks = Ticker::with_delay((0..), Duration::from_secs(5), Duration::from_secs(1));
+    /// for check in checks {
+    ///     check_health(check)
+    /// }
+    /// ````
+    pub fn with_delay(src: I, initial: Duration, interval: Duration) -> Self {
+        Ticker::new(src, interval).initial_delay(initial)
+    }
+
+    /// limited creates a Ticker that returns at most ````max_ticks```` items,
+    /// one every ````interval````. See ````max_ticks()````.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn probe(_: i32) {}
+    /// for attempt in Ticker::limited((0..), Duration::from_secs(1), 5) {
+    ///     probe(attempt)
+    /// }
+    /// ````
+    pub fn limited(src: I, interval: Duration, max_ticks: usize) -> Self {
+        Ticker::new(src, interval).max_ticks(max_ticks)
+    }
+
+    /// new_precise creates an inline Ticker like ````new()```` that spins
+    /// through the last ````DEFAULT_SPIN_THRESHOLD```` of each wait, for
+    /// intervals too short for sleeping to keep up with. See
+    /// ````spin_threshold()````.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn sample(_: i32) {}
+    /// for i in Ticker::new_precise((0..), Duration::from_micros(500)) {
+    ///     sample(i)
+    /// }
+    /// ````
+    pub fn new_precise(src: I, interval: Duration) -> Self {
+        Ticker::new(src, interval).inline(true).spin_threshold(DEFAULT_SPIN_THRESHOLD)
+    }
+
+    /// at_rate creates a Ticker which returns at most ````per_second```` items
+    /// each second. Fractional rates are fine; the interval is computed to the
+    /// nanosecond, and never rounds down to zero however high the rate.
+    ///
+    /// Panics if ````per_second```` is not finite and positive.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # fn send_request(_: i32) {}
+    /// for request in Ticker::at_rate((0..), 7.5) {
+    ///     send_request(request)
+    /// }
+    /// ````
+    pub fn at_rate(src: I, per_second: f64) -> Self {
+        Ticker::new(src, rate_interval(per_second, Duration::from_secs(1)))
+    }
+
+    /// per_minute creates a Ticker which returns at most ````per_minute````
+    /// items each minute, like ````at_rate()````.
+    ///
+    /// Panics if ````per_minute```` is not finite and positive.
+    pub fn per_minute(src: I, per_minute: f64) -> Self {
+        Ticker::new(src, rate_interval(per_minute, Duration::from_secs(60)))
+    }
+
+    /// token_bucket creates a Ticker that allows bursts: it holds up to
+    /// ````capacity```` tokens, gains one every ````refill_interval```` while
+    /// the consumer is idle, and ````.next()```` only blocks once they run out.
+    /// Over time it still returns one item per ````refill_interval````. The
+    /// bucket starts full.
+    ///
+    /// This is ````Ticker::with_pacer(src, TokenBucket::new(capacity,
+    /// refill_interval))````, with a capacity past ````u32::MAX```` cut to
+    /// it. See TokenBucket.
+    ///
+    /// Panics if ````capacity```` is 0.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn call_api(_: i32) {}
+    /// // Bursts of up to 10 requests, 2 per second on average.
+    /// for request in Ticker::token_bucket((0..), Duration::from_millis(500), 10) {
+    ///     call_api(request)
+    /// }
+    /// ````
+    pub fn token_bucket(src: I, refill_interval: Duration, capacity: usize) -> Ticker<I, TokenBucket> {
+        let capacity = capacity.min(u32::MAX as usize) as u32;
+        Ticker::with_pacer(src, TokenBucket::new(capacity, refill_interval))
+    }
+
+    /// batched creates a Ticker that releases up to ````batch_size```` items
+    /// of ````src```` per tick, as a Vec. The last batch holds whatever the
+    /// source has left, and once the source is exhausted no empty batch is
+    /// returned; see Batches.
+    ///
+    /// Panics if ````batch_size```` is 0.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn send_batch(_: Vec<i32>) {}
+    /// // Up to 20 requests per batch, one batch per second.
+    /// for batch in Ticker::batched((0..), Duration::from_secs(1), 20) {
+    ///     send_batch(batch)
+    /// }
+    /// ````
+    pub fn batched(src: I, interval: Duration, batch_size: usize) -> Ticker<Batches<I>> {
+        Ticker::new(Batches::new(src, batch_size), interval)
+    }
+
+    /// weighted rate limits ````src```` by the cost of each item rather than
+    /// per item: after returning an item, the next one waits
+    /// ````cost(&item) * interval_per_unit````. See Weighted.
+    ///
+    /// A wait too long to represent as an Instant never ends, so the item
+    /// after one whose cost runs past it blocks ````.next()```` forever.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn upload(_: Vec<u8>) {}
+    /// # let payloads: Vec<Vec<u8>> = vec![];
+    /// // Upload at most 1 MB per second.
+    /// let kb = |p: &Vec<u8>| (p.len() / 1024) as u64;
+    /// for payload in Ticker::weighted(payloads.into_iter(), Duration::from_millis(1), kb) {
+    ///     upload(payload)
+    /// }
+    /// ````
+    pub fn weighted<F>(src: I, interval_per_unit: Duration, cost: F) -> Weighted<I, F>
+    where
+        F: FnMut(&I::Item) -> u64,
+    {
+        Weighted::new(src, interval_per_unit, cost)
+    }
+
+    /// classified rate limits ````src```` with a separate interval for each
+    /// class of item, as ````classify```` sorts them: ````rates[class]````, or
+    /// ````default```` for a class without a rate. See Classified.
+    ///
+    /// A class whose interval is too long to represent as an Instant, such
+    /// as ````Duration::MAX````, gets one item: the next blocks ````.next()````
+    /// forever.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::collections::HashMap;
+    /// # use std::time::Duration;
+    /// # enum Request { Get(String), Put(String) }
+    /// # fn send(_: Request) {}
+    /// # let requests: Vec<Request> = vec![];
+    /// // 100 reads and 5 writes a second.
+    /// let mut rates = HashMap::new();
+    /// rates.insert("read", Duration::from_millis(10));
+    /// rates.insert("write", Duration::from_millis(200));
+    /// let class = |r: &Request| match r {
+    ///     Request::Get(_) => "read",
+    ///     Request::Put(_) => "write",
+    /// };
+    /// for request in Ticker::classified(requests.into_iter(), class, rates, Duration::from_secs(1)) {
+    ///     send(request)
+    /// }
+    /// ````
+    pub fn classified<F, K>(src: I, classify: F, rates: HashMap<K, Duration>, default: Duration) -> Classified<I, F, K>
+    where
+        F: FnMut(&I::Item) -> K,
+        K: Eq + Hash,
+    {
+        Classified::new(src, classify, rates, default)
+    }
+
+    /// adaptive rate limits ````src```` with a delay after each item chosen by
+    /// ````delay(&item)````, or ````default```` when it returns None: for
+    /// responses that say how long to wait before the next request. See
+    /// Adaptive.
+    ///
+    /// A delay too long to represent as an Instant never ends: the item after
+    /// it blocks ````.next()```` forever.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # struct Response { retry_after: Option<Duration> }
+    /// # fn poll() -> Response { Response { retry_after: None } }
+    /// # fn handle(_: Response) {}
+    /// let responses = Ticker::adaptive(std::iter::repeat_with(poll), Duration::from_secs(1), |r| r.retry_after);
+    /// for response in responses {
+    ///     handle(response)
+    /// }
+    /// ````
+    pub fn adaptive<F>(src: I, default: Duration, delay: F) -> Adaptive<I, F>
+    where
+        F: FnMut(&I::Item) -> Option<Duration>,
+    {
+        Adaptive::new(src, default, delay)
+    }
+
+    /// per_window rate limits ````src```` to at most ````n```` items in any
+    /// span of ````window````, letting them clump within it. See Windowed.
+    ///
+    /// A window too long to represent as an Instant after an item, such as
+    /// ````Duration::MAX````, never passes, so once ````n```` items have gone
+    /// the next blocks ````.next()```` forever.
+    ///
+    /// Panics if ````n```` is 0.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn call_api(_: i32) {}
+    /// // The API allows 10 calls in any second.
+    /// for request in Ticker::per_window((0..), 10, Duration::from_secs(1)) {
+    ///     call_api(request)
+    /// }
+    /// ````
+    pub fn per_window(src: I, n: usize, window: Duration) -> Windowed<I> {
+        Windowed::new(src, n, window)
+    }
+
+    /// from_ticks releases an item from ````src```` for each tick from
+    /// ````ticks````, such as a message on a Receiver, instead of on a timer,
+    /// and ends once ````ticks```` does. See TickSource.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::sync::mpsc::channel;
+    /// # fn rebuild(_: i32) {}
+    /// let (changed, changes) = channel::<()>();
+    /// # drop(changed);
+    /// // Whatever watches the files sends on changed.
+    /// for build in Ticker::from_ticks((0..), changes) {
+    ///     rebuild(build)
+    /// }
+    /// ````
+    pub fn from_ticks<T: TickSource>(src: I, ticks: T) -> FromTicks<I, T> {
+        FromTicks::new(src, ticks)
+    }
+
+    /// throttle_while rate limits ````src```` to once every ````interval````
+    /// only while ````flag```` is set, and lets items straight through while
+    /// it is clear. See ThrottleWhile.
+    ///
+    /// An interval too long to represent as an Instant never passes, so while
+    /// the flag is set items wait until it is cleared.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::sync::Arc;
+    /// # use std::sync::atomic::AtomicBool;
+    /// # use std::time::Duration;
+    /// # fn process(_: i32) {}
+    /// let under_pressure = Arc::new(AtomicBool::new(false));
+    /// // Monitoring code sets under_pressure while the system is loaded.
+    /// for job in Ticker::throttle_while((0..), Duration::from_secs(1), under_pressure.clone()) {
+    ///     process(job)
+    /// }
+    /// ````
+    pub fn throttle_while(src: I, interval: Duration, flag: Arc<AtomicBool>) -> ThrottleWhile<I> {
+        ThrottleWhile::new(src, interval, flag)
+    }
+
+    /// ramp rate limits ````src```` with a delay that moves from ````from````
+    /// to ````to```` over ````over````, for warming up caches and connection
+    /// pools gently before running at full rate. See Ramp.
+    ///
+    /// A delay too long to represent as an Instant, which a ramp to or from
+    /// ````Duration::MAX```` soon reaches, never ends: the next item blocks
+    /// ````.next()```` forever.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn fetch(_: i32) {}
+    /// // Start at one request every 2 s, reaching 10 a second after a minute.
+    /// let requests = Ticker::ramp((0..), Duration::from_secs(2), Duration::from_millis(100), Duration::from_secs(60));
+    /// for request in requests {
+    ///     fetch(request)
+    /// }
+    /// ````
+    pub fn ramp(src: I, from: Duration, to: Duration, over: Duration) -> Ramp<I> {
+        Ramp::new(src, from, to, over)
+    }
+
+    /// with_schedule rate limits ````src```` with a different gap for each
+    /// tick, taken in turn from ````schedule````. Iteration ends when the
+    /// schedule does; see Scheduled.
+    ///
+    /// A gap too long to represent as an Instant, such as
+    /// ````Duration::MAX````, never passes: the ````.next()```` waiting on it
+    /// blocks forever, and so does any ````backoff()```` that reaches such a
+    /// ````max````.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::iter;
+    /// # use std::time::Duration;
+    /// # fn probe(_: i32) {}
+    /// // Warm up with 5 s gaps, run at 1 s gaps, then cool down.
+    /// let secs = Duration::from_secs;
+    /// let schedule = iter::repeat(secs(5)).take(3)
+    ///     .chain(iter::repeat(secs(1)).take(60))
+    ///     .chain(iter::repeat(secs(5)).take(3));
+    /// for i in Ticker::with_schedule((0..), schedule) {
+    ///     probe(i)
+    /// }
+    /// ````
+    pub fn with_schedule<S>(src: I, schedule: S) -> Scheduled<I, S::IntoIter>
+    where
+        S: IntoIterator<Item = Duration>,
+    {
+        Scheduled::new(src, schedule.into_iter())
+    }
+
+    /// backoff rate limits ````src```` for retry loops: the first item comes
+    /// at once, the second after ````initial````, and each gap after that is
+    /// ````factor```` times the last, up to ````max````. A finite source gives
+    /// exactly as many attempts as it has items.
+    ///
+    /// Panics if ````factor```` is not finite or is less than 1.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn connect() -> Result<(), ()> { Ok(()) }
+    /// let (initial, max) = (Duration::from_millis(100), Duration::from_secs(10));
+    /// for _attempt in Ticker::backoff(0..5, initial, 2.0, max) {
+    ///     if connect().is_ok() {
+    ///         break;
+    ///     }
+    /// }
+    /// ````
+    pub fn backoff(src: I, initial: Duration, factor: f64, max: Duration) -> Scheduled<I, Backoff> {
+        Ticker::with_schedule(src, Backoff::new(initial, factor, max))
+    }
 
-        Ticker { src, recv, kill }
+    /// aligned creates a Ticker whose ticks land on wall clock multiples of
+    /// ````interval```` since the Unix epoch: the top of every minute for a
+    /// one minute interval, or :00, :05, :10 and so on for five seconds.
+    ///
+    /// Only the first tick is aligned using the system clock. After that the
+    /// Ticker keeps to its grid in monotonic time like any other, so it is not
+    /// thrown by clock adjustments, but nor does it follow them; ticks drift
+    /// from the wall clock grid by however far the system clock is moved.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn report(_: i32) {}
+    /// for minute in Ticker::aligned((0..), Duration::from_secs(60)) {
+    ///     report(minute)
+    /// }
+    /// ````
+    pub fn aligned(src: I, interval: Duration) -> Self {
+        let mut ticker = Ticker::new(src, interval);
+        ticker.config.aligned = true;
+        ticker
     }
 }
 
-impl<I: Iterator> Drop for Ticker<I> {
-    fn drop(&mut self) {
-        let _ = self.kill.send(());
+#[cfg(feature = "std")]
+impl<I: Iterator, P: Pacer + Send + 'static> Ticker<I, P> {
+    /// with_pacer creates a Ticker that rate limits ````src```` with
+    /// ````pacer````, a strategy such as a TokenBucket or one of your own:
+    /// each item is due when ````pacer```` says, asked on the Ticker's Clock.
+    /// Every item costs 1. ````Ticker::new(src, interval)```` is
+    /// ````Ticker::with_pacer(src, FixedInterval::new(interval))````. See
+    /// Pacer.
+    ///
+    /// Its handle, stats, hooks and backend work as for any Ticker, and
+    /// ````TickerHandle::set_interval```` is passed on to the Pacer. The
+    /// settings about where ticks fall on a schedule, the missed tick
+    /// behavior, pacing, edge, first tick, initial delay, alignment, full
+    /// start, jitter and resume policy, only apply to a Pacer with a fixed
+    /// interval, such as FixedInterval; any other Pacer decides those for
+    /// itself. On a worker thread up to
+    /// ````max_pending```` ticks the Pacer has let through wait for the
+    /// consumer.
+    ///
+    /// ````no_run
+    /// # use ticker::{Ticker, TokenBucket};
+    /// # use std::time::Duration;
+    /// # fn call_api(_: i32) {}
+    /// // Bursts of up to 5 calls, 2 a second in the long run.
+    /// let bucket = TokenBucket::new(5, Duration::from_millis(500));
+    /// for request in Ticker::with_pacer((0..), bucket) {
+    ///     call_api(request)
+    /// }
+    /// ````
+    pub fn with_pacer(src: I, pacer: P) -> Self {
+        let (ctrl, ctrl_recv) = channel();
+        let shared = Arc::new(Shared::default());
+        Ticker {
+            src,
+            done: false,
+            config: Config::new(pacer.fixed().unwrap_or(Duration::ZERO)),
+            pacer: Arc::new(Mutex::new(pacer)),
+            cost: None,
+            ctrl,
+            ctrl_recv: Some(ctrl_recv),
+            start: shared.clock.now(),
+            shared,
+            backend: None,
+            item: None,
+            ticked: 0,
+            burst: 0,
+            burst_end: None,
+        }
+    }
+
+    /// with_pacer_cost is ````with_pacer()````, with each item costing what
+    /// ````cost```` says, so heavier items use up more of the rate. Since
+    /// the cost of an item is only known once it is pulled, such a Ticker is
+    /// inline. On a fixed interval an item costing ````n```` is followed by
+    /// the next ````n```` intervals later.
+    pub fn with_pacer_cost<F>(src: I, pacer: P, cost: F) -> Self
+    where
+        F: Fn(&I::Item) -> u32 + Send + Sync + 'static,
+    {
+        let mut ticker = Ticker::with_pacer(src, pacer);
+        ticker.cost = Some(Arc::new(cost));
+        ticker
+    }
+
+    /// clock schedules the Ticker against ````clock```` instead of the
+    /// system clock, as ````Ticker::new_with_clock```` does. Take handles
+    /// after setting it.
+    pub fn clock<C: Clock + 'static>(mut self, clock: C) -> Self {
+        self.shared = Arc::new(Shared::with_clock(Arc::new(clock)));
+        self.start = self.shared.clock.now();
+        self
+    }
+
+    /// paced is whether the Ticker asks its Pacer when each tick is due,
+    /// rather than keeping a fixed interval's schedule itself.
+    fn paced(&self) -> bool {
+        pacer::lock(&self.pacer).fixed().is_none()
+    }
+
+    /// manual_ticks finishes configuring the Ticker so that, as well as
+    /// ticking on time, it ticks whenever the returned TickHandle does: for
+    /// waking a poller early when there is news, say. Such a Ticker is
+    /// inline, and carries on ticking on time when every TickHandle is gone.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::iter;
+    /// # use std::time::Duration;
+    /// # fn poll() {}
+    /// let (polls, wake) = Ticker::new(iter::repeat(()), Duration::from_secs(60)).manual_ticks();
+    /// // Whoever learns of an update calls wake.tick() to poll now.
+    /// for _ in polls {
+    ///     poll()
+    /// }
+    /// ````
+    pub fn manual_ticks(mut self) -> (TickIter<I, P>, TickHandle) {
+        self.config.manual = Manual::WithTimer;
+        self.tick_handle()
+    }
+
+    /// tick_handle is the Ticker as a TickIter, and a TickHandle to tick it.
+    fn tick_handle(self) -> (TickIter<I, P>, TickHandle) {
+        let handle = TickHandle {
+            sender: Arc::new(TickSender(self.ctrl.clone())),
+        };
+        (TickIter { ticker: self }, handle)
+    }
+
+    /// stats returns what this Ticker has done so far. See TickerStats.
+    pub fn stats(&self) -> TickerStats {
+        self.shared.stats.snapshot()
+    }
+
+    /// interval is how often the Ticker ticks: the interval it was created
+    /// with, or the last one given to ````TickerHandle::set_interval````. A
+    /// Ticker paced by a Pacer without a fixed interval has none of its own,
+    /// and it is zero until one is set.
+    pub fn interval(&self) -> Duration {
+        let set = *self.shared.interval.lock().unwrap_or_else(|e| e.into_inner());
+        set.unwrap_or(self.config.interval)
+    }
+
+    /// ticks_elapsed is how many ticks have fired so far, as counted by
+    /// ````stats().ticks````.
+    pub fn ticks_elapsed(&self) -> u64 {
+        self.shared.stats.snapshot().ticks
+    }
+
+    /// time_until_next_tick estimates how long until the next tick fires:
+    /// one ````interval()```` after the last tick, or before the first, when
+    /// the first is due after the Ticker was created. It is zero once a tick
+    /// is due, and ````Duration::MAX```` for a Ticker that only ticks by
+    /// hand. The estimate does not know about jitter, pauses, alignment or
+    /// ticks already waiting to be taken, and an interval set since the last
+    /// tick counts from that tick, or the cost of the next item. Nor does it
+    /// know what a Pacer without a fixed interval will say, so for a Ticker
+    /// paced by one it is always zero.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn idle(_: Duration) {}
+    /// # fn work(_: i32) {}
+    /// let mut ticker = Ticker::new(0.., Duration::from_secs(1));
+    /// loop {
+    ///     idle(ticker.time_until_next_tick());
+    ///     work(ticker.next().unwrap())
+    /// }
+    /// ````
+    pub fn time_until_next_tick(&self) -> Duration {
+        if self.config.manual == Manual::Only {
+            return Duration::MAX;
+        }
+        if self.paced() {
+            return Duration::ZERO;
+        }
+        let now = self.shared.clock.now();
+        let next = match self.shared.stats.last_fired() {
+            Some(fired) => fired.checked_add(self.interval()),
+            None if self.burst < self.config.initial_burst => Some(now),
+            None => self.burst_end.unwrap_or(self.start).checked_add(self.config.first_delay()),
+        };
+        next.map_or(Duration::MAX, |next| next.saturating_duration_since(now))
+    }
+
+    /// handle returns a TickerHandle to adjust this Ticker from another thread,
+    /// before or while it is iterated. Handles do not affect Streams made from
+    /// it.
+    pub fn handle(&self) -> TickerHandle {
+        TickerHandle {
+            ctrl: self.ctrl.clone(),
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// edge sets whether the Ticker waits out each interval before or after
+    /// the item it paces. Defaults to ````TickEdge::Leading````. See
+    /// TickEdge.
+    ///
+    /// ````no_run
+    /// # use ticker::{TickEdge, Ticker};
+    /// # use std::time::Duration;
+    /// # fn migrate(_: i32) {}
+    /// // Start at once, and cool down for 5 s between batches.
+    /// let batches = Ticker::new(0..10, Duration::from_secs(5)).edge(TickEdge::Trailing);
+    /// for batch in batches {
+    ///     migrate(batch)
+    /// }
+    /// ````
+    pub fn edge(mut self, edge: TickEdge) -> Self {
+        self.config.edge = edge;
+        self
+    }
+
+    /// first_tick_immediately sets whether the first call to ````.next()````
+    /// returns right away instead of waiting one ````interval````. Later items
+    /// are still spaced by ````interval````. Defaults to false.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn refresh() {}
+    /// let ticker = Ticker::new((0..), Duration::from_secs(30)).first_tick_immediately(true);
+    /// for _ in ticker {
+    ///     refresh()
+    /// }
+    /// ````
+    pub fn first_tick_immediately(mut self, immediate: bool) -> Self {
+        self.config.first_tick_immediately = immediate;
+        self
+    }
+
+    /// for_duration ends iteration ````total```` after the Ticker is created:
+    /// from then on ````.next()```` returns None even if the source has more
+    /// items, and a ````.next()```` still waiting for its tick returns None
+    /// right at the cutoff rather than sleeping past it. A tick due exactly
+    /// at the cutoff does not release an item. By default a Ticker runs until
+    /// the source ends.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn send_request(_: i32) {}
+    /// let load = Ticker::new((0..), Duration::from_millis(50))
+    ///     .for_duration(Duration::from_secs(120));
+    /// for request in load {
+    ///     send_request(request)
+    /// }
+    /// ````
+    pub fn for_duration(mut self, total: Duration) -> Self {
+        self.config.run_for = Some(total);
+        self
+    }
+
+    /// max_ticks ends iteration after ````max_ticks```` ticks: from then on
+    /// ````.next()```` returns None even if the source has more items, and
+    /// the worker thread exits as soon as the last tick is taken rather than
+    /// when the Ticker is dropped. A source that ends sooner ends the Ticker
+    /// sooner, as usual. ````nth()```` takes one tick however many items it
+    /// skips. By default there is no limit.
+    pub fn max_ticks(mut self, max_ticks: usize) -> Self {
+        self.config.max_ticks = Some(max_ticks);
+        self
+    }
+
+    /// initial_delay sets how long after the Ticker is created the first tick
+    /// comes, separately from the ````interval```` between ticks, which is
+    /// measured from the first tick on. A zero delay is the same as
+    /// ````first_tick_immediately(true)````, and a delay overrides that
+    /// setting. Defaults to one ````interval````. A delay too long to
+    /// represent as an Instant, such as ````Duration::MAX````, blocks
+    /// ````.next()```` forever.
+    pub fn initial_delay(mut self, delay: Duration) -> Self {
+        self.config.initial_delay = Some(delay);
+        self
+    }
+
+    /// initial_burst lets the first ````burst```` items through without
+    /// waiting, as a one time allowance that is never replenished. The
+    /// schedule then starts from the last of them, so item ````burst + 1````
+    /// comes one interval (or ````initial_delay````) after it, and the rest
+    /// are paced as usual. Burst items take no ticks: they are not counted
+    /// by ````max_ticks```` or ````stats()````. A burst at least as long as
+    /// the source leaves it unpaced. Defaults to 0.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn fetch(_: i32) {}
+    /// let pages = Ticker::new((0..10), Duration::from_secs(1)).initial_burst(4);
+    /// for page in pages {
+    ///     fetch(page)
+    /// }
+    /// ````
+    pub fn initial_burst(mut self, burst: usize) -> Self {
+        self.config.initial_burst = burst;
+        self
+    }
+
+    /// missed_tick_behavior sets what happens to ticks that come due while the
+    /// consumer is busy. Defaults to ````MissedTickBehavior::Burst````.
+    ///
+    /// ````no_run
+    /// # use ticker::{MissedTickBehavior, Ticker};
+    /// # use std::time::Duration;
+    /// # fn slow_work(_: i32) {}
+    /// let ticker = Ticker::new((0..), Duration::from_secs(1))
+    ///     .missed_tick_behavior(MissedTickBehavior::Skip);
+    /// for i in ticker {
+    ///     slow_work(i)
+    /// }
+    /// ````
+    pub fn missed_tick_behavior(mut self, behavior: MissedTickBehavior) -> Self {
+        self.config.missed_tick_behavior = behavior;
+        self
+    }
+
+    /// pacing sets whether the interval runs from one tick to the next, or
+    /// from when the consumer comes back for each item to when it gets it.
+    /// Defaults to ````Pacing::Fixed````. See Pacing.
+    ///
+    /// ````no_run
+    /// # use ticker::{Pacing, Ticker};
+    /// # use std::time::Duration;
+    /// # fn scrape(_: i32) {}
+    /// // Rest a second between scrapes, however long each one takes.
+    /// let ticker = Ticker::new((0..), Duration::from_secs(1)).pacing(Pacing::MinGap);
+    /// for page in ticker {
+    ///     scrape(page)
+    /// }
+    /// ````
+    pub fn pacing(mut self, pacing: Pacing) -> Self {
+        self.config.pacing = pacing;
+        self
+    }
+
+    /// max_pending caps how many ticks can be waiting for a slow consumer under
+    /// ````MissedTickBehavior::Burst````; ticks past the cap are dropped. After
+    /// a long stall at most ````max_pending```` items are returned back to
+    /// back. The other behaviors never hold more than one pending tick.
+    /// Defaults to 1.
+    ///
+    /// Panics if ````max_pending```` is 0.
+    pub fn max_pending(mut self, max_pending: usize) -> Self {
+        assert!(max_pending > 0, "max_pending must be at least 1");
+        self.config.max_pending = max_pending;
+        self
+    }
+
+    /// resume_policy sets what happens when the Ticker wakes more than
+    /// ````after```` intervals later than it went to sleep for, as it does
+    /// when the machine is suspended: ticks are kept against Instant
+    /// deadlines, and however the OS accounts for the time asleep, the whole
+    /// gap has come due at once. A consumer that is merely late is handled by
+    /// the MissedTickBehavior as usual; this is only for the Ticker itself
+    /// sleeping through its ticks.
+    ///
+    /// ````MissedTickBehavior::Delay```` returns one item and starts the
+    /// schedule again from the wake; ````Skip```` returns one item and carries
+    /// on from the next tick of the old schedule. ````Burst```` leaves the gap
+    /// to the Ticker's own MissedTickBehavior, so a Burst Ticker returns the
+    /// backlog, up to ````max_pending```` items. The ticks passed over are
+    /// reported to ````on_missed_tick()````, and the gap to ````on_resume()````.
+    /// Defaults to ````Delay```` after 10 intervals. Pass ````u32::MAX```` to
+    /// treat every late wake like any other missed tick.
+    ///
+    /// ````no_run
+    /// # use ticker::{MissedTickBehavior, Ticker};
+    /// # use std::time::Duration;
+    /// # fn sync(_: i32) {}
+    /// // After a night asleep, sync once and carry on from the hour.
+    /// let ticker = Ticker::new((0..), Duration::from_secs(60))
+    ///     .resume_policy(2, MissedTickBehavior::Skip)
+    ///     .on_resume(|gap| eprintln!("woke {:?} late", gap));
+    /// for i in ticker {
+    ///     sync(i)
+    /// }
+    /// ````
+    pub fn resume_policy(mut self, after: u32, behavior: MissedTickBehavior) -> Self {
+        self.config.resume = Resume { after, behavior };
+        self
+    }
+
+    /// inline sets whether the Iterator sleeps inside ````.next()```` instead
+    /// of receiving ticks from a worker thread. An inline Ticker spawns no
+    /// thread, which makes it cheap to create and drop.
+    /// It keeps the same schedule as a threaded one, so a consumer that was
+    /// already slower than ````interval```` does not sleep at all. Defaults to
+    /// false.
+    pub fn inline(mut self, inline: bool) -> Self {
+        self.config.inline = inline;
+        self
+    }
+
+    /// start_full sets whether the Ticker starts with as many ticks waiting as
+    /// it can hold, as if the consumer had been idle since long before it was
+    /// created. Under ````MissedTickBehavior::Burst```` that is
+    /// ````max_pending```` items returned without waiting; otherwise one.
+    /// Defaults to false.
+    pub fn start_full(mut self, full: bool) -> Self {
+        self.config.start_full = full;
+        self
+    }
+
+    /// pull_first sets whether each item is pulled from the source before
+    /// waiting for its tick, instead of once the tick comes. Pulling first
+    /// means that once the source is exhausted ````.next()```` returns None
+    /// without waiting out another interval, but an item from a live
+    /// source, such as a sensor read, is then up to an interval old when it
+    /// is returned. A Ticker whose items have a cost always pulls first, and
+    /// an item held over from a bounded wait is used before pulling another.
+    /// Defaults to false.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn upload(_: i32) {}
+    /// // Done as soon as the last item is uploaded, not an interval later.
+    /// for item in Ticker::new(0..3, Duration::from_secs(10)).pull_first(true) {
+    ///     upload(item)
+    /// }
+    /// ````
+    pub fn pull_first(mut self, first: bool) -> Self {
+        self.config.pull_first = first;
+        self
+    }
+
+    /// jitter moves each tick by a random offset of up to ````jitter````
+    /// earlier or later, so many Tickers started together do not fire in
+    /// lockstep. Offsets do not accumulate: each tick is jittered around its
+    /// own deadline, so the average rate stays one per ````interval````. The
+    /// offset is capped at half the interval, so ticks keep their order and
+    /// gaps stay between zero and two intervals. Defaults to no jitter.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn poll_backend() {}
+    /// let ticker = Ticker::new((0..), Duration::from_secs(10))
+    ///     .jitter(Duration::from_secs(2));
+    /// for _ in ticker {
+    ///     poll_backend()
+    /// }
+    /// ````
+    pub fn jitter(mut self, jitter: Duration) -> Self {
+        self.config.jitter = jitter;
+        self
+    }
+
+    /// jitter_seed seeds the random offsets picked by ````jitter()````, so the
+    /// same seed gives the same offsets. By default a random seed is used.
+    pub fn jitter_seed(mut self, seed: u64) -> Self {
+        self.config.jitter_seed = Some(seed);
+        self
+    }
+
+    /// spin_threshold sets how much of the end of each wait is spent spinning
+    /// on the clock instead of sleeping. Sleeps usually overshoot by up to a
+    /// millisecond or two, which is most of a very short interval; spinning
+    /// lands within microseconds of the deadline, at the cost of keeping a
+    /// core busy for up to ````threshold```` per tick. Anything longer is
+    /// still slept, so long intervals waste little CPU. Defaults to zero,
+    /// which never spins.
+    ///
+    /// Under a threaded Ticker the worker spins, and the tick still has to
+    /// wake the consumer, so precise Tickers are best made inline.
+    pub fn spin_threshold(mut self, threshold: Duration) -> Self {
+        self.config.spin = threshold;
+        self
+    }
+
+    /// on_tick runs ````f```` with the time of every tick as it fires, whether
+    /// or not the consumer takes it; these are the ticks counted by
+    /// ````TickerStats::ticks````. It runs on the worker thread, or inside
+    /// ````.next()```` for an inline Ticker, so it must be quick. If ````f````
+    /// panics, it is not run again, and the Ticker carries on ticking.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::sync::atomic::{AtomicU64, Ordering};
+    /// # use std::sync::Arc;
+    /// # use std::time::Duration;
+    /// # fn work(_: i32) {}
+    /// let ticks = Arc::new(AtomicU64::new(0));
+    /// let counter = ticks.clone();
+    /// let ticker = Ticker::new((0..), Duration::from_secs(1))
+    ///     .on_tick(move |_| { counter.fetch_add(1, Ordering::Relaxed); });
+    /// for i in ticker {
+    ///     work(i)
+    /// }
+    /// ````
+    pub fn on_tick<F>(mut self, f: F) -> Self
+    where
+        F: Fn(Instant) + Send + Sync + 'static,
+    {
+        self.config.hooks.on_tick = Hook::new(f);
+        self
+    }
+
+    /// on_missed_tick runs ````f```` with the number of ticks dropped or
+    /// skipped because the consumer was late, each time some are, as decided
+    /// by the ````MissedTickBehavior````. Under ````Delay```` these are the
+    /// ticks the schedule moved past. It runs where ````on_tick()```` does,
+    /// and is turned off in the same way if it panics.
+    pub fn on_missed_tick<F>(mut self, f: F) -> Self
+    where
+        F: Fn(u64) + Send + Sync + 'static,
+    {
+        self.config.hooks.on_missed = Hook::new(f);
+        self
+    }
+
+    /// on_resume runs ````f```` with how late the Ticker woke, each time it
+    /// wakes late enough for its ````resume_policy()````, so that a suspend
+    /// can be logged. It runs where ````on_tick()```` does, and is turned off
+    /// in the same way if it panics.
+    pub fn on_resume<F>(mut self, f: F) -> Self
+    where
+        F: Fn(Duration) + Send + Sync + 'static,
+    {
+        self.config.hooks.on_resume = Hook::new(f);
+        self
+    }
+
+    /// thread_name names the worker thread, for debuggers and ````/proc````.
+    /// Defaults to ````ticker-```` and the interval, e.g. ````ticker-1s````.
+    pub fn thread_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.config.thread_name = Some(name.into());
+        self
+    }
+
+    /// stack_size sets the worker thread's stack size in bytes. The worker
+    /// needs very little, so a few KiB saves memory when running many
+    /// Tickers; the platform may round it up to its minimum. Defaults to the
+    /// standard library's default.
+    pub fn stack_size(mut self, bytes: usize) -> Self {
+        self.config.stack_size = Some(bytes);
+        self
     }
 }
 
-impl<I: Iterator> IntoIterator for Ticker<I> {
-    type Item = I::Item;
-    type IntoIter = TickIter<I>;
+/// THREADS is whether the target can spawn and sleep threads. Where it cannot,
+/// as on ````wasm32-unknown-unknown````, every Ticker ticks inline and waits
+/// by spinning.
+#[cfg(feature = "std")]
+pub(crate) const THREADS: bool = !cfg!(all(target_arch = "wasm32", target_os = "unknown"));
+
+/// DEFAULT_SPIN_THRESHOLD is how much of each wait a Ticker made by
+/// ````Ticker::new_precise()```` spins through.
+#[cfg(feature = "std")]
+pub const DEFAULT_SPIN_THRESHOLD: Duration = Duration::from_millis(2);
 
-    fn into_iter(self) -> Self::IntoIter {
-        TickIter { ticker: self }
+/// recv_until waits for a Control until ````deadline````, spinning through
+/// the last ````spin```` of the wait rather than sleeping. A ````spin```` too
+/// long to subtract from ````deadline```` spins through the whole wait. Like
+/// ````recv_timeout````, it may return before the deadline; check the clock.
+#[cfg(feature = "std")]
+fn recv_until(ctrl: &Receiver<Control>,
+              clock: &dyn Clock,
+              deadline: Instant,
+              spin: Duration)
+              -> Result<Control, RecvTimeoutError> {
+    match deadline.checked_sub(spin) {
+        Some(coarse) if spin == Duration::ZERO || clock.now() < coarse => {
+            return ctrl.recv_timeout(clock.timeout(coarse));
+        }
+        _ => {}
     }
+    hint::spin_loop();
+    ctrl.try_recv().map_err(|e| match e {
+        TryRecvError::Empty => RecvTimeoutError::Timeout,
+        TryRecvError::Disconnected => RecvTimeoutError::Disconnected,
+    })
 }
 
-/// TickIter implements a rate limited Iterator; derive this from Ticker using
-/// for loop syntax or ````.into_iter()````.
-pub struct TickIter<I: Iterator> {
-    ticker: Ticker<I>,
+/// rate_interval returns the interval between ticks for ````rate```` ticks every
+/// ````period````, at least one nanosecond.
+#[cfg(feature = "std")]
+fn rate_interval(rate: f64, period: Duration) -> Duration {
+    assert!(rate.is_finite() && rate > 0.0,
+            "rate must be finite and positive, got {}",
+            rate);
+    let nanos = period.as_nanos() as f64 / rate;
+    Duration::from_nanos(nanos.round().max(1.0) as u64)
 }
 
-impl<I: Iterator> Iterator for TickIter<I> {
-    type Item = I::Item;
+/// until_aligned is how long after ````since_epoch```` the next multiple of
+/// ````interval```` is.
+#[cfg(feature = "std")]
+fn until_aligned(since_epoch: Duration, interval: Duration) -> Duration {
+    let interval = interval.as_nanos();
+    if interval == 0 {
+        return Duration::ZERO;
+    }
+    match since_epoch.as_nanos() % interval {
+        0 => Duration::ZERO,
+        into => {
+            let nanos = interval - into;
+            Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32)
+        }
+    }
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let _ = self.ticker.recv.recv().expect("ticker channel to live");
-        self.ticker.src.next()
+/// Manual is whether a Ticker can be ticked by a TickHandle.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Manual {
+    Off,
+    /// WithTimer ticks by hand and on time.
+    WithTimer,
+    /// Only ticks by hand.
+    Only,
+}
+
+/// Resume is what a Ticker does when it wakes long after a tick was due, as
+/// when the machine was suspended.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug)]
+struct Resume {
+    /// after is how many intervals late a wake must be to count.
+    after: u32,
+    behavior: MissedTickBehavior,
+}
+
+#[cfg(feature = "std")]
+impl Default for Resume {
+    fn default() -> Self {
+        Resume {
+            after: 10,
+            behavior: MissedTickBehavior::Delay,
+        }
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+#[cfg(feature = "std")]
+impl Resume {
+    /// gap is how late a wake at ````now```` is for the tick due at ````due````,
+    /// if it is late enough to count.
+    fn gap(&self, interval: Duration, due: Instant, now: Instant) -> Option<Duration> {
+        let late = now.checked_duration_since(due)?;
+        let limit = interval.checked_mul(self.after)?;
+        if interval > Duration::ZERO && late > limit {
+            Some(late)
+        } else {
+            None
+        }
+    }
+}
 
-    #[test]
-    fn it_works() {
-        let vec: Vec<usize> = (0..10).collect();
-        let ticker = Ticker::new(vec.iter(), Duration::from_secs(1));
-        for i in ticker {
-            println!("{:?}", i);
+/// Config holds the settings a Ticker starts with.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+struct Config {
+    interval: Duration,
+    first_tick_immediately: bool,
+    missed_tick_behavior: MissedTickBehavior,
+    pacing: Pacing,
+    edge: TickEdge,
+    max_pending: usize,
+    inline: bool,
+    start_full: bool,
+    pull_first: bool,
+    aligned: bool,
+    jitter: Duration,
+    jitter_seed: Option<u64>,
+    spin: Duration,
+    initial_delay: Option<Duration>,
+    initial_burst: usize,
+    manual: Manual,
+    run_for: Option<Duration>,
+    max_ticks: Option<usize>,
+    thread_name: Option<String>,
+    stack_size: Option<usize>,
+    resume: Resume,
+    hooks: Hooks,
+}
+
+#[cfg(feature = "std")]
+impl Config {
+    fn new(interval: Duration) -> Self {
+        Config {
+            interval,
+            first_tick_immediately: false,
+            missed_tick_behavior: MissedTickBehavior::default(),
+            pacing: Pacing::default(),
+            edge: TickEdge::default(),
+            max_pending: 1,
+            inline: false,
+            start_full: false,
+            pull_first: false,
+            aligned: false,
+            jitter: Duration::ZERO,
+            jitter_seed: None,
+            spin: Duration::ZERO,
+            initial_delay: None,
+            initial_burst: 0,
+            manual: Manual::Off,
+            run_for: None,
+            max_ticks: None,
+            thread_name: None,
+            stack_size: None,
+            resume: Resume::default(),
+            hooks: Hooks::default(),
+        }
+    }
+
+    /// pending_cap is how many ticks may wait for a late consumer.
+    fn pending_cap(&self) -> usize {
+        match self.missed_tick_behavior {
+            MissedTickBehavior::Burst => self.max_pending,
+            _ => 1,
+        }
+    }
+
+    /// first_tick is when the first tick is due, for ticking that started at
+    /// ````start````, ````since_start```` ago. A full start is a schedule that
+    /// began early enough for every pending slot to be due already. It is
+    /// None if the tick is too far off for an Instant to hold, so it never
+    /// comes.
+    fn first_tick(&self, start: Instant, since_start: Duration) -> Option<Instant> {
+        if self.edge == TickEdge::Trailing {
+            start.checked_add(since_start)
+        } else if self.start_full {
+            let backlog = self.pending_cap().min(u32::MAX as usize) as u32 - 1;
+            self.interval
+                .checked_mul(backlog)
+                .and_then(|backlog| start.checked_sub(backlog))
+                .or(Some(start))
+        } else if self.aligned {
+            let since_epoch = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or(Duration::ZERO)
+                .saturating_sub(since_start);
+            start.checked_add(until_aligned(since_epoch, self.interval))
+        } else {
+            start.checked_add(self.first_delay())
+        }
+    }
+
+    /// first_delay is how long after the start the first tick is due,
+    /// unless it is aligned or starts full.
+    fn first_delay(&self) -> Duration {
+        if self.edge == TickEdge::Trailing {
+            return Duration::ZERO;
+        }
+        match self.initial_delay {
+            Some(delay) => delay,
+            None if self.first_tick_immediately => Duration::ZERO,
+            None => self.interval,
+        }
+    }
+
+    /// thread is how the worker thread is spawned.
+    fn thread(&self) -> thread::Builder {
+        let name = self.thread_name
+            .clone()
+            .unwrap_or_else(|| format!("ticker-{:?}", self.interval));
+        let builder = thread::Builder::new().name(name);
+        match self.stack_size {
+            Some(size) => builder.stack_size(size),
+            None => builder,
+        }
+    }
+
+    fn jitter(&self) -> Jitter {
+        let seed = self.jitter_seed
+            .unwrap_or_else(|| RandomState::new().build_hasher().finish());
+        Jitter {
+            max: self.jitter,
+            state: seed,
+        }
+    }
+}
+
+/// Jitter picks random offsets for ticks, of up to ````max```` earlier or
+/// later.
+#[cfg(feature = "std")]
+struct Jitter {
+    max: Duration,
+    state: u64,
+}
+
+#[cfg(feature = "std")]
+impl Jitter {
+    /// offset picks the offset for the next tick, capped at half of
+    /// ````interval````.
+    fn offset(&mut self, interval: Duration) -> Offset {
+        let max = self.max.min(interval / 2);
+        if max == Duration::ZERO {
+            return Offset::default();
+        }
+        let nanos = max.as_nanos().min(u64::MAX as u128 - 1) as u64;
+        let r = self.next_u64();
+        Offset {
+            by: Duration::from_nanos(r % (nanos + 1)),
+            early: r >> 63 == 1,
         }
     }
+
+    /// next_u64 is splitmix64.
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// Offset moves a tick away from its deadline.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, Default)]
+struct Offset {
+    by: Duration,
+    early: bool,
+}
+
+#[cfg(feature = "std")]
+impl Offset {
+    fn apply(self, deadline: Instant) -> Instant {
+        if self.early {
+            deadline.checked_sub(self.by).unwrap_or(deadline)
+        } else {
+            deadline.checked_add(self.by).unwrap_or(deadline)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I: Iterator, P: Pacer + Send + 'static> Ticker<I, P> {
+    /// backend creates what paces this Ticker if the first ````.next()````
+    /// has not already, and returns it.
+    ///
+    /// Panics if the worker thread cannot be spawned.
+    fn backend(&mut self) -> &mut Backend {
+        match self.start_backend() {
+            Ok(backend) => backend,
+            Err(e) => panic!("failed to spawn ticker thread: {}", e),
+        }
+    }
+
+    /// start_backend is ````backend()````, failing if the worker thread
+    /// cannot be spawned.
+    fn start_backend(&mut self) -> io::Result<&mut Backend> {
+        if self.backend.is_none() {
+            self.config.hooks.span = Span::new(self.config.interval);
+            let start = self.burst_end.unwrap_or(self.start);
+            let since_start = self.shared.clock.now().saturating_duration_since(start);
+            let first = self.config.first_tick(start, since_start);
+            let ctrl_recv = self.ctrl_recv.take().expect("ticker to start once");
+            let inline = self.config.inline || self.config.pacing == Pacing::MinGap || self.config.manual != Manual::Off;
+            let pacer: Option<SharedPacer> = if self.paced() { Some(self.pacer.clone()) } else { None };
+            let unlimited = pacer.is_none() && self.config.interval == Duration::ZERO;
+            self.backend = Some(if inline || !THREADS || unlimited || self.cost.is_some() {
+                Backend::Inline {
+                    schedule: Schedule::new(&self.config, self.shared.clock.clone(), first, pacer),
+                    ctrl: ctrl_recv,
+                    shared: self.shared.clone(),
+                }
+            } else {
+                Backend::Thread(WorkerHandle::spawn(&self.config,
+                                                    (self.ctrl.clone(), ctrl_recv),
+                                                    self.shared.clone(),
+                                                    first,
+                                                    pacer)?)
+            });
+        }
+        Ok(self.backend.as_mut().expect("ticker to have started"))
+    }
+
+    /// next_tick is ````.next()````, also returning when the item's tick
+    /// fired.
+    fn next_tick(&mut self) -> Option<(I::Item, Instant)> {
+        self.tick_with(front)
+    }
+
+    /// tick_with takes an item with ````pull````, which is given the source
+    /// and any item held over from a bounded wait, then waits for its tick.
+    fn tick_with<F>(&mut self, pull: F) -> Option<(I::Item, Instant)>
+    where
+        F: FnOnce(&mut I, &mut Option<I::Item>) -> Option<I::Item>,
+    {
+        self.tick_until(pull, None).unwrap_or(None)
+    }
+
+    /// tick_until is ````tick_with````, giving up at ````deadline````. The
+    /// item taken for a tick that has not come by then is held over for the
+    /// next call, so neither the item nor the tick is lost.
+    fn tick_until<F>(&mut self,
+                     pull: F,
+                     deadline: Option<Instant>)
+                     -> Result<Option<(I::Item, Instant)>, TimedOut>
+    where
+        F: FnOnce(&mut I, &mut Option<I::Item>) -> Option<I::Item>,
+    {
+        if self.done || self.shared.stopped.load(Ordering::SeqCst) {
+            return Ok(None);
+        }
+        let end = self.end();
+        if self.past(end) || self.ticks_left() == Some(0) {
+            self.finish();
+            return Ok(None);
+        }
+        if self.paced() {
+            pacer::lock(&self.pacer).on_idle(self.shared.clock.now());
+        }
+        let bursting = self.burst < self.config.initial_burst && self.backend.is_none();
+        // Ok is an item pulled before waiting for its tick, and Err the pull
+        // still to make once the tick comes.
+        let item = if self.item.is_some() || self.config.pull_first || self.cost.is_some() || bursting {
+            match pull(&mut self.src, &mut self.item) {
+                Some(item) => Ok(item),
+                None => return Ok(self.exhausted()),
+            }
+        } else {
+            Err(pull)
+        };
+        if bursting {
+            let now = self.shared.clock.now();
+            self.burst += 1;
+            if self.burst == self.config.initial_burst {
+                self.burst_end = Some(now);
+            }
+            return Ok(item.ok().map(|item| (item, now)));
+        }
+        let wait = match (deadline, end) {
+            (Some(deadline), Some(end)) => Some(deadline.min(end)),
+            (deadline, end) => deadline.or(end),
+        };
+        let cost = match (&self.cost, &item) {
+            (Some(cost), Ok(item)) => cost(item),
+            _ => 1,
+        };
+        let asked = self.shared.clock.now();
+        match self.backend().wait_until(wait, cost) {
+            Wait::Tick(fired) => {
+                let item = match item {
+                    Ok(item) => item,
+                    Err(pull) => match pull(&mut self.src, &mut self.item) {
+                        Some(item) => item,
+                        None => return Ok(self.exhausted()),
+                    },
+                };
+                self.shared.stats.returned(fired, asked);
+                self.ticked += 1;
+                if self.ticks_left() == Some(0) {
+                    self.finish();
+                }
+                Ok(Some((item, fired)))
+            }
+            Wait::Ended => {
+                self.finish();
+                Ok(None)
+            }
+            Wait::TimedOut => {
+                self.item = item.ok();
+                if self.past(end) {
+                    self.finish();
+                    return Ok(None);
+                }
+                Err(TimedOut)
+            }
+        }
+    }
+
+    /// exhausted ends iteration once the source has run out.
+    fn exhausted<T>(&mut self) -> Option<T> {
+        #[cfg(feature = "tracing")]
+        self.shared.exhausted.store(true, Ordering::SeqCst);
+        self.finish();
+        None
+    }
+
+    /// finish ends iteration, stopping the worker now rather than when the
+    /// Ticker is dropped, so an exhausted Ticker that is kept around costs
+    /// nothing.
+    fn finish(&mut self) {
+        self.done = true;
+        self.backend = None;
+    }
+
+    /// end is when a Ticker set to run ````for_duration()```` stops.
+    fn end(&self) -> Option<Instant> {
+        self.config.run_for.and_then(|total| self.start.checked_add(total))
+    }
+
+    /// ticks_left is how many more ticks a Ticker set ````max_ticks()```` may
+    /// take.
+    fn ticks_left(&self) -> Option<usize> {
+        self.config.max_ticks.map(|max| max.saturating_sub(self.ticked))
+    }
+
+    /// past is whether ````end```` has come.
+    fn past(&self, end: Option<Instant>) -> bool {
+        match end {
+            Some(end) => self.shared.clock.now() >= end,
+            None => false,
+        }
+    }
+
+    /// try_next returns the next item if its tick is already due, without
+    /// blocking. It returns ````Err(NotDue)```` if no tick is due yet, and
+    /// ````Ok(None)```` once the source is exhausted. A tick is only used up
+    /// together with an item, so polling in a loop never raises the rate.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn poll_other_sources() {}
+    /// let mut ticker = Ticker::new(0.., Duration::from_secs(1));
+    /// loop {
+    ///     match ticker.try_next() {
+    ///         Ok(Some(i)) => println!("{}", i),
+    ///         Ok(None) => break,
+    ///         Err(_) => poll_other_sources(),
+    ///     }
+    /// }
+    /// ````
+    pub fn try_next(&mut self) -> Result<Option<I::Item>, NotDue> {
+        let now = self.shared.clock.now();
+        match self.tick_until(front, Some(now)) {
+            Ok(tick) => Ok(tick.map(|(item, _)| item)),
+            Err(TimedOut) => Err(NotDue),
+        }
+    }
+
+    /// next_timeout is ````.next()````, waiting no longer than ````timeout````
+    /// for the item's tick. It returns ````Err(Timeout)```` if the tick does
+    /// not come in time, and ````Ok(None)```` once the source is exhausted.
+    /// Timing out loses nothing: the next call gets the same tick, on
+    /// schedule.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::sync::atomic::{AtomicBool, Ordering};
+    /// # use std::time::Duration;
+    /// # static SHUTDOWN: AtomicBool = AtomicBool::new(false);
+    /// let mut ticker = Ticker::new((0..), Duration::from_secs(30));
+    /// while !SHUTDOWN.load(Ordering::SeqCst) {
+    ///     match ticker.next_timeout(Duration::from_millis(100)) {
+    ///         Ok(Some(i)) => println!("{}", i),
+    ///         Ok(None) => break,
+    ///         Err(_) => continue,
+    ///     }
+    /// }
+    /// ````
+    pub fn next_timeout(&mut self, timeout: Duration) -> Result<Option<I::Item>, Timeout> {
+        let deadline = self.shared.clock.now().checked_add(timeout);
+        match self.tick_until(front, deadline) {
+            Ok(tick) => Ok(tick.map(|(item, _)| item)),
+            Err(TimedOut) => Err(Timeout),
+        }
+    }
+
+    /// peek returns the next item without waiting for its tick. The item is
+    /// pulled from the source and held, so peeking again returns the same one,
+    /// and the tick is only taken when ````.next()```` returns it. Returns
+    /// None once the source is exhausted, and ````.next()```` then returns
+    /// None without waiting.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn send(_: String) {}
+    /// # let messages: Vec<String> = vec![];
+    /// let mut outbox = Ticker::new(messages.into_iter(), Duration::from_secs(1));
+    /// while let Some(message) = outbox.peek() {
+    ///     if message.is_empty() {
+    ///         outbox.skip_items(1);
+    ///         continue;
+    ///     }
+    ///     send(outbox.next().unwrap())
+    /// }
+    /// ````
+    pub fn peek(&mut self) -> Option<&I::Item> {
+        if self.done || self.shared.stopped.load(Ordering::SeqCst) {
+            return None;
+        }
+        if self.past(self.end()) || self.ticks_left() == Some(0) {
+            self.finish();
+            return None;
+        }
+        if self.item.is_none() {
+            self.item = self.src.next();
+            if self.item.is_none() {
+                self.finish();
+            }
+        }
+        self.item.as_ref()
+    }
+
+    /// skip_items discards the next ````n```` items of the source without
+    /// waiting for any ticks, and returns how many there were.
+    pub fn skip_items(&mut self, n: usize) -> usize {
+        if self.done || n == 0 {
+            return 0;
+        }
+        let held = self.item.take().is_some() as usize;
+        let skipped = held + self.src.by_ref().take(n - held).count();
+        if skipped < n {
+            self.finish();
+        }
+        skipped
+    }
+
+    /// timestamped turns this Ticker into an Iterator that yields each item
+    /// with the Instant its tick fired. The Instant is taken when the tick is
+    /// generated, so it is not skewed by how late the consumer calls
+    /// ````.next()````.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::{Duration, Instant};
+    /// # fn process(_: i32) {}
+    /// for (fired, job) in Ticker::new((0..), Duration::from_secs(1)).timestamped() {
+    ///     process(job);
+    ///     println!("job {} done {:?} after its tick", job, fired.elapsed())
+    /// }
+    /// ````
+    pub fn timestamped(self) -> Timestamped<I, P> {
+        Timestamped { ticker: self }
+    }
+
+    /// split takes this Ticker apart into its ticks and its source, so the
+    /// ticks can be waited on alongside other events. Each tick arrives on the
+    /// Receiver as the Instant it fired, and the source is handed back to pull
+    /// items from by hand, starting with any item a bounded wait held over.
+    ///
+    /// Pairing ticks with items is then up to the caller: taking a tick
+    /// without pulling an item, or pulling items without waiting for ticks,
+    /// is not rate limited. Up to ````max_pending```` ticks wait on the
+    /// Receiver (at most 1024) and later ones are dropped, as for a Ticker
+    /// with ````MissedTickBehavior::Burst````; other behaviors keep one. A
+    /// TickerHandle still adjusts the ticks, and ````stop()```` disconnects the
+    /// Receiver, as does splitting a Ticker whose source has already run out.
+    /// Ticks are forwarded from a thread spawned by split, which exits once
+    /// the Receiver is dropped.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::sync::mpsc::{channel, RecvTimeoutError};
+    /// # use std::time::Duration;
+    /// # fn process(_: i32) {}
+    /// let (shutdown, shutdown_recv) = channel::<()>();
+    /// # drop(shutdown);
+    /// let (ticks, mut jobs) = Ticker::new((0..), Duration::from_secs(1)).split();
+    /// while shutdown_recv.try_recv().is_err() {
+    ///     match ticks.recv_timeout(Duration::from_millis(10)) {
+    ///         Ok(_) => process(jobs.next().unwrap()),
+    ///         Err(RecvTimeoutError::Timeout) => continue,
+    ///         Err(RecvTimeoutError::Disconnected) => break,
+    ///     }
+    /// }
+    /// ````
+    pub fn split(mut self) -> (Receiver<Instant>, iter::Chain<option::IntoIter<I::Item>, I>) {
+        let (send, recv) = mpsc::sync_channel(self.config.pending_cap().min(FORWARD_PENDING_CAP));
+        let ticking = !self.done && !self.shared.stopped.load(Ordering::SeqCst);
+        if ticking {
+            if self.backend.is_none() {
+                self.config.inline = true;
+            }
+            self.backend();
+        }
+        let limit = self.ticks_left();
+        let Ticker { src, item, backend, .. } = self;
+        if let (true, Some(backend)) = (ticking, backend) {
+            forward(backend, limit, move |fired| !matches!(send.try_send(fired), Err(mpsc::TrySendError::Disconnected(_))));
+        }
+        (recv, item.into_iter().chain(src))
+    }
+
+    /// drain stops pacing and hands back the source, to finish the items left
+    /// in it as fast as they come: at shutdown, say, once the rate limit no
+    /// longer matters. The worker thread is stopped at once, and the items
+    /// start with any item ````peek()```` or a bounded wait held over. Items
+    /// that ````max_ticks````, ````for_duration```` or ````stop()```` cut off
+    /// are included.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn shutting_down() -> bool { true }
+    /// # fn upload(_: i32) {}
+    /// let mut uploads = Ticker::new((0..1000), Duration::from_secs(1));
+    /// while let Some(item) = uploads.next() {
+    ///     upload(item);
+    ///     if shutting_down() {
+    ///         break;
+    ///     }
+    /// }
+    /// uploads.drain().for_each(upload);
+    /// ````
+    pub fn drain(self) -> iter::Chain<option::IntoIter<I::Item>, I> {
+        let Ticker { src, item, .. } = self;
+        item.into_iter().chain(src)
+    }
+
+    /// into_inner stops the Ticker and returns its source, along with the
+    /// item ````peek()```` or a bounded wait took from it for the next tick,
+    /// if any, so that nothing is lost or taken twice. The worker thread is
+    /// stopped at once.
+    pub fn into_inner(self) -> (I, Option<I::Item>) {
+        let Ticker { src, item, .. } = self;
+        (src, item)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I: Iterator> Ticker<I> {
+    /// into_parts is ````into_inner()````, also returning a TickerBuilder
+    /// with this Ticker's settings, to wrap the source again or to make more
+    /// Tickers like it. Its interval is the one the Ticker had last, if it
+    /// was changed through a TickerHandle, and Tickers it builds share this
+    /// one's Clock.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn crawl(_: i32) {}
+    /// let mut pages = Ticker::new(0.., Duration::from_secs(1));
+    /// pages.by_ref().take(10).for_each(crawl);
+    /// let (rest, held, builder) = pages.into_parts();
+    /// assert!(held.is_none());
+    /// for page in builder.first_tick_immediately(true).wrap(rest) {
+    ///     crawl(page)
+    /// }
+    /// ````
+    pub fn into_parts(self) -> (I, Option<I::Item>, TickerBuilder) {
+        let mut config = self.config.clone();
+        config.interval = self.interval();
+        let Ticker { src, item, shared, .. } = self;
+        (src, item, TickerBuilder::with_config(config, shared.clock.clone()))
+    }
+}
+
+/// FORWARD_PENDING_CAP bounds how many forwarded ticks wait in their channel,
+/// since it is allocated up front.
+#[cfg(feature = "std")]
+const FORWARD_PENDING_CAP: usize = 1024;
+
+/// forward spawns a thread that waits out the ticks of ````backend```` and
+/// passes each one to ````send````, until it returns false, the ticks end or
+/// ````limit```` ticks have been passed on.
+#[cfg(feature = "std")]
+fn forward<F>(mut backend: Backend, limit: Option<usize>, mut send: F)
+where
+    F: FnMut(Instant) -> bool + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut left = limit;
+        while left != Some(0) {
+            let fired = match backend.wait_until(None, 1) {
+                Wait::Tick(fired) => fired,
+                _ => return,
+            };
+            if !send(fired) {
+                return;
+            }
+            left = left.map(|left| left - 1);
+        }
+    });
+}
+
+/// front pulls the next item from the front: the held over one, if any, then
+/// the source's.
+#[cfg(feature = "std")]
+fn front<I: Iterator>(src: &mut I, held: &mut Option<I::Item>) -> Option<I::Item> {
+    held.take().or_else(|| src.next())
+}
+
+/// back pulls the next item from the back, where the held over item is last.
+#[cfg(feature = "std")]
+fn back<I: DoubleEndedIterator>(src: &mut I, held: &mut Option<I::Item>) -> Option<I::Item> {
+    src.next_back().or_else(|| held.take())
+}
+
+/// nth pulls the nth item from the front.
+#[cfg(feature = "std")]
+fn nth<I: Iterator>(src: &mut I, held: &mut Option<I::Item>, n: usize) -> Option<I::Item> {
+    match held.take() {
+        Some(item) if n == 0 => Some(item),
+        Some(_) => src.nth(n - 1),
+        None => src.nth(n),
+    }
+}
+
+/// NotDue is returned by ````try_next()```` when the next tick is not due yet.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NotDue;
+
+#[cfg(feature = "std")]
+impl fmt::Display for NotDue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("the next tick is not due yet")
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for NotDue {}
+
+/// Timeout is returned by ````next_timeout()```` when no tick came in time.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timeout;
+
+#[cfg(feature = "std")]
+impl fmt::Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("timed out waiting for a tick")
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for Timeout {}
+
+/// TimedOut is a bounded wait for a tick giving up.
+#[cfg(feature = "std")]
+struct TimedOut;
+
+/// Wait is how waiting for a tick ended.
+#[cfg(feature = "std")]
+enum Wait {
+    /// Tick is a tick, with when it fired.
+    Tick(Instant),
+    /// Ended is the Ticker being stopped, or losing its worker.
+    Ended,
+    TimedOut,
+}
+
+/// Cloning a Ticker makes another that carries on from the same point on its
+/// own: a clone of the source, any item held for the next tick, and what is
+/// left of ````max_ticks()````, ````for_duration()```` and
+/// ````initial_burst()````. Its schedule starts from the moment of cloning,
+/// at the current interval, with its own worker thread, handles and stats, so
+/// pausing, stopping or dropping one never affects the other.
+///
+/// A TickHandle only ticks the Ticker it came with, so a clone of a Ticker
+/// from ````Ticker::manual```` ends at once, as one whose TickHandles are all
+/// gone does.
+#[cfg(feature = "std")]
+impl<I, P> Clone for Ticker<I, P>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+    P: Pacer + Clone + Send + 'static,
+{
+    fn clone(&self) -> Self {
+        let now = self.shared.clock.now();
+        let mut config = self.config.clone();
+        config.interval = self.interval();
+        config.max_ticks = self.ticks_left();
+        config.run_for = self.end().map(|end| end.saturating_duration_since(now));
+        config.initial_burst = config.initial_burst.saturating_sub(self.burst);
+        let mut pacer = pacer::lock(&self.pacer).clone();
+        if self.paced() {
+            if let Some(interval) = *self.shared.interval.lock().unwrap_or_else(|e| e.into_inner()) {
+                pacer.set_interval(interval);
+            }
+        }
+        let mut ticker = Ticker::with_pacer(self.src.clone(), pacer);
+        ticker.config = config;
+        ticker.cost = self.cost.clone();
+        ticker.shared = Arc::new(Shared::with_clock(self.shared.clock.clone()));
+        ticker.start = ticker.shared.clock.now();
+        ticker.item = self.item.clone();
+        ticker.done = self.done || self.shared.stopped.load(Ordering::SeqCst);
+        if ticker.config.manual == Manual::Only {
+            let _ = ticker.ctrl.send(Control::Hangup);
+        }
+        ticker
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I: Iterator, P: Pacer + Send + 'static> Iterator for Ticker<I, P> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_tick().map(|(item, _)| item)
+    }
+
+    /// nth skips ````n```` items in the source directly, only waiting for the
+    /// tick of the item it returns.
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.tick_with(|src, held| nth(src, held, n)).map(|(item, _)| item)
+    }
+
+    /// count does not wait for any ticks, since no items are returned, unless
+    /// the Ticker runs ````for_duration()````: then how many items it returns
+    /// depends on the ticks.
+    fn count(mut self) -> usize {
+        if self.done || self.shared.stopped.load(Ordering::SeqCst) {
+            return 0;
+        }
+        if self.config.run_for.is_some() {
+            return self.by_ref().fold(0, |n, _| n + 1);
+        }
+        let held = self.item.is_some() as usize;
+        match self.ticks_left() {
+            Some(left) => left.min(held) + self.src.take(left.saturating_sub(held)).count(),
+            None => held + self.src.count(),
+        }
+    }
+
+    /// last does not wait for any ticks; only one item is returned, at once.
+    /// As with ````count()````, a Ticker that runs ````for_duration()```` is
+    /// the exception.
+    fn last(mut self) -> Option<Self::Item> {
+        if self.done || self.shared.stopped.load(Ordering::SeqCst) {
+            return None;
+        }
+        if self.config.run_for.is_some() {
+            return self.by_ref().fold(None, |_, item| Some(item));
+        }
+        match self.ticks_left() {
+            Some(0) => None,
+            Some(left) => {
+                let held = self.item.is_some() as usize;
+                self.src.take(left - held).last().or(self.item)
+            }
+            None => self.src.last().or(self.item),
+        }
+    }
+
+    /// size_hint is the source's, except that an endless source's lower
+    /// bound is not passed on, since such a Ticker ends only when stopped,
+    /// and neither bound goes past ````max_ticks()````.
+    /// ````len()```` is exact unless the Ticker is stopped or runs
+    /// ````for_duration()````.
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done || self.shared.stopped.load(Ordering::SeqCst) {
+            return (0, Some(0));
+        }
+        let held = self.item.is_some() as usize;
+        let (lo, hi) = match self.src.size_hint() {
+            (_, None) => (0, None),
+            (lo, hi) => (lo.saturating_add(held), hi.and_then(|hi| hi.checked_add(held))),
+        };
+        match self.ticks_left() {
+            Some(left) => (lo.min(left), Some(hi.map_or(left, |hi| hi.min(left)))),
+            None => (lo, hi),
+        }
+    }
+}
+
+/// next_back takes from the back of the source, and shares the same ticks as
+/// ````.next()````.
+#[cfg(feature = "std")]
+impl<I: DoubleEndedIterator, P: Pacer + Send + 'static> DoubleEndedIterator for Ticker<I, P> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.tick_with(back).map(|(item, _)| item)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I: ExactSizeIterator, P: Pacer + Send + 'static> ExactSizeIterator for Ticker<I, P> {}
+
+/// A Ticker is always fused, whatever its source.
+#[cfg(feature = "std")]
+impl<I: Iterator, P: Pacer + Send + 'static> FusedIterator for Ticker<I, P> {}
+
+/// Worker sends ticks to a Ticker from its own thread. Ticks are scheduled
+/// against absolute deadlines, so time spent sending does not push later ticks
+/// back. At most ````max_pending```` ticks are ever waiting in the channel.
+#[cfg(feature = "std")]
+struct Worker {
+    interval: Duration,
+    behavior: MissedTickBehavior,
+    resume: Resume,
+    spin: Duration,
+    max_pending: usize,
+    /// deadline is when the next tick is due before jitter, or None if it
+    /// never is.
+    deadline: Option<Instant>,
+    jitter: Jitter,
+    /// offset is the jitter for the tick at deadline.
+    offset: Offset,
+    /// epoch is the Shared epoch the worker last sent a tick in.
+    epoch: usize,
+    /// pacer is asked when each tick is due instead, if the Ticker has one.
+    pacer: Option<SharedPacer>,
+    hooks: Hooks,
+    shared: Arc<Shared>,
+    send: Sender<Tick>,
+    ctrl: Receiver<Control>,
+}
+
+#[cfg(feature = "std")]
+impl Worker {
+    fn run(mut self) {
+        let span = self.hooks.span.clone();
+        span.run(|| self.work())
+    }
+
+    /// work sends ticks until the worker should stop, and says why.
+    fn work(&mut self) -> Exit {
+        if let Some(pacer) = self.pacer.clone() {
+            return self.pace(&pacer);
+        }
+        loop {
+            if self.interval == Duration::ZERO {
+                if let Err(exit) = self.wait_for_limit() {
+                    return exit;
+                }
+                self.deadline = self.shared.clock.now().checked_add(self.interval);
+            }
+            let mut slept = false;
+            let (deadline, due) = loop {
+                let deadline = self.deadline.map(|deadline| (deadline, self.offset.apply(deadline)));
+                let control = match deadline {
+                    Some((_, due)) if self.shared.clock.now() < due => {
+                        recv_until(&self.ctrl, &*self.shared.clock, due, self.spin)
+                    }
+                    Some(deadline) => break deadline,
+                    None => self.ctrl.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                };
+                slept = true;
+                match control {
+                    Ok(control) => {
+                        if !self.apply(control) {
+                            return self.killed();
+                        }
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => return Exit::SenderDropped,
+                }
+            };
+
+            let epoch = self.shared.epoch.load(Ordering::SeqCst);
+            if Shared::paused(epoch) {
+                // Sleep until resumed; Resume moves the deadline.
+                loop {
+                    match self.ctrl.recv() {
+                        Ok(Control::Resume(at)) => {
+                            self.deadline = at.checked_add(self.interval);
+                            break;
+                        }
+                        Ok(control) => {
+                            if !self.apply(control) {
+                                return self.killed();
+                            }
+                        }
+                        Err(_) => return Exit::SenderDropped,
+                    }
+                }
+                continue;
+            }
+
+            if epoch != self.epoch {
+                self.epoch = epoch;
+                self.shared.pending.store(0, Ordering::SeqCst);
+            }
+            let now = self.shared.clock.now();
+            let mut deadline = deadline;
+            if let Some(gap) = self.resume.gap(self.interval, due, now).filter(|_| slept) {
+                self.hooks.resumed(gap);
+                if self.resume.behavior != MissedTickBehavior::Burst {
+                    // Tick now, from the deadline the policy carries on after.
+                    let (next, dropped) = pace::catch_up(self.resume.behavior, 1, self.interval, deadline, now);
+                    deadline = next - self.interval;
+                    self.hooks.missed(u64::from(dropped));
+                }
+            }
+            if self.shared.pending.load(Ordering::SeqCst) < self.max_pending {
+                if !self.fire(epoch) {
+                    return Exit::ReceiverDropped;
+                }
+            } else {
+                let mut missed = 1;
+                if let Some(behind) = self.shared.clock.now().checked_duration_since(deadline) {
+                    // Every tick due by now would be dropped too, so skip
+                    // past them at once rather than waking for each.
+                    let behind = (behind.as_nanos() / self.interval.as_nanos()).min(u32::MAX as u128) as u32;
+                    deadline += self.interval * behind;
+                    missed += u64::from(behind);
+                }
+                self.hooks.missed(missed);
+            }
+            self.deadline = deadline.checked_add(self.interval);
+            if self.behavior == MissedTickBehavior::Delay && matches!(self.deadline, Some(next) if next <= self.shared.clock.now()) {
+                // Fell a whole tick behind, as when the first tick comes
+                // long after the Ticker was created: carry on from now
+                // rather than catching up.
+                self.deadline = self.shared.clock.now().checked_add(self.interval);
+            }
+            self.offset = self.jitter.offset(self.interval);
+        }
+    }
+
+    /// wait_for_limit applies Controls while the interval is zero, since
+    /// there are no ticks to send until it changes. A WorkerHandle may have
+    /// started waiting on a tick while paused, so one is sent whenever the
+    /// Ticker is running and none is pending. It returns why if the worker
+    /// should stop.
+    fn wait_for_limit(&mut self) -> Result<(), Exit> {
+        while self.interval == Duration::ZERO {
+            let epoch = self.shared.epoch.load(Ordering::SeqCst);
+            if epoch != self.epoch {
+                self.epoch = epoch;
+                self.shared.pending.store(0, Ordering::SeqCst);
+            }
+            if !Shared::paused(epoch) && self.shared.pending.load(Ordering::SeqCst) < self.max_pending && !self.fire(epoch) {
+                return Err(Exit::ReceiverDropped);
+            }
+            match self.ctrl.recv() {
+                Ok(control) => {
+                    if !self.apply(control) {
+                        return Err(self.killed());
+                    }
+                }
+                Err(_) => return Err(Exit::SenderDropped),
+            }
+        }
+        Ok(())
+    }
+
+    /// pace sends a tick whenever the Pacer says, for a Ticker with one. It
+    /// only asks for a tick once there is room for it to wait, so that none
+    /// the Pacer lets through is dropped while the consumer is busy.
+    fn pace(&mut self, pacer: &SharedPacer) -> Exit {
+        loop {
+            let epoch = self.shared.epoch.load(Ordering::SeqCst);
+            if epoch != self.epoch {
+                self.epoch = epoch;
+                self.shared.pending.store(0, Ordering::SeqCst);
+            }
+            if Shared::paused(epoch) || self.shared.pending.load(Ordering::SeqCst) >= self.max_pending {
+                // Taken or Resume makes room.
+                match self.ctrl.recv() {
+                    Ok(control) => {
+                        if !self.apply(control) {
+                            return self.killed();
+                        }
+                    }
+                    Err(_) => return Exit::SenderDropped,
+                }
+                continue;
+            }
+
+            let now = self.shared.clock.now();
+            let due = Due::since(now, pacer::lock(pacer).next_delay(now, 1));
+            loop {
+                let control = match due {
+                    Due::At(due) if self.shared.clock.now() < due => {
+                        recv_until(&self.ctrl, &*self.shared.clock, due, self.spin)
+                    }
+                    Due::Never => self.ctrl.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                    _ => break,
+                };
+                match control {
+                    Ok(control) => {
+                        if !self.apply(control) {
+                            return self.killed();
+                        }
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => return Exit::SenderDropped,
+                }
+            }
+            // A tick let through while paused waits for the resume.
+            while Shared::paused(self.shared.epoch.load(Ordering::SeqCst)) {
+                match self.ctrl.recv() {
+                    Ok(control) => {
+                        if !self.apply(control) {
+                            return self.killed();
+                        }
+                    }
+                    Err(_) => return Exit::SenderDropped,
+                }
+            }
+            let epoch = self.shared.epoch.load(Ordering::SeqCst);
+            if epoch != self.epoch {
+                self.epoch = epoch;
+                self.shared.pending.store(0, Ordering::SeqCst);
+            }
+            if !self.fire(epoch) {
+                return Exit::ReceiverDropped;
+            }
+        }
+    }
+
+    /// fire sends a tick in ````epoch````, returning false if the Ticker is
+    /// gone.
+    fn fire(&mut self, epoch: usize) -> bool {
+        self.shared.pending.fetch_add(1, Ordering::SeqCst);
+        let tick = Tick {
+            fired: self.shared.clock.now(),
+            epoch,
+        };
+        self.shared.stats.fired(tick.fired);
+        self.hooks.fired(tick.fired);
+        self.send.send(tick).is_ok()
+    }
+
+    /// killed is why the worker was sent Kill.
+    fn killed(&self) -> Exit {
+        #[cfg(feature = "tracing")]
+        if self.shared.exhausted.load(Ordering::SeqCst) {
+            return Exit::Exhausted;
+        }
+        Exit::Killed
+    }
+
+    /// apply handles a Control, returning false if the worker should stop.
+    fn apply(&mut self, control: Control) -> bool {
+        match control {
+            Control::Kill => return false,
+            Control::Delay(at) | Control::Resume(at) => self.deadline = at.checked_add(self.interval),
+            Control::SetInterval(interval) => match self.pacer {
+                Some(ref pacer) => pacer::lock(pacer).set_interval(interval),
+                None => {
+                    self.deadline = match self.deadline {
+                        Some(deadline) => rebase(deadline, self.interval, interval),
+                        None => self.shared.clock.now().checked_add(interval),
+                    };
+                    self.interval = interval;
+                }
+            },
+            Control::Pause | Control::Tick | Control::Hangup | Control::Taken => {}
+        }
+        true
+    }
+}
+
+/// WorkerHandle is the Ticker's end of a Worker; dropping it stops the
+/// Worker.
+#[cfg(feature = "std")]
+struct WorkerHandle {
+    behavior: MissedTickBehavior,
+    /// paced is whether the worker asks a Pacer when each tick is due.
+    paced: bool,
+    /// hooks are run for the ticks let through while unlimited, which the
+    /// worker does not send.
+    hooks: Hooks,
+    shared: Arc<Shared>,
+    recv: Receiver<Tick>,
+    ctrl: Sender<Control>,
+}
+
+#[cfg(feature = "std")]
+impl WorkerHandle {
+    fn spawn(config: &Config,
+             (ctrl, ctrl_recv): (Sender<Control>, Receiver<Control>),
+             shared: Arc<Shared>,
+             first: Option<Instant>,
+             pacer: Option<SharedPacer>)
+             -> io::Result<Self> {
+        let (send, recv) = channel::<Tick>();
+        let mut jitter = config.jitter();
+        let paced = pacer.is_some();
+        let worker = Worker {
+            interval: config.interval,
+            behavior: config.missed_tick_behavior,
+            resume: config.resume,
+            spin: config.spin,
+            max_pending: config.pending_cap(),
+            deadline: first,
+            offset: jitter.offset(config.interval),
+            jitter,
+            epoch: shared.epoch.load(Ordering::SeqCst),
+            pacer,
+            hooks: config.hooks.clone(),
+            shared: shared.clone(),
+            send,
+            ctrl: ctrl_recv,
+        };
+        config.thread().spawn(move || worker.run())?;
+
+        Ok(WorkerHandle {
+            behavior: config.missed_tick_behavior,
+            paced,
+            hooks: config.hooks.clone(),
+            shared,
+            recv,
+            ctrl,
+        })
+    }
+
+    /// wait_until blocks until the next tick, or ````deadline```` if there is
+    /// one. Waiting ends if the Ticker is stopped or the worker thread has
+    /// gone.
+    fn wait_until(&mut self, deadline: Option<Instant>) -> Wait {
+        let arrived = self.shared.clock.now();
+        let unlimited = !self.paced && self.shared.unlimited.load(Ordering::SeqCst);
+        if unlimited && !Shared::paused(self.shared.epoch.load(Ordering::SeqCst)) {
+            self.shared.stats.fired(arrived);
+            self.hooks.fired(arrived);
+            return Wait::Tick(arrived);
+        }
+        loop {
+            let tick = match deadline {
+                None => match self.recv.recv() {
+                    Ok(tick) => tick,
+                    Err(_) => return Wait::Ended,
+                },
+                Some(deadline) => match self.recv.recv_timeout(self.shared.clock.timeout(deadline)) {
+                    Ok(tick) => tick,
+                    Err(RecvTimeoutError::Disconnected) => return Wait::Ended,
+                    Err(RecvTimeoutError::Timeout) => {
+                        if self.shared.clock.now() >= deadline {
+                            return Wait::TimedOut;
+                        }
+                        continue;
+                    }
+                },
+            };
+            if tick.epoch != self.shared.epoch.load(Ordering::SeqCst) {
+                continue;
+            }
+            self.shared.pending.fetch_sub(1, Ordering::SeqCst);
+            if self.paced {
+                let _ = self.ctrl.send(Control::Taken);
+            } else if tick.fired < arrived && self.behavior == MissedTickBehavior::Delay {
+                let _ = self.ctrl.send(Control::Delay(self.shared.clock.now()));
+            }
+            return Wait::Tick(tick.fired);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for WorkerHandle {
+    fn drop(&mut self) {
+        let _ = self.ctrl.send(Control::Kill);
+    }
+}
+
+/// Schedule paces an inline Ticker by sleeping in ````.next()````. It keeps
+/// the same deadlines a Worker would, including which missed ticks would have
+/// been waiting for a late consumer.
+#[cfg(feature = "std")]
+struct Schedule {
+    interval: Duration,
+    behavior: MissedTickBehavior,
+    resume: Resume,
+    max_pending: usize,
+    /// next is when the next tick is due before jitter, or None if it is too
+    /// far off for an Instant to hold, so it never comes.
+    next: Option<Instant>,
+    jitter: Jitter,
+    /// offset is the jitter for the tick at next.
+    offset: Offset,
+    paused: bool,
+    /// resting is None under ````Pacing::Fixed````. Under ````MinGap```` it is
+    /// set after a tick, until the consumer comes back and the interval to
+    /// the next one starts.
+    resting: Option<bool>,
+    /// timed is false for a Ticker that only ticks by hand.
+    timed: bool,
+    /// manual counts ticks sent by a TickHandle that have not been taken.
+    manual: u64,
+    /// hung_up is set once the last TickHandle is dropped.
+    hung_up: bool,
+    spin: Duration,
+    /// pacer is asked when each tick is due instead, if the Ticker has one.
+    pacer: Option<SharedPacer>,
+    /// asked is when the tick last asked of the pacer is due, until it is
+    /// taken.
+    asked: Option<Due>,
+    hooks: Hooks,
+    clock: Arc<dyn Clock>,
+}
+
+#[cfg(feature = "std")]
+impl Schedule {
+    fn new(config: &Config, clock: Arc<dyn Clock>, first: Option<Instant>, pacer: Option<SharedPacer>) -> Self {
+        let mut jitter = config.jitter();
+        Schedule {
+            clock,
+            interval: config.interval,
+            behavior: config.missed_tick_behavior,
+            resume: config.resume,
+            max_pending: config.pending_cap(),
+            next: first,
+            offset: jitter.offset(config.interval),
+            jitter,
+            paused: false,
+            resting: match config.pacing {
+                Pacing::Fixed => None,
+                Pacing::MinGap => Some(false),
+            },
+            timed: config.manual != Manual::Only,
+            manual: 0,
+            hung_up: false,
+            spin: if THREADS { config.spin } else { Duration::MAX },
+            pacer,
+            asked: None,
+            hooks: config.hooks.clone(),
+        }
+    }
+
+    /// due is when the next tick fires, after jitter, or None if it never
+    /// does, or is for a pacer that has not been asked.
+    fn due(&self) -> Option<Instant> {
+        match (&self.pacer, self.asked) {
+            (None, _) => self.next.map(|next| self.offset.apply(next)),
+            (Some(_), Some(Due::At(due))) => Some(due),
+            (Some(_), _) => None,
+        }
+    }
+
+    /// wait_until sleeps until the next tick, or ````deadline```` if there is
+    /// one, applying any Controls sent meanwhile. Waiting ends if sent Kill.
+    /// A pacer is asked about the tick at ````cost````.
+    fn wait_until(&mut self, ctrl: &Receiver<Control>, deadline: Option<Instant>, cost: u32) -> Wait {
+        while let Ok(control) = ctrl.try_recv() {
+            if self.apply(control).is_none() {
+                return Wait::Ended;
+            }
+        }
+        if let Some(true) = self.resting {
+            self.resting = Some(false);
+            self.next = self.clock.now().checked_add(self.interval);
+        }
+        let mut slept = false;
+        loop {
+            let now = self.clock.now();
+            if self.manual > 0 {
+                self.manual -= 1;
+                self.hooks.fired(now);
+                return Wait::Tick(now);
+            }
+            if self.hung_up && !self.timed {
+                return Wait::Ended;
+            }
+            let idle = self.paused || !self.timed;
+            if let (Some(pacer), None, false) = (&self.pacer, self.asked, idle) {
+                self.asked = Some(Due::since(now, pacer::lock(pacer).next_delay(now, cost)));
+            }
+            let due = self.due();
+            if let Some(due) = due.filter(|&due| !idle && now >= due) {
+                return Wait::Tick(self.take(due, now, slept, cost));
+            }
+            let wake = match (deadline, due) {
+                (Some(deadline), _) if now >= deadline => return Wait::TimedOut,
+                (Some(deadline), Some(due)) if !idle && due <= deadline => Some(due),
+                (Some(deadline), _) => Some(deadline),
+                (None, _) if idle => None,
+                (None, due) => due,
+            };
+            slept = true;
+            let control = match wake {
+                None => ctrl.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                Some(wake) => recv_until(ctrl, &*self.clock, wake, self.spin),
+            };
+            match control {
+                Ok(control) => {
+                    if self.apply(control).is_none() {
+                        return Wait::Ended;
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => match wake {
+                    Some(wake) => {
+                        if let Some(coarse) = wake.checked_sub(self.spin) {
+                            thread::sleep(self.clock.timeout(coarse))
+                        }
+                    }
+                    None if !self.timed => return Wait::Ended,
+                    None => self.paused = false,
+                },
+            }
+        }
+    }
+
+    /// take takes the tick due at ````due```` for a consumer at ````now````,
+    /// who ````slept```` waiting for it, for an item costing ````cost````,
+    /// and returns when it was due.
+    fn take(&mut self, due: Instant, now: Instant, slept: bool, cost: u32) -> Instant {
+        if self.pacer.is_some() {
+            self.asked = None;
+            self.hooks.fired(due);
+            return due;
+        }
+        let behavior = match self.resume.gap(self.interval, due, now) {
+            Some(gap) if slept => {
+                self.hooks.resumed(gap);
+                match self.resume.behavior {
+                    MissedTickBehavior::Burst => self.behavior,
+                    behavior => behavior,
+                }
+            }
+            _ => self.behavior,
+        };
+        let taken = self.tick_with(now, behavior, cost);
+        taken.unwrap_or(due)
+    }
+
+    /// apply handles a Control, returning None for Kill.
+    fn apply(&mut self, control: Control) -> Option<()> {
+        match control {
+            Control::SetInterval(interval) => match self.pacer {
+                Some(ref pacer) => pacer::lock(pacer).set_interval(interval),
+                None => {
+                    self.next = match self.next {
+                        Some(next) => rebase(next, self.interval, interval),
+                        None => self.clock.now().checked_add(interval),
+                    };
+                    self.interval = interval;
+                }
+            },
+            Control::Pause => self.paused = true,
+            Control::Resume(at) => {
+                self.paused = false;
+                self.next = at.checked_add(self.interval);
+            }
+            Control::Tick => self.manual += 1,
+            Control::Hangup => self.hung_up = true,
+            Control::Delay(_) | Control::Taken => {}
+            Control::Kill => return None,
+        }
+        Some(())
+    }
+
+    /// tick_at takes the next tick for a consumer arriving at ````now```` and
+    /// returns when it is due, or None if it never is.
+    #[cfg(any(test, feature = "stream"))]
+    fn tick_at(&mut self, now: Instant) -> Option<Instant> {
+        self.tick_with(now, self.behavior, 1)
+    }
+
+    /// tick_with is ````tick_at()````, catching up on any missed ticks as
+    /// ````behavior```` says, for an item costing ````cost```` intervals.
+    fn tick_with(&mut self, now: Instant, behavior: MissedTickBehavior, cost: u32) -> Option<Instant> {
+        let next = self.next?;
+        let due = self.offset.apply(next);
+        // The next tick is ````cost```` intervals after the slot taken.
+        let after = |slot: Instant, interval: Duration| interval.checked_mul(cost).and_then(|taken| slot.checked_add(taken));
+        if self.resting.is_some() {
+            self.resting = Some(true);
+        } else if now < due || self.interval == Duration::ZERO {
+            self.next = after(next, self.interval);
+        } else if now.checked_add(self.interval).is_some() {
+            // The consumer is late; the ticks due since then are the ones a
+            // Worker would have sent meanwhile.
+            let (next, dropped) = pace::catch_up(behavior, self.max_pending, self.interval, next, now);
+            self.next = after(next - self.interval, self.interval);
+            self.hooks.missed(u64::from(dropped));
+        } else {
+            // Even the tick after this one would be past what an Instant
+            // can hold.
+            self.next = None;
+        }
+        self.offset = self.jitter.offset(self.interval);
+        self.hooks.fired(due);
+        Some(due)
+    }
+}
+
+/// Backend is how a Ticker waits for its ticks.
+#[cfg(feature = "std")]
+enum Backend {
+    Thread(WorkerHandle),
+    Inline {
+        schedule: Schedule,
+        ctrl: Receiver<Control>,
+        shared: Arc<Shared>,
+    },
+    /// Ticks takes ticks forwarded for ````Ticker::ticks()````, in turn with
+    /// whoever else is receiving them.
+    #[cfg(feature = "crossbeam")]
+    Ticks {
+        recv: crossbeam_channel::Receiver<Instant>,
+        clock: Arc<dyn Clock>,
+    },
+}
+
+#[cfg(feature = "std")]
+impl Backend {
+    /// wait_until waits for the next tick, for an item costing ````cost````,
+    /// or until ````deadline```` if there is one.
+    fn wait_until(&mut self, deadline: Option<Instant>, cost: u32) -> Wait {
+        match *self {
+            Backend::Thread(ref mut worker) => worker.wait_until(deadline),
+            Backend::Inline { ref mut schedule, ref ctrl, ref shared } => {
+                let wait = schedule.wait_until(ctrl, deadline, cost);
+                if let Wait::Tick(due) = wait {
+                    shared.stats.fired(due);
+                }
+                wait
+            }
+            #[cfg(feature = "crossbeam")]
+            Backend::Ticks { ref recv, ref clock } => ticks::wait_until(recv, &**clock, deadline),
+        }
+    }
+}
+
+/// TickIter is a rate limited Iterator, as returned by ````.ticked()````. It is
+/// a Ticker that has already been configured, and behaves just like one.
+#[cfg(feature = "std")]
+pub struct TickIter<I: Iterator, P = FixedInterval> {
+    ticker: Ticker<I, P>,
+}
+
+#[cfg(feature = "std")]
+impl<I: Iterator, P: Pacer + Send + 'static> TickIter<I, P> {
+    /// handle returns a TickerHandle to adjust this TickIter from another
+    /// thread.
+    pub fn handle(&self) -> TickerHandle {
+        self.ticker.handle()
+    }
+
+    /// stats is ````Ticker::stats````.
+    pub fn stats(&self) -> TickerStats {
+        self.ticker.stats()
+    }
+
+    /// interval is ````Ticker::interval````.
+    pub fn interval(&self) -> Duration {
+        self.ticker.interval()
+    }
+
+    /// ticks_elapsed is ````Ticker::ticks_elapsed````.
+    pub fn ticks_elapsed(&self) -> u64 {
+        self.ticker.ticks_elapsed()
+    }
+
+    /// time_until_next_tick is ````Ticker::time_until_next_tick````.
+    pub fn time_until_next_tick(&self) -> Duration {
+        self.ticker.time_until_next_tick()
+    }
+
+    /// peek is ````Ticker::peek````.
+    pub fn peek(&mut self) -> Option<&I::Item> {
+        self.ticker.peek()
+    }
+
+    /// skip_items is ````Ticker::skip_items````.
+    pub fn skip_items(&mut self, n: usize) -> usize {
+        self.ticker.skip_items(n)
+    }
+
+    /// next_timeout is ````Ticker::next_timeout````.
+    pub fn next_timeout(&mut self, timeout: Duration) -> Result<Option<I::Item>, Timeout> {
+        self.ticker.next_timeout(timeout)
+    }
+
+    /// try_next is ````Ticker::try_next````.
+    pub fn try_next(&mut self) -> Result<Option<I::Item>, NotDue> {
+        self.ticker.try_next()
+    }
+
+    /// timestamped is ````Ticker::timestamped````.
+    pub fn timestamped(self) -> Timestamped<I, P> {
+        self.ticker.timestamped()
+    }
+
+    /// split is ````Ticker::split````.
+    pub fn split(self) -> (Receiver<Instant>, iter::Chain<option::IntoIter<I::Item>, I>) {
+        self.ticker.split()
+    }
+
+    /// drain is ````Ticker::drain````.
+    pub fn drain(self) -> iter::Chain<option::IntoIter<I::Item>, I> {
+        self.ticker.drain()
+    }
+
+    /// into_inner is ````Ticker::into_inner````.
+    pub fn into_inner(self) -> (I, Option<I::Item>) {
+        self.ticker.into_inner()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I: Iterator> TickIter<I> {
+    /// into_parts is ````Ticker::into_parts````.
+    pub fn into_parts(self) -> (I, Option<I::Item>, TickerBuilder) {
+        self.ticker.into_parts()
+    }
+}
+
+/// Cloning a TickIter clones its Ticker.
+#[cfg(feature = "std")]
+impl<I, P> Clone for TickIter<I, P>
+where
+    I: Iterator + Clone,
+    I::Item: Clone,
+    P: Pacer + Clone + Send + 'static,
+{
+    fn clone(&self) -> Self {
+        TickIter {
+            ticker: self.ticker.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I: Iterator, P: Pacer + Send + 'static> Iterator for TickIter<I, P> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.ticker.next()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.ticker.nth(n)
+    }
+
+    fn count(self) -> usize {
+        self.ticker.count()
+    }
+
+    fn last(self) -> Option<Self::Item> {
+        self.ticker.last()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ticker.size_hint()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I: DoubleEndedIterator, P: Pacer + Send + 'static> DoubleEndedIterator for TickIter<I, P> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.ticker.next_back()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I: ExactSizeIterator, P: Pacer + Send + 'static> ExactSizeIterator for TickIter<I, P> {}
+
+#[cfg(feature = "std")]
+impl<I: Iterator, P: Pacer + Send + 'static> FusedIterator for TickIter<I, P> {}
+
+/// Timestamped is a Ticker that yields ````(Instant, item)```` pairs, where
+/// the Instant is when the item's tick fired; derive this from Ticker with
+/// ````.timestamped()````.
+#[cfg(feature = "std")]
+pub struct Timestamped<I: Iterator, P = FixedInterval> {
+    ticker: Ticker<I, P>,
+}
+
+#[cfg(feature = "std")]
+impl<I: Iterator, P: Pacer + Send + 'static> Timestamped<I, P> {
+    /// handle returns a TickerHandle to adjust this Timestamped from another
+    /// thread.
+    pub fn handle(&self) -> TickerHandle {
+        self.ticker.handle()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I: Iterator, P: Pacer + Send + 'static> Iterator for Timestamped<I, P> {
+    type Item = (Instant, I::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.ticker.next_tick().map(|(item, fired)| (fired, item))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.ticker.tick_with(|src, held| nth(src, held, n)).map(|(item, fired)| (fired, item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ticker.size_hint()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I: DoubleEndedIterator, P: Pacer + Send + 'static> DoubleEndedIterator for Timestamped<I, P> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.ticker.tick_with(back).map(|(item, fired)| (fired, item))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I: ExactSizeIterator, P: Pacer + Send + 'static> ExactSizeIterator for Timestamped<I, P> {}
+
+#[cfg(feature = "std")]
+impl<I: Iterator, P: Pacer + Send + 'static> FusedIterator for Timestamped<I, P> {}
+
+/// Every is an endless Iterator of ticks, yielding the Instant each one fired;
+/// create one with ````Ticker::every````. Like a Ticker it is paced by a worker
+/// thread, which stops when the Every is dropped.
+#[cfg(feature = "std")]
+pub struct Every {
+    ticker: Ticker<iter::Repeat<()>>,
+}
+
+#[cfg(feature = "std")]
+impl Every {
+    /// handle returns a TickerHandle to adjust this Every from another thread.
+    pub fn handle(&self) -> TickerHandle {
+        self.ticker.handle()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Iterator for Every {
+    type Item = Instant;
+
+    fn next(&mut self) -> Option<Instant> {
+        self.ticker.next_tick().map(|(_, fired)| fired)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ticker.size_hint()
+    }
+}
+
+#[cfg(feature = "std")]
+impl FusedIterator for Every {}
+
+/// TickedIterator rate limits any Iterator in place, so a ticker can sit in the
+/// middle of an adapter chain.
+///
+/// Print every other number, one per second, stopping after five:
+///
+/// ````no_run
+/// # use std::time::Duration;
+/// use ticker::TickedIterator;
+///
+/// let evens = (0..).filter(|i| i % 2 == 0).ticked(Duration::from_secs(1));
+/// for (n, i) in evens.take(5).enumerate() {
+///     println!("{}: {:?}", n, i)
+/// }
+/// ````
+#[cfg(feature = "std")]
+pub trait TickedIterator: Iterator + Sized {
+    /// ticked rate limits this Iterator, returning from ````.next()```` at most
+    /// once every ````interval````. It is the same as
+    /// ````Ticker::new(self, interval)````.
+    fn ticked(self, interval: Duration) -> TickIter<Self> {
+        TickIter {
+            ticker: Ticker::new(self, interval),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I: Iterator> TickedIterator for I {}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    fn assert_send<T: Send>() {}
+    fn assert_sync<T: Sync>() {}
+
+    #[test]
+    fn send_and_sync() {
+        type Src = std::vec::IntoIter<String>;
+        assert_send::<Ticker<Src>>();
+        assert_send::<TickIter<Src>>();
+        assert_send::<Timestamped<Src>>();
+        assert_send::<Ticker<Src, TokenBucket>>();
+        assert_send::<Every>();
+        assert_send::<TickerHandle>();
+        assert_sync::<TickerHandle>();
+        assert_send::<SharedTicker>();
+        assert_sync::<SharedTicker>();
+        assert_send::<TickerBuilder>();
+        assert_sync::<TickerBuilder>();
+    }
+
+    #[test]
+    fn iterate_on_another_thread() {
+        let interval = Duration::from_millis(10);
+        for &inline in &[false, true] {
+            let start = Instant::now();
+            let mut iter = Ticker::builder(interval).inline(inline).build_iter(0..);
+            assert_eq!(iter.next(), Some(0));
+            let handle = iter.handle();
+            let moved = thread::spawn(move || {
+                let arrivals: Vec<_> = iter.by_ref().take(3).map(|i| (i, start.elapsed())).collect();
+                (iter, arrivals)
+            });
+            let (iter, arrivals) = moved.join().unwrap();
+            assert_eq!(arrivals.len(), 3, "{:?}", arrivals);
+            for &(i, arrival) in &arrivals {
+                assert!(arrival + Duration::from_millis(1) >= interval * (i + 1), "{:?}", arrivals);
+            }
+            let stats = handle.stats();
+            assert_eq!(stats.items, 4);
+            // Dropping it there still stops whatever paced it.
+            let ctrl = iter.ticker.ctrl.clone();
+            thread::spawn(move || drop(iter)).join().unwrap();
+            let start = Instant::now();
+            while ctrl.send(Control::Pause).is_ok() {
+                assert!(start.elapsed() < Duration::from_secs(1), "still ticking");
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+    }
+
+    #[test]
+    fn it_works() {
+        let vec: Vec<usize> = (0..10).collect();
+        let ticker = Ticker::new(vec.iter(), Duration::from_secs(1));
+        for i in ticker {
+            println!("{:?}", i);
+        }
+    }
+
+    /// manual_ticks advances a ManualClock by each of ````steps```` in turn,
+    /// taking ````takes```` ticks from an inline Ticker after each, and returns
+    /// when they fired relative to the start.
+    fn manual_ticks(behavior: MissedTickBehavior, steps: &[(u64, usize)]) -> Vec<u64> {
+        let clock = ManualClock::new();
+        let start = clock.now();
+        let mut iter = Ticker::new_with_clock(0.., Duration::from_millis(10), clock.clone())
+            .missed_tick_behavior(behavior)
+            .max_pending(3)
+            .inline(true)
+            .timestamped();
+        let mut fired = Vec::new();
+        for &(step, takes) in steps {
+            clock.advance(Duration::from_millis(step));
+            for _ in 0..takes {
+                let (t, _) = iter.next().unwrap();
+                fired.push((t - start).as_millis() as u64);
+            }
+        }
+        fired
+    }
+
+    #[test]
+    fn manual_clock_missed_ticks() {
+        use MissedTickBehavior::*;
+        assert_eq!(manual_ticks(Burst, &[(55, 3), (10, 1)]), [10, 40, 50, 60]);
+        assert_eq!(manual_ticks(Skip, &[(55, 1), (10, 1)]), [10, 60]);
+        assert_eq!(manual_ticks(Delay, &[(55, 1), (10, 1)]), [10, 65]);
+    }
+
+    /// paced takes an item from an inline Ticker of 20ms ticks after each of
+    /// ````steps```` of a ManualClock, and returns when each one was returned.
+    fn paced(pacing: Pacing, steps: &[u64]) -> Vec<Option<u64>> {
+        let clock = ManualClock::new();
+        let start = clock.now();
+        let mut ticker = Ticker::new_with_clock(0.., Duration::from_millis(20), clock.clone())
+            .pacing(pacing)
+            .max_pending(2)
+            .inline(true);
+        steps.iter()
+            .map(|&step| {
+                clock.advance(Duration::from_millis(step));
+                ticker.try_next().ok().map(|_| (clock.now() - start).as_millis() as u64)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pacing_fixed_and_min_gap() {
+        // A 30ms loop body after each item, then a stall.
+        let steps = [20, 30, 30, 0, 60, 0, 0];
+        let fixed = paced(Pacing::Fixed, &steps);
+        assert_eq!(fixed, [Some(20), Some(50), Some(80), Some(80), Some(140), Some(140), None]);
+        let min_gap = paced(Pacing::MinGap, &[20, 30, 19, 1, 30, 20, 60, 0, 20]);
+        assert_eq!(min_gap, [Some(20), None, None, Some(70), None, Some(120), None, None, Some(200)]);
+
+        let (interval, body) = (Duration::from_millis(10), Duration::from_millis(15));
+        let mut handed = Vec::new();
+        for _ in Ticker::new(0..3, interval).first_tick_immediately(true).pacing(Pacing::MinGap) {
+            handed.push(Instant::now());
+            thread::sleep(body);
+        }
+        for pair in handed.windows(2) {
+            assert!(pair[1] - pair[0] + Duration::from_millis(1) >= body + interval, "{:?}", handed);
+        }
+    }
+
+    #[test]
+    fn leading_and_trailing_edges() {
+        let interval = Duration::from_millis(30);
+        // Which intervals in try_next() gets each item, and the end.
+        let leading = [(1, Some(0)), (2, Some(1)), (3, Some(2)), (4, None)];
+        let trailing = [(0, Some(0)), (1, Some(1)), (2, Some(2)), (3, None), (4, None)];
+        for &(edge, want) in &[(TickEdge::Leading, &leading[..]), (TickEdge::Trailing, &trailing[..])] {
+            let clock = ManualClock::new();
+            let mut ticker = Ticker::new_with_clock(0..3, interval, clock.clone()).edge(edge).inline(true);
+            let mut got = Vec::new();
+            for step in 0..5 {
+                if let Ok(item) = ticker.try_next() {
+                    got.push((step, item));
+                }
+                clock.advance(interval);
+            }
+            assert_eq!(got, want, "{:?}", edge);
+
+            let start = Instant::now();
+            let arrivals: Vec<_> = Ticker::new(0..3, interval).edge(edge).map(|_| start.elapsed()).collect();
+            for (n, arrival) in arrivals.iter().enumerate() {
+                let due = interval * (n as u32 + want[0].0);
+                assert!(*arrival + Duration::from_millis(1) >= due, "{:?}: {:?}", edge, arrivals);
+            }
+        }
+    }
+
+    #[test]
+    fn initial_burst_then_paced() {
+        let interval = Duration::from_millis(20);
+        let clock = ManualClock::new();
+        let mut ticker = Ticker::new_with_clock(0..10, interval, clock.clone()).initial_burst(4).inline(true);
+        clock.advance(interval * 3);
+        for i in 0..4 {
+            assert_eq!(ticker.try_next(), Ok(Some(i)));
+        }
+        // Pacing starts from the end of the burst, not from creation.
+        assert_eq!(ticker.try_next(), Err(NotDue));
+        clock.advance(interval);
+        assert_eq!(ticker.try_next(), Ok(Some(4)));
+        assert_eq!((ticker.stats().ticks, ticker.stats().items), (1, 1));
+
+        let start = Instant::now();
+        let arrivals: Vec<_> = Ticker::new(0..10, interval).initial_burst(4).map(|_| start.elapsed()).collect();
+        assert_eq!(arrivals.len(), 10);
+        assert!(arrivals[3] < interval / 2, "{:?}", arrivals);
+        for (n, arrival) in arrivals[4..].iter().enumerate() {
+            let due = interval * (n as u32 + 1);
+            assert!(*arrival + Duration::from_millis(1) >= due, "{:?}", arrivals);
+        }
+
+        let start = Instant::now();
+        assert_eq!(Ticker::new(0..5, Duration::from_secs(60)).initial_burst(8).count(), 5);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn resume_policy_handles_a_suspend() {
+        let (minute, hour) = (Duration::from_secs(60), Duration::from_secs(3600));
+        let cases = [(false, MissedTickBehavior::Delay, 0), (true, MissedTickBehavior::Delay, 0),
+                     (false, MissedTickBehavior::Burst, 4), (true, MissedTickBehavior::Burst, 4)];
+        for &(inline, behavior, backlog) in cases.iter() {
+            let clock = ManualClock::new();
+            let gaps = Arc::new(Mutex::new(Vec::new()));
+            let missed = Arc::new(AtomicUsize::new(0));
+            let (g, m) = (gaps.clone(), missed.clone());
+            let mut ticker = Ticker::new_with_clock(0.., minute, clock.clone())
+                .inline(inline)
+                .max_pending(5)
+                .resume_policy(10, behavior)
+                .on_resume(move |gap| g.lock().unwrap().push(gap))
+                .on_missed_tick(move |n| { m.fetch_add(n as usize, Ordering::SeqCst); });
+            // The Ticker is asleep waiting for its first tick when the clock
+            // jumps an hour.
+            let jump = {
+                let clock = clock.clone();
+                thread::spawn(move || {
+                    thread::sleep(Duration::from_millis(20));
+                    clock.advance(hour);
+                })
+            };
+            assert_eq!(ticker.next(), Some(0));
+            jump.join().unwrap();
+            // ManualClock time stands still, so give a worker real time to
+            // send what it will.
+            let mut poll = || {
+                let start = Instant::now();
+                loop {
+                    match ticker.try_next() {
+                        Err(NotDue) if start.elapsed() < Duration::from_millis(50) => thread::sleep(Duration::from_millis(1)),
+                        next => return next,
+                    }
+                }
+            };
+            for i in 1..=backlog {
+                assert_eq!(poll(), Ok(Some(i)), "{:?}", behavior);
+            }
+            assert_eq!(poll(), Err(NotDue), "{:?}", behavior);
+            assert_eq!(*gaps.lock().unwrap(), [hour - minute], "{:?}", behavior);
+            if behavior == MissedTickBehavior::Delay {
+                assert_eq!(missed.load(Ordering::SeqCst), 59, "inline: {}", inline);
+                // The schedule starts again from the wake.
+                clock.advance(minute);
+                assert_eq!(poll(), Ok(Some(1)));
+            }
+        }
+
+        // A consumer that is just late is no suspend.
+        let clock = ManualClock::new();
+        let mut ticker = Ticker::new_with_clock(0.., minute, clock.clone()).inline(true).max_pending(5);
+        clock.advance(hour);
+        assert_eq!(ticker.by_ref().take(5).collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+        assert_eq!(ticker.try_next(), Err(NotDue));
+    }
+
+    #[test]
+    fn time_until_next_tick_counts_down() {
+        let interval = Duration::from_secs(60);
+        let clock = ManualClock::new();
+        let mut ticker = Ticker::new_with_clock(0.., interval, clock.clone()).inline(true);
+        assert_eq!(ticker.interval(), interval);
+        for tick in 1..3 {
+            let mut last = ticker.time_until_next_tick();
+            assert_eq!(last, interval);
+            for _ in 0..3 {
+                clock.advance(interval / 4);
+                let left = ticker.time_until_next_tick();
+                assert!(left < last, "{:?} after {:?}", left, last);
+                last = left;
+            }
+            clock.advance(interval / 4);
+            assert_eq!(ticker.time_until_next_tick(), Duration::ZERO);
+            assert!(ticker.try_next().unwrap().is_some());
+            assert_eq!(ticker.ticks_elapsed(), tick);
+        }
+        ticker.handle().set_interval(interval * 2);
+        assert_eq!(ticker.interval(), interval * 2);
+        assert_eq!(ticker.time_until_next_tick(), interval * 2);
+
+        // A threaded Ticker's ticks are counted when its worker fires them.
+        let interval = Duration::from_millis(40);
+        let mut iter = (0..3).ticked(interval);
+        let mut last = iter.time_until_next_tick();
+        assert!(last <= interval && last + Duration::from_millis(10) > interval, "{:?}", last);
+        while iter.next().is_some() {
+            let left = iter.time_until_next_tick();
+            assert!(left + Duration::from_millis(10) > interval, "{:?}", left);
+            thread::sleep(interval / 4);
+            assert!(iter.time_until_next_tick() < left);
+            last = left;
+        }
+        assert!(last <= interval);
+        // The last tick found the source exhausted.
+        assert_eq!(iter.ticks_elapsed(), 4);
+    }
+
+    #[test]
+    fn manual_ticks_release_one_item_each() {
+        let (mut iter, ticks) = Ticker::manual(0..);
+        assert_send::<TickHandle>();
+        assert_eq!(iter.try_next(), Err(NotDue));
+        assert_eq!(iter.next_timeout(Duration::from_millis(20)), Err(Timeout));
+        ticks.tick();
+        assert_eq!(iter.try_next(), Ok(Some(0)));
+        assert_eq!(iter.try_next(), Err(NotDue));
+
+        let consumer = thread::spawn(move || iter.collect::<Vec<_>>());
+        let clone = ticks.clone();
+        for _ in 0..3 {
+            clone.tick();
+            thread::sleep(Duration::from_millis(5));
+        }
+        ticks.tick();
+        drop(clone);
+        drop(ticks);
+        assert_eq!(consumer.join().unwrap(), [1, 2, 3, 4]);
+
+        // Ticks sent before the handle goes are still taken.
+        let (iter, ticks) = Ticker::manual(0..);
+        ticks.tick();
+        ticks.tick();
+        drop(ticks);
+        assert_eq!(iter.collect::<Vec<_>>(), [0, 1]);
+    }
+
+    #[test]
+    fn manual_ticks_alongside_the_timer() {
+        let interval = Duration::from_secs(60);
+        let clock = ManualClock::new();
+        let (mut iter, ticks) = Ticker::new_with_clock(0.., interval, clock.clone()).manual_ticks();
+        assert_eq!(iter.try_next(), Err(NotDue));
+        ticks.tick();
+        assert_eq!(iter.try_next(), Ok(Some(0)));
+        clock.advance(interval);
+        assert_eq!(iter.try_next(), Ok(Some(1)));
+        assert_eq!(iter.try_next(), Err(NotDue));
+        drop(ticks);
+        clock.advance(interval);
+        assert_eq!(iter.try_next(), Ok(Some(2)));
+        assert_eq!(iter.ticks_elapsed(), 3);
+    }
+
+    #[test]
+    fn manual_clock_threaded() {
+        let clock = ManualClock::new();
+        let start = clock.now();
+        let mut iter = Ticker::new_with_clock(0.., Duration::from_secs(3600), clock.clone())
+            .timestamped();
+        clock.advance(Duration::from_secs(3600));
+        let (fired, _) = iter.next().unwrap();
+        assert_eq!(fired - start, Duration::from_secs(3600));
+    }
+
+    #[test]
+    fn iterate_by_reference() {
+        let interval = Duration::from_millis(20);
+        for &inline in &[false, true] {
+            let mut ticker = Ticker::new(0.., interval).inline(inline);
+            let mut first = Vec::new();
+            for i in &mut ticker {
+                first.push(i);
+                if first.len() == 3 {
+                    break;
+                }
+            }
+            assert_eq!(first, [0, 1, 2]);
+
+            thread::sleep(interval * 5 / 2);
+            // The tick missed while away is waiting; after that, pacing holds.
+            let start = Instant::now();
+            let rest: Vec<i32> = ticker.by_ref().take(3).collect();
+            assert_eq!(rest, [3, 4, 5]);
+            let elapsed = start.elapsed();
+            assert!(elapsed >= interval, "inline: {}, {:?}", inline, elapsed);
+            assert!(elapsed < interval * 3, "inline: {}, {:?}", inline, elapsed);
+        }
+    }
+
+    #[test]
+    fn size_hint_follows_source() {
+        let interval = Duration::from_millis(5);
+        let mut ticker = Ticker::new(0..5, interval);
+        assert_eq!(ticker.len(), 5);
+        ticker.next();
+        assert_eq!(ticker.size_hint(), (4, Some(4)));
+        let rest: Vec<i32> = ticker.collect();
+        assert!(rest.capacity() >= 4, "{}", rest.capacity());
+
+        assert_eq!(Ticker::new(0.., interval).size_hint(), (0, None));
+        assert_eq!((0..3).ticked(interval).timestamped().len(), 3);
+
+        let mut ticker = Ticker::new(0..5, interval);
+        ticker.handle().stop();
+        assert_eq!(ticker.len(), 0);
+        assert_eq!(ticker.next(), None);
+    }
+
+    #[test]
+    fn nth_waits_one_tick() {
+        let interval = Duration::from_millis(100);
+        let start = Instant::now();
+        let mut ticker = Ticker::new(0.., interval);
+        assert_eq!(ticker.nth(50), Some(50));
+        assert_eq!(ticker.skip_items(10), 10);
+        assert_eq!(ticker.next(), Some(61));
+        let elapsed = start.elapsed();
+        assert!(elapsed >= interval * 2, "{:?}", elapsed);
+        assert!(elapsed < interval * 3, "{:?}", elapsed);
+
+        let start = Instant::now();
+        let skipped: Vec<i32> = (0..60).ticked(interval).skip(50).take(1).collect();
+        assert_eq!(skipped, [50]);
+        assert!(start.elapsed() < interval * 2, "{:?}", start.elapsed());
+    }
+
+    #[test]
+    fn count_and_last_do_not_wait() {
+        let interval = Duration::from_secs(10);
+        let start = Instant::now();
+        assert_eq!(Ticker::new(0..1000, interval).count(), 1000);
+        assert_eq!(Ticker::new(0..1000, interval).last(), Some(999));
+        assert_eq!((0..10).ticked(interval).count(), 10);
+
+        let mut ticker = Ticker::new(0..3, interval);
+        assert_eq!(ticker.skip_items(5), 3);
+        assert_eq!(ticker.next(), None);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn try_next_does_not_block() {
+        let interval = Duration::from_millis(10);
+        for &inline in &[false, true] {
+            let start = Instant::now();
+            let mut slow = Ticker::new(0.., Duration::from_secs(10)).inline(inline);
+            assert_eq!(slow.try_next(), Err(NotDue));
+            assert_eq!(slow.try_next(), Err(NotDue));
+            assert!(start.elapsed() < Duration::from_secs(1), "inline: {}", inline);
+
+            let mut ticker = Ticker::new(0.., interval).inline(inline);
+            assert_eq!(ticker.try_next(), Err(NotDue));
+
+            let start = Instant::now();
+            let mut got = Vec::new();
+            while start.elapsed() < interval * 11 / 2 {
+                if let Ok(item) = ticker.try_next() {
+                    got.push(item.unwrap());
+                }
+            }
+            // No item was lost to a tick that was not due.
+            assert_eq!(got, (0..got.len() as i32).collect::<Vec<_>>(), "inline: {}", inline);
+            assert!((4..=6).contains(&got.len()), "inline: {}, {:?}", inline, got);
+        }
+    }
+
+    #[test]
+    fn try_next_on_exhausted_source() {
+        let mut ticker = Ticker::new(0..1, Duration::from_millis(5)).pull_first(true);
+        assert_eq!(ticker.try_next(), Err(NotDue));
+        thread::sleep(Duration::from_millis(10));
+        assert_eq!(ticker.try_next(), Ok(Some(0)));
+        assert_eq!(ticker.try_next(), Ok(None));
+        assert_eq!(ticker.next(), None);
+    }
+
+    #[test]
+    fn next_timeout_keeps_schedule() {
+        let interval = Duration::from_millis(50);
+        let short = Duration::from_millis(15);
+        for &inline in &[false, true] {
+            let start = Instant::now();
+            let mut ticker = Ticker::new(0..2, interval).inline(inline).pull_first(true);
+            for _ in 0..2 {
+                assert_eq!(ticker.next_timeout(short), Err(Timeout), "inline: {}", inline);
+            }
+            assert_eq!(ticker.next_timeout(interval * 2), Ok(Some(0)), "inline: {}", inline);
+            let first = start.elapsed();
+            assert!(first >= interval - Duration::from_millis(1), "inline: {}, {:?}", inline, first);
+            assert!(first < interval + short, "inline: {}, {:?}", inline, first);
+
+            assert_eq!(ticker.next_timeout(short), Err(Timeout), "inline: {}", inline);
+            assert_eq!(ticker.next_timeout(interval * 2), Ok(Some(1)), "inline: {}", inline);
+            let second = start.elapsed();
+            assert!(second >= interval * 2 - Duration::from_millis(1), "inline: {}, {:?}", inline, second);
+            assert!(second < interval * 2 + short, "inline: {}, {:?}", inline, second);
+
+            let exhausted = Instant::now();
+            assert_eq!(ticker.next_timeout(interval * 2), Ok(None), "inline: {}", inline);
+            assert!(exhausted.elapsed() < short);
+        }
+    }
+
+    #[test]
+    fn split_selects_ticks_and_stop() {
+        let interval = Duration::from_millis(10);
+        let (ticks, mut src) = Ticker::new(0.., interval).split();
+        let (stop, stop_recv) = channel();
+        thread::spawn(move || {
+            thread::sleep(interval * 9 / 2);
+            stop.send(()).unwrap();
+        });
+        let mut items = Vec::new();
+        while stop_recv.try_recv().is_err() {
+            match ticks.recv_timeout(Duration::from_millis(1)) {
+                Ok(_) => items.push(src.next().unwrap()),
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => panic!("ticks ended early"),
+            }
+        }
+        assert!(items.len() >= 3 && items.len() <= 5, "{:?}", items);
+        assert_eq!(items, (0..items.len() as i32).collect::<Vec<_>>());
+
+        thread::sleep(interval * 4);
+        assert_eq!(ticks.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn split_keeps_held_item_and_handle() {
+        let mut ticker = Ticker::new(0..3, Duration::from_millis(10));
+        let handle = ticker.handle();
+        assert_eq!(ticker.next_timeout(Duration::ZERO), Err(Timeout));
+        let (ticks, src) = ticker.split();
+        assert_eq!(src.collect::<Vec<_>>(), [0, 1, 2]);
+        assert!(ticks.recv().is_ok());
+        handle.stop();
+        let start = Instant::now();
+        while ticks.recv().is_ok() {
+            assert!(start.elapsed() < Duration::from_secs(1));
+        }
+
+        let mut ticker = Ticker::new(0..0, Duration::from_secs(10));
+        assert_eq!(ticker.next(), None);
+        let (ticks, _) = ticker.split();
+        assert!(ticks.recv().is_err());
+    }
+
+    /// receive_gaps takes every message from a Ticker on ````recv```` and
+    /// returns how long after start each arrived.
+    fn receive_gaps(recv: Receiver<i32>, interval: Duration) -> Vec<Duration> {
+        let start = Instant::now();
+        Ticker::from_receiver(recv, interval).map(|_| start.elapsed()).collect()
+    }
+
+    #[test]
+    fn from_receiver_fast_producer() {
+        let interval = Duration::from_millis(20);
+        let (send, recv) = channel();
+        for i in 0..4 {
+            send.send(i).unwrap();
+        }
+        drop(send);
+        let arrivals = receive_gaps(recv, interval);
+        assert_eq!(arrivals.len(), 4);
+        for (n, at) in arrivals.iter().enumerate() {
+            let due = interval * (n as u32 + 1);
+            assert!(*at + Duration::from_millis(1) >= due, "{:?}", arrivals);
+            assert!(*at < due + interval / 2, "{:?}", arrivals);
+        }
+    }
+
+    #[test]
+    fn from_receiver_slow_producer() {
+        let interval = Duration::from_millis(10);
+        let clock = ManualClock::new();
+        let start = clock.now();
+        let (send, recv) = channel();
+        let (arrived, arrivals) = channel();
+        let ticker = Ticker::from_receiver(recv, interval).clock(clock.clone()).inline(true);
+        let consumer = {
+            let clock = clock.clone();
+            thread::spawn(move || {
+                for i in ticker {
+                    arrived.send((i, clock.now() - start)).unwrap();
+                }
+            })
+        };
+        // Each message goes out once it arrives; its tick has long fired.
+        for i in 0..3 {
+            clock.advance(interval * 3);
+            send.send(i).unwrap();
+            assert_eq!(arrivals.recv().unwrap(), (i, interval * 3 * (i as u32 + 1)));
+        }
+        drop(send);
+        consumer.join().unwrap();
+        assert!(arrivals.recv().is_err());
+    }
+
+    #[test]
+    fn from_receiver_no_burst_after_idle() {
+        let interval = Duration::from_millis(20);
+        let (send, recv) = channel();
+        thread::spawn(move || {
+            thread::sleep(interval * 3);
+            for i in 0..3 {
+                send.send(i).unwrap();
+            }
+        });
+        let arrivals = receive_gaps(recv, interval);
+        for pair in arrivals.windows(2) {
+            assert!(pair[1] - pair[0] + Duration::from_millis(1) >= interval, "{:?}", arrivals);
+        }
+    }
+
+    #[test]
+    fn from_receiver_ends_on_disconnect() {
+        let interval = Duration::from_millis(10);
+        let (send, recv) = channel();
+        let producer = thread::spawn(move || {
+            send.send(1).unwrap();
+            send.send(2).unwrap();
+            thread::sleep(interval * 3);
+        });
+        let start = Instant::now();
+        let items: Vec<i32> = Ticker::from_receiver(recv, interval).collect();
+        assert_eq!(items, [1, 2]);
+        producer.join().unwrap();
+        assert!(start.elapsed() < interval * 5, "{:?}", start.elapsed());
+    }
+
+    #[test]
+    fn reversed_is_rate_limited() {
+        let interval = Duration::from_millis(20);
+        let start = Instant::now();
+        let mut ticker = Ticker::new(0..6, interval);
+        assert_eq!(ticker.next_back(), Some(5));
+        assert_eq!(ticker.next(), Some(0));
+        let rest: Vec<i32> = ticker.rev().collect();
+        assert_eq!(rest, [4, 3, 2, 1]);
+        let elapsed = start.elapsed();
+        assert!(elapsed >= interval * 6, "{:?}", elapsed);
+        assert!(elapsed < interval * 8, "{:?}", elapsed);
+    }
+
+    #[test]
+    fn ticked_chains_with_std_adapters() {
+        use crate::TickedIterator;
+
+        let items: Vec<(usize, i32)> = (0..10)
+            .map(|i| i * 10)
+            .filter(|i| i % 20 == 0)
+            .ticked(Duration::from_millis(10))
+            .take(3)
+            .enumerate()
+            .collect();
+        assert_eq!(items, vec![(0, 0), (1, 20), (2, 40)]);
+    }
+
+    #[test]
+    fn first_tick_immediately() {
+        let interval = Duration::from_millis(200);
+        let mut iter = Ticker::new(0..3, interval)
+            .first_tick_immediately(true);
+
+        let start = Instant::now();
+        assert_eq!(iter.next(), Some(0));
+        assert!(start.elapsed() < interval / 4);
+
+        assert_eq!(iter.next(), Some(1));
+        assert!(start.elapsed() >= interval * 3 / 4);
+    }
+
+    #[test]
+    fn first_tick_waits_by_default() {
+        let interval = Duration::from_millis(100);
+        let mut iter = Ticker::new(0..3, interval);
+
+        let start = Instant::now();
+        assert_eq!(iter.next(), Some(0));
+        assert!(start.elapsed() >= interval * 3 / 4);
+    }
+
+    #[test]
+    fn initial_delay_differs_from_interval() {
+        let (initial, interval) = (Duration::from_millis(60), Duration::from_millis(20));
+        for &inline in &[false, true] {
+            let start = Instant::now();
+            let arrivals: Vec<Duration> = Ticker::with_delay(0..3, initial, interval)
+                .inline(inline)
+                .map(|_| start.elapsed())
+                .collect();
+            assert!(arrivals[0] + Duration::from_millis(1) >= initial, "inline: {}, {:?}", inline, arrivals);
+            assert!(arrivals[0] < initial + interval / 2, "inline: {}, {:?}", inline, arrivals);
+            for (n, arrival) in arrivals.iter().enumerate().skip(1) {
+                let due = initial + interval * n as u32;
+                assert!(*arrival + Duration::from_millis(1) >= due, "inline: {}, {:?}", inline, arrivals);
+                assert!(*arrival < due + interval / 2, "inline: {}, {:?}", inline, arrivals);
+            }
+        }
+
+        let start = Instant::now();
+        let mut ticker = Ticker::with_delay(0..2, Duration::ZERO, Duration::from_secs(10));
+        assert_eq!(ticker.next(), Some(0));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn max_interval_never_ticks() {
+        let short = Duration::from_millis(20);
+        for &inline in &[false, true] {
+            let mut ticker = Ticker::new(0.., Duration::MAX).inline(inline);
+            assert_eq!(ticker.time_until_next_tick(), Duration::MAX);
+            assert_eq!(ticker.next_timeout(short), Err(Timeout), "inline: {}", inline);
+
+            let mut ticker = Ticker::new(0.., Duration::MAX).inline(inline).first_tick_immediately(true);
+            assert_eq!(ticker.next(), Some(0));
+            assert_eq!(ticker.next_timeout(short), Err(Timeout), "inline: {}", inline);
+
+            let mut ticker = Ticker::with_delay(0.., Duration::MAX, short).inline(inline);
+            assert_eq!(ticker.next_timeout(short * 2), Err(Timeout), "inline: {}", inline);
+
+            let mut ticker = Ticker::new(0.., Duration::MAX)
+                .inline(inline)
+                .pacing(Pacing::MinGap)
+                .first_tick_immediately(true);
+            assert_eq!(ticker.next(), Some(0));
+            assert_eq!(ticker.next_timeout(short), Err(Timeout), "inline: {}", inline);
+
+            let mut ticker = Ticker::new(0.., short).inline(inline);
+            let handle = ticker.handle();
+            assert_eq!(ticker.next(), Some(0));
+            handle.set_interval(Duration::MAX);
+            assert_eq!(ticker.next_timeout(short * 2), Err(Timeout), "inline: {}", inline);
+            handle.pause();
+            handle.resume();
+            assert_eq!(ticker.next_timeout(short * 2), Err(Timeout), "inline: {}", inline);
+            // A usable interval brings the ticks back.
+            handle.set_interval(short);
+            assert_eq!(ticker.next_timeout(short * 10), Ok(Some(1)), "inline: {}", inline);
+        }
+    }
+
+    #[test]
+    fn for_duration_cuts_off() {
+        let (interval, total) = (Duration::from_millis(20), Duration::from_millis(110));
+        for &inline in &[false, true] {
+            let start = Instant::now();
+            let mut ticker = Ticker::new(0.., interval).inline(inline).for_duration(total);
+            let items: Vec<i32> = ticker.by_ref().collect();
+            let elapsed = start.elapsed();
+            assert!((4..=5).contains(&items.len()), "inline: {}, {:?}", inline, items);
+            assert_eq!(items, (0..items.len() as i32).collect::<Vec<_>>());
+            assert!(elapsed + Duration::from_millis(1) >= total, "inline: {}, {:?}", inline, elapsed);
+            assert!(elapsed < total + interval, "inline: {}, {:?}", inline, elapsed);
+            assert_eq!(ticker.next(), None);
+        }
+
+        let start = Instant::now();
+        let count = Ticker::new(0.., Duration::from_secs(10)).for_duration(Duration::from_millis(50)).count();
+        assert_eq!(count, 0);
+        assert!(start.elapsed() < Duration::from_secs(1), "{:?}", start.elapsed());
+    }
+
+    #[test]
+    fn max_ticks_stops_the_worker() {
+        let interval = Duration::from_millis(10);
+        let mut ticker = Ticker::limited(0.., interval, 3);
+        assert_eq!(ticker.by_ref().collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(ticker.next(), None);
+        // The worker drops its end of the control channel as it exits.
+        let start = Instant::now();
+        while ticker.ctrl.send(Control::Pause).is_ok() {
+            assert!(start.elapsed() < Duration::from_secs(1), "worker still running");
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        let ticker = Ticker::limited(0..2, interval, 5).inline(true);
+        assert_eq!(ticker.collect::<Vec<_>>(), [0, 1]);
+    }
+
+    #[test]
+    fn exhaustion_stops_the_worker() {
+        let mut ticker = Ticker::new(0..3, Duration::from_millis(5));
+        assert_eq!(ticker.by_ref().collect::<Vec<_>>(), [0, 1, 2]);
+        let start = Instant::now();
+        while ticker.ctrl.send(Control::Pause).is_ok() {
+            assert!(start.elapsed() < Duration::from_secs(1), "worker still running");
+            thread::sleep(Duration::from_millis(1));
+        }
+        let ticks = ticker.stats().ticks;
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(ticker.next(), None);
+        assert_eq!(ticker.stats().ticks, ticks);
+
+        let mut ticker = Ticker::new(0..3, Duration::from_secs(3600));
+        assert_eq!(ticker.next_timeout(Duration::from_millis(1)), Err(Timeout));
+        assert_eq!(ticker.skip_items(5), 3);
+        assert!(ticker.backend.is_none());
+        assert_eq!(ticker.next(), None);
+    }
+
+    #[test]
+    fn peek_takes_no_tick() {
+        let start = Instant::now();
+        let mut iter = (0..2).ticked(Duration::from_secs(3600));
+        assert_eq!(iter.peek(), Some(&0));
+        assert_eq!(iter.peek(), Some(&0));
+        assert_eq!(iter.len(), 2);
+        assert!(start.elapsed() < Duration::from_millis(100), "{:?}", start.elapsed());
+
+        let interval = Duration::from_millis(10);
+        let start = Instant::now();
+        let mut iter = (0..2).ticked(interval);
+        for i in 0..2 {
+            assert_eq!(iter.peek(), Some(&i));
+            assert_eq!(iter.next(), Some(i));
+            assert!(start.elapsed() + Duration::from_millis(1) >= interval * (i + 1), "{:?}", start.elapsed());
+        }
+        assert_eq!(iter.peek(), None);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.stats().ticks, 2);
+
+        let mut ticker = Ticker::limited(0.., interval, 1).inline(true);
+        assert_eq!(ticker.next(), Some(0));
+        assert_eq!(ticker.peek(), None);
+        assert_eq!(ticker.next(), None);
+    }
+
+    #[test]
+    fn max_ticks_bounds_count_and_size_hint() {
+        let hour = Duration::from_secs(3600);
+        assert_eq!(Ticker::limited(0.., hour, 3).size_hint(), (0, Some(3)));
+        assert_eq!(Ticker::limited(0..10, hour, 3).len(), 3);
+        assert_eq!(Ticker::limited(0..2, hour, 3).len(), 2);
+        assert_eq!(Ticker::limited(0.., hour, 3).count(), 3);
+        assert_eq!(Ticker::limited(0.., hour, 3).last(), Some(2));
+        assert_eq!(Ticker::limited(0.., hour, 0).last(), None);
+
+        let mut ticker = Ticker::limited(0.., Duration::from_secs(5), 2).first_tick_immediately(true).inline(true);
+        assert_eq!(ticker.next_timeout(Duration::ZERO), Ok(Some(0)));
+        assert_eq!(ticker.next_timeout(Duration::ZERO), Err(Timeout));
+        assert_eq!(ticker.count(), 1);
+    }
+
+    /// ThreadNames is the system clock, noting the name of every thread that
+    /// reads it.
+    #[derive(Clone, Debug, Default)]
+    struct ThreadNames(Arc<std::sync::Mutex<Vec<String>>>);
+
+    impl Clock for ThreadNames {
+        fn now(&self) -> Instant {
+            let name = thread::current().name().unwrap_or_default().to_string();
+            self.0.lock().unwrap().push(name);
+            Instant::now()
+        }
+    }
+
+    impl ThreadNames {
+        fn saw(&self, name: &str) -> bool {
+            self.0.lock().unwrap().iter().any(|seen| seen == name)
+        }
+    }
+
+    #[test]
+    fn worker_thread_is_named() {
+        let interval = Duration::from_millis(10);
+        let names = ThreadNames::default();
+        let items: Vec<i32> = Ticker::new_with_clock(0..2, interval, names.clone()).collect();
+        assert_eq!(items, [0, 1]);
+        assert!(names.saw("ticker-10ms"), "{:?}", names);
+
+        let names = ThreadNames::default();
+        let ticker = Ticker::builder(interval)
+            .clock(names.clone())
+            .thread_name("poller")
+            .stack_size(64 * 1024)
+            .try_wrap(0..2)
+            .unwrap();
+        assert_eq!(ticker.collect::<Vec<_>>(), [0, 1]);
+        assert!(names.saw("poller"), "{:?}", names);
+        assert!(!names.saw("ticker-10ms"), "{:?}", names);
+
+        assert_eq!(Ticker::try_new(0..2, interval).unwrap().collect::<Vec<_>>(), [0, 1]);
+    }
+
+    #[test]
+    fn from_fn_ends_with_the_closure() {
+        let interval = Duration::from_millis(5);
+        let mut calls = Vec::new();
+        let start = Instant::now();
+        let items: Vec<usize> = Ticker::from_fn(interval, || {
+                calls.push(start.elapsed());
+                if calls.len() <= 3 { Some(calls.len()) } else { None }
+            })
+            .collect();
+        assert_eq!(items, [1, 2, 3]);
+        assert_eq!(calls.len(), 4);
+        // Each call waits for its own tick.
+        for (i, at) in calls.iter().enumerate() {
+            assert!(*at + Duration::from_millis(1) >= interval * (i as u32 + 1), "{:?}", calls);
+        }
+    }
+
+    #[test]
+    fn run_every_stops_on_break() {
+        let interval = Duration::from_millis(5);
+        let start = Instant::now();
+        let mut calls = Vec::new();
+        Ticker::run_every(interval, || {
+            calls.push(start.elapsed());
+            if calls.len() < 3 { ControlFlow::Continue(()) } else { ControlFlow::Break(()) }
+        });
+        assert_eq!(calls.len(), 3);
+        for (n, call) in calls.iter().enumerate() {
+            assert!(*call + Duration::from_millis(1) >= interval * (n as u32 + 1), "{:?}", calls);
+        }
+    }
+
+    #[test]
+    fn stats_tell_a_slow_consumer() {
+        let interval = Duration::from_millis(10);
+        for &inline in &[false, true] {
+            let mut ticker = Ticker::new(0.., interval).inline(inline);
+            ticker.by_ref().take(4).for_each(drop);
+            let fast = ticker.stats();
+            assert_eq!(fast.items, 4, "inline: {}", inline);
+            assert_eq!(fast.late, 0, "inline: {}, {:?}", inline, fast);
+            assert!(fast.ticks >= 4, "inline: {}, {:?}", inline, fast);
+            assert!(fast.mean_gap + Duration::from_millis(1) >= interval, "inline: {}, {:?}", inline, fast);
+            assert!(fast.max_gap >= fast.mean_gap, "inline: {}, {:?}", inline, fast);
+            assert!(fast.last_tick.is_some());
+
+            let handle = ticker.handle();
+            for _ in 0..3 {
+                thread::sleep(interval * 2);
+                ticker.next();
+            }
+            let slow = handle.stats();
+            assert_eq!(slow.items, 7, "inline: {}", inline);
+            assert_eq!(slow.late, 3, "inline: {}, {:?}", inline, slow);
+            assert!(slow.max_gap + Duration::from_millis(1) >= interval * 2, "inline: {}, {:?}", inline, slow);
+        }
+        let idle = Ticker::new(0..1, interval).stats();
+        assert_eq!((idle.items, idle.mean_gap, idle.last_tick), (0, Duration::ZERO, None));
+    }
+
+    #[test]
+    fn exhaustion_does_not_wait() {
+        let interval = Duration::from_millis(100);
+        let start = Instant::now();
+        let items: Vec<i32> = Ticker::new(0..3, interval).pull_first(true).collect();
+        assert_eq!(items, vec![0, 1, 2]);
+        assert!(start.elapsed() < interval * 3 + interval / 2);
+    }
+
+    #[test]
+    fn pulls_after_the_tick_unless_first() {
+        let interval = Duration::from_millis(10);
+        for &first in &[false, true] {
+            let clock = ManualClock::new();
+            let start = clock.now();
+            let pulled = {
+                let clock = clock.clone();
+                iter::repeat_with(move || clock.now() - start)
+            };
+            let mut ticker = Ticker::new_with_clock(pulled, interval, clock.clone())
+                .inline(true)
+                .pull_first(first);
+            assert_eq!(ticker.try_next(), Err(NotDue));
+            clock.advance(interval);
+            let expected = if first { Duration::ZERO } else { interval };
+            assert_eq!(ticker.next(), Some(expected), "first: {}", first);
+        }
+    }
+
+    #[test]
+    fn fused_after_source_ends() {
+        let mut calls = 0;
+        let src = std::iter::from_fn(move || {
+            calls += 1;
+            if calls == 2 { None } else { Some(calls) }
+        });
+        let mut iter = Ticker::new(src, Duration::from_millis(10));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    /// slow_consumer takes one item, stalls for 2.5 intervals, then returns the
+    /// gaps before each of the next three items.
+    fn slow_consumer(behavior: MissedTickBehavior, interval: Duration) -> Vec<Duration> {
+        let mut iter = Ticker::new(0.., interval)
+            .missed_tick_behavior(behavior)
+            .max_pending(3);
+        iter.next();
+        thread::sleep(interval * 5 / 2);
+
+        let mut last = Instant::now();
+        (0..3)
+            .map(|_| {
+                iter.next();
+                let gap = last.elapsed();
+                last = Instant::now();
+                gap
+            })
+            .collect()
+    }
+
+    #[test]
+    fn missed_ticks_burst() {
+        let interval = Duration::from_millis(100);
+        let gaps = slow_consumer(MissedTickBehavior::Burst, interval);
+        assert!(gaps[0] < interval / 5, "{:?}", gaps);
+        assert!(gaps[1] < interval / 5, "{:?}", gaps);
+        assert!(gaps[2] < interval * 3 / 4, "{:?}", gaps);
+    }
+
+    #[test]
+    fn missed_ticks_skip() {
+        let interval = Duration::from_millis(100);
+        let gaps = slow_consumer(MissedTickBehavior::Skip, interval);
+        assert!(gaps[0] < interval / 5, "{:?}", gaps);
+        assert!(gaps[1] > interval / 5, "{:?}", gaps);
+        assert!(gaps[1] < interval * 3 / 4, "{:?}", gaps);
+        assert!(gaps[2] > interval * 3 / 4, "{:?}", gaps);
+    }
+
+    #[test]
+    fn missed_ticks_delay() {
+        let interval = Duration::from_millis(100);
+        let gaps = slow_consumer(MissedTickBehavior::Delay, interval);
+        assert!(gaps[0] < interval / 5, "{:?}", gaps);
+        assert!(gaps[1] > interval * 3 / 4, "{:?}", gaps);
+        assert!(gaps[2] > interval * 3 / 4, "{:?}", gaps);
+    }
+
+    #[test]
+    fn ticks_do_not_drift() {
+        let interval = Duration::from_millis(2);
+        let ticks = 200;
+        let start = Instant::now();
+        // Let a consumer that is briefly starved of CPU catch up, so only the
+        // schedule decides when the last item arrives.
+        let iter = Ticker::new(0..ticks, interval)
+            .max_pending(ticks as usize);
+        for i in iter {
+            let expected = interval * (i + 1);
+            let actual = start.elapsed();
+            assert!(actual >= expected, "tick {} early: {:?}", i, actual);
+        }
+        let expected = interval * ticks;
+        let actual = start.elapsed();
+        assert!(actual < expected + interval * 5, "drifted to {:?}", actual);
+    }
+
+    #[test]
+    fn inline_paces_like_threaded() {
+        let interval = Duration::from_millis(20);
+        let start = Instant::now();
+        let items: Vec<i32> = Ticker::new(0..5, interval).inline(true).pull_first(true).collect();
+        assert_eq!(items, vec![0, 1, 2, 3, 4]);
+        assert!(start.elapsed() >= interval * 5);
+        assert!(start.elapsed() < interval * 6);
+    }
+
+    #[test]
+    fn inline_does_not_oversleep_for_slow_consumer() {
+        let interval = Duration::from_millis(50);
+        let mut iter = Ticker::new(0.., interval).inline(true);
+        iter.next();
+        thread::sleep(interval * 5 / 2);
+
+        let start = Instant::now();
+        iter.next();
+        assert!(start.elapsed() < interval / 5);
+        iter.next();
+        assert!(start.elapsed() < interval);
+    }
+
+    #[test]
+    fn inline_drop_is_cheap() {
+        let start = Instant::now();
+        for _ in 0..10000 {
+            let mut iter = Ticker::new(0.., Duration::from_secs(60))
+                .inline(true)
+                .first_tick_immediately(true);
+            iter.next();
+        }
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn inline_missed_ticks_match_threaded() {
+        let interval = Duration::from_millis(100);
+        for &behavior in &[MissedTickBehavior::Burst,
+                           MissedTickBehavior::Skip,
+                           MissedTickBehavior::Delay] {
+            let mut config = Config::new(interval);
+            config.missed_tick_behavior = behavior;
+            config.max_pending = 3;
+            let start = Instant::now();
+            let mut schedule = Schedule::new(&config, Arc::new(SystemClock), Some(start), None);
+            let late = start + interval * 5 / 2;
+            assert_eq!(schedule.tick_at(late), Some(start));
+            let expected = match behavior {
+                MissedTickBehavior::Burst => start + interval,
+                MissedTickBehavior::Skip => start + interval * 3,
+                MissedTickBehavior::Delay => late + interval,
+            };
+            assert_eq!(schedule.next, Some(expected), "{:?}", behavior);
+        }
+    }
+
+    /// gaps_after_set_interval takes two items, halves the interval from
+    /// another thread and returns the gaps before the next three items.
+    fn gaps_after_set_interval(ticker: Ticker<std::ops::RangeFrom<i32>>) -> Vec<Duration> {
+        let handle = ticker.handle();
+        let mut iter = ticker;
+        iter.next();
+        iter.next();
+        thread::spawn(move || handle.set_interval(Duration::from_millis(20)))
+            .join()
+            .unwrap();
+
+        let mut last = Instant::now();
+        (0..3)
+            .map(|_| {
+                iter.next();
+                let gap = last.elapsed();
+                last = Instant::now();
+                gap
+            })
+            .collect()
+    }
+
+    /// tick_errors returns how late each of ````n```` ticks of an inline
+    /// ````ticker```` arrived after it was due.
+    fn tick_errors(ticker: Ticker<std::ops::RangeFrom<i32>>, n: usize) -> Vec<Duration> {
+        ticker.timestamped().take(n).map(|(due, _)| due.elapsed()).collect()
+    }
+
+    #[test]
+    fn precise_ticks_keep_schedule() {
+        let interval = Duration::from_micros(500);
+        let start = Instant::now();
+        for (i, _) in Ticker::new_precise(0..20, interval).enumerate() {
+            let due = interval * (i as u32 + 1);
+            assert!(start.elapsed() >= due, "tick {} at {:?}", i, start.elapsed());
+        }
+        assert!(start.elapsed() < interval * 20 + Duration::from_millis(20), "{:?}", start.elapsed());
+    }
+
+    #[test]
+    fn spinning_through_whole_waits() {
+        // This is how Tickers wait on targets without threads.
+        let interval = Duration::from_millis(5);
+        let start = Instant::now();
+        let mut ticker = Ticker::new(0.., interval).inline(true).spin_threshold(Duration::MAX);
+        let handle = ticker.handle();
+        assert_eq!(ticker.by_ref().take(3).collect::<Vec<_>>(), [0, 1, 2]);
+        assert!(start.elapsed() + Duration::from_millis(1) >= interval * 3, "{:?}", start.elapsed());
+        assert!(start.elapsed() < interval * 3 + Duration::from_millis(20), "{:?}", start.elapsed());
+
+        handle.set_interval(Duration::from_secs(3600));
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            handle.stop();
+        });
+        assert_eq!(ticker.next(), None);
+    }
+
+    #[test]
+    #[ignore = "measures timer precision, which depends on the machine"]
+    fn precise_tick_error_at_500us() {
+        let interval = Duration::from_micros(500);
+        let stats = |mut errors: Vec<Duration>| {
+            errors.sort();
+            let mean = errors.iter().sum::<Duration>() / errors.len() as u32;
+            (mean, errors[errors.len() * 99 / 100])
+        };
+        let (slept_mean, slept_p99) = stats(tick_errors(Ticker::new(0.., interval).inline(true), 2000));
+        let (mean, p99) = stats(tick_errors(Ticker::new_precise(0.., interval), 2000));
+        // Sleeping alone is shown for comparison.
+        let measured = format!("precise: mean {:?}, p99 {:?}; sleeping: mean {:?}, p99 {:?}", mean, p99, slept_mean, slept_p99);
+        assert!(mean < Duration::from_micros(50), "{}", measured);
+        assert!(p99 < Duration::from_micros(200), "{}", measured);
+    }
+
+    #[test]
+    fn zero_interval_passes_through() {
+        for &inline in &[false, true] {
+            let start = Instant::now();
+            let sum: u64 = Ticker::new(0..1_000_000u64, Duration::ZERO).inline(inline).sum();
+            assert_eq!(sum, 499_999_500_000);
+            assert!(start.elapsed() < Duration::from_secs(5), "inline: {}, {:?}", inline, start.elapsed());
+        }
+    }
+
+    #[test]
+    fn zero_interval_set_while_running() {
+        let interval = Duration::from_millis(20);
+        let mut ticker = Ticker::new(0.., interval);
+        let handle = ticker.handle();
+        ticker.next();
+        handle.set_interval(Duration::ZERO);
+        let start = Instant::now();
+        assert_eq!(ticker.by_ref().take(10_000).count(), 10_000);
+        assert!(start.elapsed() < interval, "{:?}", start.elapsed());
+
+        handle.pause();
+        let resumer = handle.clone();
+        thread::spawn(move || {
+            thread::sleep(interval);
+            resumer.resume();
+        });
+        let start = Instant::now();
+        ticker.next();
+        assert!(start.elapsed() + Duration::from_millis(1) >= interval, "{:?}", start.elapsed());
+
+        handle.set_interval(interval);
+        let start = Instant::now();
+        ticker.next();
+        ticker.next();
+        assert!(start.elapsed() + Duration::from_millis(1) >= interval, "{:?}", start.elapsed());
+    }
+
+    #[test]
+    fn set_interval_changes_spacing() {
+        let ticker = Ticker::new(0.., Duration::from_millis(60));
+        for gap in gaps_after_set_interval(ticker) {
+            assert!(gap < Duration::from_millis(40), "{:?}", gap);
+        }
+    }
+
+    #[test]
+    fn set_interval_changes_spacing_inline() {
+        let ticker = Ticker::new(0.., Duration::from_millis(60)).inline(true);
+        for gap in gaps_after_set_interval(ticker) {
+            assert!(gap < Duration::from_millis(40), "{:?}", gap);
+        }
+    }
+
+    #[test]
+    fn set_same_interval_keeps_countdown() {
+        let interval = Duration::from_millis(100);
+        let mut iter = Ticker::new(0.., interval);
+        let handle = iter.handle();
+        iter.next();
+
+        let start = Instant::now();
+        while start.elapsed() < interval / 2 {
+            handle.set_interval(interval);
+            thread::sleep(Duration::from_millis(5));
+        }
+        iter.next();
+        assert!(start.elapsed() < interval * 5 / 4);
+    }
+
+    #[test]
+    fn pause_blocks_until_resume() {
+        let interval = Duration::from_millis(30);
+        for &inline in &[false, true] {
+            let mut iter = Ticker::new(0.., interval).inline(inline);
+            let handle = iter.handle();
+            iter.next();
+            handle.pause();
+            handle.pause();
+
+            let resumer = thread::spawn(move || {
+                thread::sleep(interval * 5);
+                handle.resume();
+                handle.resume();
+            });
+            let start = Instant::now();
+            iter.next();
+            assert!(start.elapsed() >= interval * 6, "inline: {}", inline);
+
+            let start = Instant::now();
+            iter.next();
+            assert!(start.elapsed() >= interval * 3 / 4, "inline: {}", inline);
+            resumer.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn resume_discards_waiting_tick() {
+        let interval = Duration::from_millis(30);
+        for &inline in &[false, true] {
+            let mut iter = Ticker::new(0.., interval).inline(inline);
+            let handle = iter.handle();
+            iter.next();
+            thread::sleep(interval * 3 / 2);
+            handle.pause();
+            thread::sleep(interval * 3);
+            handle.resume();
+
+            let start = Instant::now();
+            iter.next();
+            assert!(start.elapsed() >= interval * 3 / 4, "inline: {}", inline);
+        }
+    }
+
+    #[test]
+    fn worker_exit_ends_iteration() {
+        let interval = Duration::from_millis(10);
+        let mut iter = Ticker::new(0.., interval);
+        assert_eq!(iter.next(), Some(0));
+        // Kill the worker behind the TickIter's back, as if it had died.
+        iter.ctrl.send(Control::Kill).unwrap();
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn worker_exit_while_waiting_ends_iteration() {
+        let mut iter = Ticker::new(0.., Duration::from_secs(60));
+        let ctrl = iter.ctrl.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            ctrl.send(Control::Kill).unwrap();
+        });
+        let start = Instant::now();
+        assert_eq!(iter.next(), None);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn stop_interrupts_wait() {
+        for &inline in &[false, true] {
+            let ticker = Ticker::new(0.., Duration::from_secs(10))
+                .first_tick_immediately(true)
+                .inline(inline);
+            let handle = ticker.handle();
+            let start = Instant::now();
+            let consumer = thread::spawn(move || ticker.collect::<Vec<i32>>());
+
+            thread::sleep(Duration::from_millis(50));
+            handle.stop();
+            assert_eq!(consumer.join().unwrap(), vec![0], "inline: {}", inline);
+            assert!(start.elapsed() < Duration::from_secs(1), "inline: {}", inline);
+        }
+    }
+
+    #[test]
+    fn stop_ends_iteration_with_items_left() {
+        let mut iter = Ticker::new(0.., Duration::from_millis(10));
+        iter.next();
+        iter.handle().stop();
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn jitter_offsets_stay_in_range() {
+        let interval = Duration::from_millis(100);
+        let mut config = Config::new(interval);
+        config.jitter = Duration::from_millis(30);
+        config.jitter_seed = Some(7);
+
+        let mut jitter = config.jitter();
+        let offsets: Vec<Offset> = (0..1000).map(|_| jitter.offset(interval)).collect();
+        assert!(offsets.iter().all(|o| o.by <= Duration::from_millis(30)));
+        assert!(offsets.iter().any(|o| o.early));
+        assert!(offsets.iter().any(|o| !o.early));
+        assert!(offsets.iter().any(|o| o.by > Duration::from_millis(20)));
+
+        let mut again = config.jitter();
+        for offset in offsets.iter().take(10) {
+            let repeat = again.offset(interval);
+            assert_eq!((repeat.by, repeat.early), (offset.by, offset.early));
+        }
+    }
+
+    #[test]
+    fn jitter_capped_at_half_interval() {
+        let interval = Duration::from_millis(10);
+        let mut config = Config::new(interval);
+        config.jitter = Duration::from_secs(1);
+        let mut jitter = config.jitter();
+        for _ in 0..1000 {
+            assert!(jitter.offset(interval).by <= interval / 2);
+        }
+    }
+
+    #[test]
+    fn jittered_gaps_stay_in_range() {
+        let interval = Duration::from_millis(20);
+        let jitter = Duration::from_millis(5);
+        for &inline in &[false, true] {
+            let start = Instant::now();
+            let mut last = start;
+            let iter = Ticker::new(0..20, interval)
+                .jitter(jitter)
+                .max_pending(20)
+                .inline(inline)
+                .pull_first(true);
+            for _ in iter {
+                let gap = last.elapsed();
+                last = Instant::now();
+                assert!(gap < interval + jitter * 2 + interval / 2, "{:?}", gap);
+            }
+            let total = start.elapsed();
+            assert!(total >= interval * 20 - jitter, "{:?}", total);
+            assert!(total < interval * 20 + jitter + interval, "{:?}", total);
+        }
+    }
+
+    #[test]
+    fn rate_interval_precision() {
+        let second = Duration::from_secs(1);
+        assert_eq!(rate_interval(50.0, second), Duration::from_millis(20));
+        assert_eq!(rate_interval(7.5, second), Duration::from_nanos(133_333_333));
+        assert_eq!(rate_interval(1e12, second), Duration::from_nanos(1));
+        assert_eq!(rate_interval(2.0, Duration::from_secs(60)), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn rate_interval_rejects_bad_rates() {
+        for &rate in &[0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let result = std::panic::catch_unwind(|| rate_interval(rate, Duration::from_secs(1)));
+            assert!(result.is_err(), "accepted {}", rate);
+        }
+    }
+
+    #[test]
+    fn at_rate_throughput() {
+        let start = Instant::now();
+        let count = Ticker::at_rate(0..20, 100.0).fold(0, |n, _| n + 1);
+        assert_eq!(count, 20);
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(200), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(250), "{:?}", elapsed);
+    }
+
+    #[test]
+    fn until_aligned_lands_on_boundary() {
+        let secs = Duration::from_secs;
+        assert_eq!(until_aligned(secs(125), secs(60)), secs(55));
+        assert_eq!(until_aligned(secs(120), secs(60)), Duration::ZERO);
+        assert_eq!(until_aligned(Duration::from_millis(12_300), secs(5)), Duration::from_millis(2_700));
+        assert_eq!(until_aligned(secs(7), Duration::ZERO), Duration::ZERO);
+    }
+
+    #[test]
+    fn aligned_first_tick() {
+        let interval = Duration::from_millis(50);
+        for &inline in &[false, true] {
+            let mut iter = Ticker::aligned(0.., interval).inline(inline);
+            iter.next();
+            let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+            let off = Duration::from_nanos((since_epoch.as_nanos() % interval.as_nanos()) as u64);
+            assert!(off < Duration::from_millis(5), "inline: {}, off by {:?}", inline, off);
+        }
+    }
+
+    #[test]
+    fn every_yields_fire_times() {
+        let interval = Duration::from_millis(20);
+        let start = Instant::now();
+        let ticks: Vec<Instant> = Ticker::every(interval).take(3).collect();
+        for (n, t) in ticks.iter().enumerate() {
+            let due = start + interval * (n as u32 + 1);
+            assert!(*t + Duration::from_millis(2) >= due, "{:?}", t.duration_since(start));
+            assert!(*t < due + interval / 2, "{:?}", t.duration_since(start));
+        }
+    }
+
+    #[test]
+    fn drain_finishes_unpaced() {
+        let interval = Duration::from_millis(10);
+        let mut iter = (0..103).ticked(interval);
+        let handle = iter.handle();
+        let start = Instant::now();
+        assert_eq!(iter.by_ref().take(3).collect::<Vec<_>>(), [0, 1, 2]);
+        assert!(start.elapsed() + Duration::from_millis(1) >= interval * 3, "{:?}", start.elapsed());
+        assert_eq!(iter.peek(), Some(&3));
+
+        let start = Instant::now();
+        let rest: Vec<_> = iter.drain().collect();
+        assert_eq!(rest, (3..103).collect::<Vec<_>>());
+        assert!(start.elapsed() < interval, "{:?}", start.elapsed());
+        thread::sleep(Duration::from_millis(5));
+        assert!(handle.ctrl.send(Control::Pause).is_err());
+
+        let mut stopped = Ticker::new(0..5, interval).max_ticks(1).inline(true);
+        assert_eq!(stopped.by_ref().count(), 1);
+        assert_eq!(stopped.drain().collect::<Vec<_>>(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn into_inner_loses_nothing() {
+        let interval = Duration::from_millis(10);
+        let mut iter = (0..10).ticked(interval);
+        let handle = iter.handle();
+        assert_eq!(iter.by_ref().take(3).collect::<Vec<_>>(), [0, 1, 2]);
+        let (mut src, held) = iter.into_inner();
+        assert_eq!((held, src.next()), (None, Some(3)));
+        thread::sleep(Duration::from_millis(5));
+        assert!(handle.ctrl.send(Control::Pause).is_err());
+
+        let clock = ManualClock::new();
+        let mut ticker = Ticker::new_with_clock(src, interval, clock.clone()).inline(true).pull_first(true);
+        assert_eq!(ticker.next_timeout(Duration::ZERO), Err(Timeout));
+        ticker.handle().set_interval(interval * 6);
+        let (src, held, builder) = ticker.into_parts();
+        assert_eq!(held, Some(4));
+        let mut ticker = builder.wrap(held.into_iter().chain(src));
+        assert_eq!(ticker.interval(), interval * 6);
+        assert_eq!(ticker.try_next(), Err(NotDue));
+        clock.advance(interval * 6);
+        assert_eq!(ticker.try_next(), Ok(Some(4)));
+        assert_eq!(ticker.drain().collect::<Vec<_>>(), (5..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn clones_carry_on_independently() {
+        let interval = Duration::from_millis(5);
+        let mut ticker = Ticker::new(0..20, interval).max_ticks(8);
+        assert_eq!(ticker.by_ref().take(2).collect::<Vec<_>>(), [0, 1]);
+        assert_eq!(ticker.peek(), Some(&2));
+        let mut clone = ticker.clone();
+        let (original, cloned) = (ticker.handle(), clone.handle());
+
+        // Stopping the clone leaves the original ticking, and its clone
+        // carries on from the same point.
+        let mut other = clone.clone();
+        cloned.stop();
+        assert_eq!(clone.next(), None);
+        assert_eq!(ticker.by_ref().collect::<Vec<_>>(), (2..8).collect::<Vec<_>>());
+        assert_eq!(other.next(), Some(2));
+        assert_eq!(original.stats().items, 8);
+        assert_eq!(other.handle().stats().items, 1);
+
+        // Each has its own worker thread, which stops when it is dropped.
+        let mut iter = (0..).ticked(interval);
+        assert_eq!(iter.next(), Some(0));
+        let mut copy = iter.clone();
+        assert_eq!(copy.next(), Some(1));
+        let workers = [other.handle().ctrl, iter.handle().ctrl];
+        let copy_worker = copy.handle().ctrl;
+        drop((other, iter));
+        thread::sleep(Duration::from_millis(20));
+        for worker in workers.iter() {
+            assert!(worker.send(Control::SetInterval(interval)).is_err());
+        }
+        assert!(copy_worker.send(Control::SetInterval(interval)).is_ok());
+        assert_eq!(copy.next(), Some(2));
+        assert_eq!(Ticker::manual(0..).0.clone().next(), None);
+    }
+
+    #[test]
+    fn every_stops_worker_when_dropped() {
+        let every = Ticker::every(Duration::from_millis(1));
+        let handle = every.handle();
+        drop(every);
+        thread::sleep(Duration::from_millis(5));
+        // The worker is gone, so its end of the control channel is too.
+        assert!(handle.ctrl.send(Control::Pause).is_err());
+    }
+
+    #[test]
+    fn timestamps_are_tick_times() {
+        let interval = Duration::from_millis(10);
+        for &inline in &[false, true] {
+            let iter = Ticker::new(0..5, interval)
+                .max_pending(10)
+                .inline(inline)
+                .timestamped();
+            let mut fired = Vec::new();
+            for (t, i) in iter {
+                assert_eq!(i as usize, fired.len());
+                fired.push(t);
+                thread::sleep(interval * 5 / 2);
+            }
+            for pair in fired.windows(2) {
+                let gap = pair[1] - pair[0];
+                assert!(gap > interval / 2, "inline: {}, {:?}", inline, gap);
+                assert!(gap < interval * 3 / 2, "inline: {}, {:?}", inline, gap);
+            }
+        }
+    }
+
+    /// instant_count counts how many items come back without waiting.
+    fn instant_count<I: Iterator>(iter: &mut I, interval: Duration) -> usize {
+        let mut instant = 0;
+        loop {
+            let start = Instant::now();
+            iter.next();
+            if start.elapsed() > interval / 4 {
+                return instant;
+            }
+            instant += 1;
+        }
+    }
+
+    #[test]
+    fn token_bucket_starts_full() {
+        let interval = Duration::from_millis(20);
+        for &inline in &[false, true] {
+            let mut iter = Ticker::token_bucket(0.., interval, 5).inline(inline);
+            assert_eq!(instant_count(&mut iter, interval), 5, "inline: {}", inline);
+        }
+    }
+
+    /// instant_after_stall counts how many items come back without waiting
+    /// after the consumer stalls for ten intervals. The stall ends a quarter
+    /// interval after a tick, so the next scheduled tick is clearly not instant.
+    fn instant_after_stall(ticker: Ticker<std::ops::RangeFrom<i32>>, interval: Duration) -> usize {
+        let mut iter = ticker;
+        instant_count(&mut iter, interval);
+        thread::sleep(interval * 41 / 4);
+        instant_count(&mut iter, interval)
+    }
+
+    #[test]
+    fn pending_ticks_capped_by_default() {
+        let interval = Duration::from_millis(20);
+        let ticker = Ticker::new(0.., interval);
+        assert_eq!(instant_after_stall(ticker, interval), 1);
+    }
+
+    #[test]
+    fn pending_ticks_capped_at_max_pending() {
+        let interval = Duration::from_millis(20);
+        let ticker = Ticker::new(0.., interval).max_pending(4);
+        assert_eq!(instant_after_stall(ticker, interval), 4);
+    }
 }
@@ -0,0 +1,197 @@
+//! Merging two sources, each rate limited at its own interval.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::wait::{clock_setter, Due, Pull};
+use crate::Clock;
+
+/// Merged is an item from one side of a Merge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Merged<A, B> {
+    /// Left is an item from the first source.
+    Left(A),
+    /// Right is an item from the second source.
+    Right(B),
+}
+
+/// Merge rate limits two Iterators at their own intervals and yields whichever
+/// one's tick comes first, so a fast source and a slow one can be polled from
+/// one loop on one thread. Create one with ````Ticker::merge````.
+///
+/// Each side ticks like a Ticker: its first item comes one interval after the
+/// Merge is created, and the rest once per interval after that. Missed ticks
+/// are not saved up, as the crate docs describe for a side that falls behind.
+/// When both sides are due at once the one with the longer interval goes
+/// first, since it would otherwise wait out every burst of the faster side;
+/// sides with equal intervals take turns.
+///
+/// An item is pulled from a side only when its tick is next, just before
+/// waiting for it. When one side ends the other carries on alone, and the
+/// Merge ends when both have.
+pub struct Merge<A, B> {
+    left: Side<A>,
+    right: Side<B>,
+    /// left_turn is which side goes first when both are due at once with
+    /// equal intervals: the one that did not tick last.
+    left_turn: bool,
+}
+
+/// Side is one source of a Merge, and its schedule.
+struct Side<I> {
+    pull: Pull<I>,
+    interval: Duration,
+    due: Due,
+}
+
+impl<I: Iterator> Side<I> {
+    fn new(src: I, interval: Duration) -> Self {
+        let pull = Pull::new(src);
+        Side {
+            due: Due::since(pull.clock().now(), interval),
+            pull,
+            interval,
+        }
+    }
+
+    /// next_tick is when this side next ticks for a consumer asking at
+    /// ````now````, or None if it never will.
+    fn next_tick(&self, now: Instant) -> Option<Instant> {
+        match self.due {
+            _ if self.pull.is_done() => None,
+            Due::Now => Some(now),
+            Due::At(due) => Some(due.max(now)),
+            Due::Never => None,
+        }
+    }
+
+    /// tick pulls an item and waits for the tick at ````at````, returning
+    /// None if the source has ended.
+    fn tick(&mut self, at: Option<Instant>) -> Option<I::Item> {
+        let Side { pull, interval, due } = self;
+        pull.next(|_, clock| {
+            due.wait(clock);
+            *due = at.map_or(Due::Never, |at| Due::since(at, *interval));
+        })
+    }
+}
+
+impl<A: Iterator, B: Iterator> Merge<A, B> {
+    pub(crate) fn new((left, left_interval): (A, Duration), (right, right_interval): (B, Duration)) -> Self {
+        let mut merge = Merge {
+            left: Side::new(left, left_interval),
+            right: Side::new(right, right_interval),
+            left_turn: true,
+        };
+        merge.start();
+        merge
+    }
+
+    clock_setter!(Merge);
+
+    /// set_clock moves onto ````clock````, starting both schedules over.
+    fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.left.pull.set_clock(clock.clone());
+        self.right.pull.set_clock(clock);
+        self.start();
+    }
+
+    /// start starts both schedules from the same moment.
+    fn start(&mut self) {
+        let start = self.left.pull.clock().now();
+        self.left.due = Due::since(start, self.left.interval);
+        self.right.due = Due::since(start, self.right.interval);
+    }
+
+    /// left_first is whether the left side ticks next.
+    fn left_first(&self, now: Instant) -> bool {
+        let (left, right) = (self.left.next_tick(now), self.right.next_tick(now));
+        match (left, right) {
+            (Some(left), Some(right)) if left == right => {
+                if self.left.interval == self.right.interval {
+                    self.left_turn
+                } else {
+                    self.left.interval > self.right.interval
+                }
+            }
+            (Some(left), Some(right)) => left < right,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            // Neither side will tick again, but one that has not ended still
+            // waits for its tick, forever.
+            (None, None) => !self.left.pull.is_done(),
+        }
+    }
+}
+
+impl<A: Iterator, B: Iterator> Iterator for Merge<A, B> {
+    type Item = Merged<A::Item, B::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.left.pull.is_done() && self.right.pull.is_done() {
+                return None;
+            }
+            let now = self.left.pull.clock().now();
+            let left = self.left_first(now);
+            self.left_turn = !left;
+            if left {
+                let at = self.left.next_tick(now);
+                if let Some(item) = self.left.tick(at) {
+                    return Some(Merged::Left(item));
+                }
+            } else {
+                let at = self.right.next_tick(now);
+                if let Some(item) = self.right.tick(at) {
+                    return Some(Merged::Right(item));
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (left_lo, left_hi) = self.left.pull.size_hint();
+        let (right_lo, right_hi) = self.right.pull.size_hint();
+        let hi = match (left_hi, right_hi) {
+            (Some(a), Some(b)) => a.checked_add(b),
+            _ => None,
+        };
+        (left_lo.saturating_add(right_lo), hi)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::JumpClock;
+    use crate::Ticker;
+    use Merged::{Left, Right};
+
+    #[test]
+    fn interleaves_by_tick() {
+        let ms = Duration::from_millis;
+        let clock = JumpClock::default();
+        let merged: Vec<_> = Ticker::merge((0.., ms(10)), (100.., ms(30))).clock(clock.clone()).take(8).collect();
+        // At 30 ms and 60 ms both sides are due, and the slower goes first.
+        assert_eq!(merged, [Left(0), Left(1), Right(100), Left(2), Left(3), Left(4), Right(101), Left(5)]);
+
+        let merged: Vec<_> = Ticker::merge((0..3, ms(10)), (10..13, ms(10))).clock(clock).collect();
+        assert_eq!(merged, [Left(0), Right(10), Left(1), Right(11), Left(2), Right(12)]);
+    }
+
+    #[test]
+    fn either_side_ending_leaves_the_other() {
+        let ms = Duration::from_millis;
+        let clock = JumpClock::default();
+        let start = clock.now();
+        let mut merged = Ticker::merge((0..2, ms(5)), (10..12, ms(40))).clock(clock.clone());
+        assert_eq!(merged.size_hint(), (4, Some(4)));
+        let items: Vec<_> = merged.by_ref().map(|item| (item, (clock.now() - start).as_millis())).collect();
+        assert_eq!(items, [(Left(0), 5), (Left(1), 10), (Right(10), 40), (Right(11), 80)]);
+        assert_eq!(merged.next(), None);
+
+        let mut merged = Ticker::merge((0..0, ms(5)), (0..5, Duration::ZERO));
+        assert_eq!(merged.by_ref().count(), 5);
+        assert_eq!(merged.size_hint(), (0, Some(0)));
+    }
+}
@@ -0,0 +1,248 @@
+//! Pacing without threads or an allocator, for ````no_std```` targets.
+
+use core::ops::{Add, Sub};
+use core::time::Duration;
+
+use crate::MissedTickBehavior;
+
+/// Sleeper is the clock a Paced Iterator reads and waits on, so that pacing
+/// can run wherever there is some way to tell the time and block, such as a
+/// hardware timer on a microcontroller. Its Instant is any monotonic point in
+/// time that Durations can be added to and measured between; a Duration
+/// since boot will do.
+///
+/// With the ````std```` feature every Clock is a Sleeper, which sleeps the
+/// thread.
+pub trait Sleeper {
+    /// Instant is a point in time on this Sleeper's clock.
+    type Instant: Copy + Ord + Add<Duration, Output = Self::Instant> + Sub<Output = Duration>;
+
+    /// now is the current time.
+    fn now(&mut self) -> Self::Instant;
+
+    /// sleep_until blocks until ````deadline````, returning at once if it has
+    /// already passed.
+    fn sleep_until(&mut self, deadline: Self::Instant);
+}
+
+/// catch_up is where a schedule goes when a consumer arrives at ````now````,
+/// late for the tick due at ````next````: it returns when the tick after the
+/// consumer's is due, and how many ticks were dropped. At most
+/// ````max_pending```` ticks could have been waiting for the consumer, as
+/// ````MissedTickBehavior::Burst```` saves up.
+pub(crate) fn catch_up<T>(behavior: MissedTickBehavior,
+                          max_pending: usize,
+                          interval: Duration,
+                          next: T,
+                          now: T)
+                          -> (T, u32)
+where
+    T: Copy + Ord + Add<Duration, Output = T> + Sub<Output = Duration>,
+{
+    let late = if now > next { now - next } else { Duration::ZERO };
+    let missed = late.as_nanos() / interval.as_nanos() + 1;
+    let missed = missed.min(u32::MAX as u128) as u32;
+    match behavior {
+        MissedTickBehavior::Burst => {
+            let dropped = missed.saturating_sub(max_pending.min(u32::MAX as usize) as u32);
+            (next + interval * (dropped + 1), dropped)
+        }
+        MissedTickBehavior::Skip => (next + interval * missed, missed - 1),
+        MissedTickBehavior::Delay => (now + interval, missed - 1),
+    }
+}
+
+/// Paced rate limits an Iterator on a Sleeper, returning from ````.next()````
+/// at most once every interval. It is the pacing of a Ticker without its
+/// worker thread, handle or hooks, so it needs nothing but ````core````: the
+/// consumer's own thread sleeps on the Sleeper between items.
+///
+/// As with a Ticker, the first item comes one interval after the Paced is
+/// created. A tick missed by a late consumer is handled as its
+/// MissedTickBehavior says, with at most one tick ever waiting.
+///
+/// ````no_run
+/// # use core::time::Duration;
+/// use ticker::{Paced, Sleeper};
+///
+/// /// Uptime is a timer counting microseconds since boot.
+/// struct Uptime;
+///
+/// impl Sleeper for Uptime {
+///     type Instant = Duration;
+///
+///     fn now(&mut self) -> Duration {
+///         # let read_timer = || 0;
+///         Duration::from_micros(read_timer())
+///     }
+///
+///     fn sleep_until(&mut self, deadline: Duration) {
+///         while self.now() < deadline {
+///             core::hint::spin_loop()
+///         }
+///     }
+/// }
+///
+/// # fn blink(_: i32) {}
+/// for i in Paced::new(0..10, Duration::from_millis(500), Uptime) {
+///     blink(i)
+/// }
+/// ````
+pub struct Paced<I, S: Sleeper> {
+    src: I,
+    sleeper: S,
+    interval: Duration,
+    behavior: MissedTickBehavior,
+    start: S::Instant,
+    next: S::Instant,
+    done: bool,
+}
+
+impl<I: Iterator, S: Sleeper> Paced<I, S> {
+    /// new creates a Paced which will rate limit ````src```` to once every
+    /// ````interval```` on ````sleeper````.
+    pub fn new(src: I, interval: Duration, mut sleeper: S) -> Self {
+        let start = sleeper.now();
+        Paced {
+            src,
+            sleeper,
+            interval,
+            behavior: MissedTickBehavior::default(),
+            start,
+            next: start + interval,
+            done: false,
+        }
+    }
+
+    /// first_tick_immediately sets whether the first item is returned as soon
+    /// as it is asked for, instead of one interval after the Paced was
+    /// created.
+    pub fn first_tick_immediately(mut self, immediate: bool) -> Self {
+        self.next = if immediate { self.start } else { self.start + self.interval };
+        self
+    }
+
+    /// missed_tick_behavior sets what happens to ticks that come due while the
+    /// consumer is busy. Defaults to ````MissedTickBehavior::Burst````.
+    pub fn missed_tick_behavior(mut self, behavior: MissedTickBehavior) -> Self {
+        self.behavior = behavior;
+        self
+    }
+}
+
+impl<I: Iterator, S: Sleeper> Iterator for Paced<I, S> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = match self.src.next() {
+            Some(item) => item,
+            None => {
+                self.done = true;
+                return None;
+            }
+        };
+        let now = self.sleeper.now();
+        let due = self.next;
+        if now < due || self.interval == Duration::ZERO {
+            self.next = due + self.interval;
+            self.sleeper.sleep_until(due);
+        } else {
+            self.next = catch_up(self.behavior, 1, self.interval, due, now).0;
+        }
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            (0, Some(0))
+        } else {
+            self.src.size_hint()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fake is a Sleeper whose time only moves when it sleeps or is worked.
+    #[derive(Default)]
+    struct Fake {
+        now: Duration,
+        slept: u32,
+    }
+
+    impl Sleeper for &mut Fake {
+        type Instant = Duration;
+
+        fn now(&mut self) -> Duration {
+            self.now
+        }
+
+        fn sleep_until(&mut self, deadline: Duration) {
+            if deadline > self.now {
+                self.now = deadline;
+                self.slept += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn catches_up_as_told() {
+        let s = Duration::from_secs;
+        let late = |behavior, max_pending| catch_up(behavior, max_pending, s(10), s(100), s(135));
+        // Ticks were due at 100, 110, 120 and 130 when the consumer came.
+        assert_eq!(late(MissedTickBehavior::Burst, 1), (s(140), 3));
+        assert_eq!(late(MissedTickBehavior::Burst, 2), (s(130), 2));
+        assert_eq!(late(MissedTickBehavior::Burst, 8), (s(110), 0));
+        assert_eq!(late(MissedTickBehavior::Skip, 8), (s(140), 3));
+        assert_eq!(late(MissedTickBehavior::Delay, 8), (s(145), 3));
+        // Arriving right on time drops nothing.
+        assert_eq!(catch_up(MissedTickBehavior::Delay, 1, s(10), s(100), s(100)), (s(110), 0));
+        assert_eq!(catch_up(MissedTickBehavior::Skip, 1, s(10), s(100), s(100)), (s(110), 0));
+    }
+
+    #[test]
+    fn paces_on_the_sleeper() {
+        let ms = Duration::from_millis;
+        let mut fake = Fake::default();
+        let mut paced = Paced::new(0..5, ms(100), &mut fake);
+        assert_eq!(paced.next(), Some(0));
+        assert_eq!(paced.sleeper.now, ms(100));
+        assert_eq!(paced.next(), Some(1));
+        assert_eq!(paced.sleeper.now, ms(200));
+
+        // A consumer busy for 250 ms gets the tick due at 300 at once, and
+        // the next on the original schedule.
+        paced.sleeper.now = ms(450);
+        assert_eq!(paced.next(), Some(2));
+        assert_eq!(paced.sleeper.now, ms(450));
+        assert_eq!(paced.next(), Some(3));
+        assert_eq!(paced.sleeper.now, ms(500));
+        assert_eq!(paced.by_ref().count(), 1);
+        assert_eq!(paced.next(), None);
+        assert_eq!((fake.now, fake.slept), (ms(600), 4));
+    }
+
+    #[test]
+    fn options_apply() {
+        let ms = Duration::from_millis;
+        let mut fake = Fake::default();
+        let mut paced = Paced::new(0.., ms(100), &mut fake)
+            .first_tick_immediately(true)
+            .missed_tick_behavior(MissedTickBehavior::Delay);
+        assert_eq!(paced.next(), Some(0));
+        assert_eq!(paced.sleeper.now, Duration::ZERO);
+        paced.sleeper.now = ms(250);
+        assert_eq!(paced.next(), Some(1));
+        assert_eq!(paced.next(), Some(2));
+        assert_eq!(paced.sleeper.now, ms(350));
+
+        let mut fake = Fake::default();
+        assert_eq!(Paced::new(0..1000, Duration::ZERO, &mut fake).count(), 1000);
+        assert_eq!((fake.now, fake.slept), (Duration::ZERO, 0));
+    }
+}
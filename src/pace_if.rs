@@ -0,0 +1,366 @@
+//! Rate limiting only some of a source's items.
+
+use std::iter::{self, FusedIterator};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::wait::Due;
+use crate::{Cost, Ticker, TickerHandle, TickerStats};
+
+/// PaceIf is a Ticker that paces only the items of its source that match a
+/// predicate. Every other item is free: it is returned as soon as it is
+/// pulled, without waiting and without taking a tick, so the next matching
+/// item is still spaced from the last one. A paced item costs what the
+/// Ticker's cost says, as under ````Ticker::with_pacer_cost````. Create one with
+/// ````Ticker::pace_if````, or from a configured Ticker with
+/// ````.rate_limit_if()````.
+///
+/// A source whose items keep failing the predicate gets them back to back,
+/// with a ````thread::yield_now()```` between consecutive free items so the
+/// loop cannot hog its CPU; set a ````.floor()```` to space them out as well.
+/// Iteration ends when the source does, or when the Ticker ends, whether or
+/// not the next item is free.
+pub struct PaceIf<I: Iterator, P> {
+    src: I,
+    /// held is an item the Ticker had already pulled when it was converted.
+    held: Option<I::Item>,
+    ticks: Ticker<iter::Repeat<()>>,
+    pred: P,
+    /// cost is the Ticker's cost, and the cell its ticks read it from.
+    cost: Option<(Cost<I::Item>, Arc<AtomicU32>)>,
+    floor: Duration,
+    /// due is when the next free item may be returned, under a floor.
+    due: Due,
+    /// free counts the free items returned since the last paced one.
+    free: usize,
+}
+
+/// OnlyOk is a PaceIf over a source of Results that paces only its ````Ok````
+/// items, so a failure never costs a slot. Create one with
+/// ````Ticker::ok_rate_limited````, or from a configured Ticker with
+/// ````.rate_limit_only_ok()````.
+pub type OnlyOk<I> = PaceIf<I, fn(&<I as Iterator>::Item) -> bool>;
+
+/// OnlySome is a PaceIf over a source of Options that paces only its
+/// ````Some```` items, for polling that mostly finds nothing. Create one with
+/// ````Ticker::some_rate_limited````, or from a configured Ticker with
+/// ````.rate_limit_only_some()````.
+pub type OnlySome<I> = PaceIf<I, fn(&<I as Iterator>::Item) -> bool>;
+
+impl<I: Iterator> Ticker<I> {
+    /// pace_if creates a Ticker that paces the items of ````src```` that
+    /// ````pred```` returns true for every ````interval````, passing the rest
+    /// straight through. See PaceIf.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn poll() -> Vec<u8> { vec![] }
+    /// # fn process(_: Vec<u8>) {}
+    /// // Poll again at once after an empty batch, but process at most one
+    /// // batch a second.
+    /// let batches = Ticker::pace_if(std::iter::repeat_with(poll), Duration::from_secs(1), |b| !b.is_empty())
+    ///     .floor(Duration::from_millis(10));
+    /// for batch in batches {
+    ///     process(batch)
+    /// }
+    /// ````
+    pub fn pace_if<P>(src: I, interval: Duration, pred: P) -> PaceIf<I, P>
+    where
+        P: FnMut(&I::Item) -> bool,
+    {
+        Ticker::new(src, interval).rate_limit_if(pred)
+    }
+
+    /// rate_limit_if turns this Ticker into one that paces only the items
+    /// ````pred```` returns true for, keeping its settings and schedule. See
+    /// PaceIf.
+    pub fn rate_limit_if<P>(self, pred: P) -> PaceIf<I, P>
+    where
+        P: FnMut(&I::Item) -> bool,
+    {
+        let Ticker { src, done, config, pacer, cost, ctrl, ctrl_recv, shared, start, backend, item, ticked, burst, burst_end } = self;
+        let cost = cost.map(|cost| (cost, Arc::new(AtomicU32::new(1))));
+        let ticks_cost: Option<Cost<()>> = cost.as_ref().map(|(_, next)| {
+            let next = next.clone();
+            Arc::new(move |_: &()| next.load(Ordering::SeqCst)) as Cost<()>
+        });
+        PaceIf {
+            src,
+            held: item,
+            ticks: Ticker {
+                src: iter::repeat(()),
+                done,
+                config,
+                pacer,
+                cost: ticks_cost,
+                ctrl,
+                ctrl_recv,
+                shared,
+                start,
+                backend,
+                item: None,
+                ticked,
+                burst,
+                burst_end,
+            },
+            pred,
+            cost,
+            floor: Duration::ZERO,
+            due: Due::Now,
+            free: 0,
+        }
+    }
+}
+
+impl<I, T, E> Ticker<I>
+where
+    I: Iterator<Item = Result<T, E>>,
+{
+    /// ok_rate_limited creates a Ticker that paces the ````Ok```` items of
+    /// ````src```` every ````interval````, passing each ````Err```` straight
+    /// through. See OnlyOk.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn lines() -> impl Iterator<Item = Result<String, std::io::Error>> { std::iter::empty() }
+    /// # fn submit(_: String) {}
+    /// for line in Ticker::ok_rate_limited(lines(), Duration::from_millis(100)) {
+    ///     match line {
+    ///         Ok(line) => submit(line),
+    ///         Err(e) => eprintln!("skipping bad line: {}", e),
+    ///     }
+    /// }
+    /// ````
+    pub fn ok_rate_limited(src: I, interval: Duration) -> OnlyOk<I> {
+        Ticker::new(src, interval).rate_limit_only_ok()
+    }
+
+    /// rate_limit_only_ok is ````.rate_limit_if()```` for ````Ok```` items.
+    /// See OnlyOk.
+    pub fn rate_limit_only_ok(self) -> OnlyOk<I> {
+        self.rate_limit_if(Result::is_ok as fn(&Result<T, E>) -> bool)
+    }
+}
+
+impl<I, T> Ticker<I>
+where
+    I: Iterator<Item = Option<T>>,
+{
+    /// some_rate_limited creates a Ticker that paces the ````Some```` items
+    /// of ````src```` every ````interval````, passing each ````None````
+    /// straight through. See OnlySome.
+    ///
+    /// ````no_run
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn check_inbox() -> Option<String> { None }
+    /// # fn reply(_: String) {}
+    /// let polls = Ticker::some_rate_limited(std::iter::repeat_with(check_inbox), Duration::from_secs(5))
+    ///     .floor(Duration::from_millis(50));
+    /// for message in polls.flatten() {
+    ///     reply(message)
+    /// }
+    /// ````
+    pub fn some_rate_limited(src: I, interval: Duration) -> OnlySome<I> {
+        Ticker::new(src, interval).rate_limit_only_some()
+    }
+
+    /// rate_limit_only_some is ````.rate_limit_if()```` for ````Some````
+    /// items. See OnlySome.
+    pub fn rate_limit_only_some(self) -> OnlySome<I> {
+        self.rate_limit_if(Option::is_some as fn(&Option<T>) -> bool)
+    }
+}
+
+impl<I: Iterator, P> PaceIf<I, P> {
+    /// floor sets the least time between a free item and the item before
+    /// it, so a source that never matches still cannot be polled flat out.
+    /// Defaults to zero.
+    pub fn floor(mut self, floor: Duration) -> Self {
+        self.floor = floor;
+        self
+    }
+
+    /// handle is ````Ticker::handle````.
+    pub fn handle(&self) -> TickerHandle {
+        self.ticks.handle()
+    }
+
+    /// stats is ````Ticker::stats````. Free items are not counted.
+    pub fn stats(&self) -> TickerStats {
+        self.ticks.stats()
+    }
+}
+
+impl<I, P> Iterator for PaceIf<I, P>
+where
+    I: Iterator,
+    P: FnMut(&I::Item) -> bool,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.ticks.done || self.ticks.shared.stopped.load(Ordering::SeqCst) {
+            return None;
+        }
+        let item = match self.held.take().or_else(|| self.src.next()) {
+            Some(item) => item,
+            None => {
+                self.ticks.done = true;
+                return None;
+            }
+        };
+        if (self.pred)(&item) {
+            self.free = 0;
+            if let Some((ref cost, ref next)) = self.cost {
+                next.store(cost(&item), Ordering::SeqCst);
+            }
+            self.ticks.next_tick()?;
+        } else {
+            if self.floor > Duration::ZERO {
+                self.due.wait(&*self.ticks.shared.clock);
+            } else if self.free > 0 {
+                thread::yield_now();
+            }
+            self.free += 1;
+        }
+        if self.floor > Duration::ZERO {
+            self.due = Due::after(&*self.ticks.shared.clock, self.floor);
+        }
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.ticks.done {
+            return (0, Some(0));
+        }
+        let held = self.held.is_some() as usize;
+        (0, self.src.size_hint().1.and_then(|hi| hi.checked_add(held)))
+    }
+}
+
+impl<I, P> FusedIterator for PaceIf<I, P>
+where
+    I: Iterator,
+    P: FnMut(&I::Item) -> bool,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::JumpClock;
+    use crate::{Clock, FixedInterval, ManualClock};
+    use std::time::Instant;
+
+    #[test]
+    fn errors_take_no_ticks() {
+        let clock = ManualClock::new();
+        let src = vec![Err('a'), Ok(0), Err('b'), Err('c'), Ok(1)];
+        let mut iter = Ticker::new_with_clock(src.into_iter(), Duration::from_secs(60), clock.clone())
+            .inline(true)
+            .rate_limit_only_ok();
+        assert_eq!(iter.next(), Some(Err('a')));
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(iter.next(), Some(Ok(0)));
+        assert_eq!(iter.next(), Some(Err('b')));
+        assert_eq!(iter.next(), Some(Err('c')));
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(iter.next(), Some(Ok(1)));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.stats().ticks, 2);
+    }
+
+    #[test]
+    fn only_oks_are_spaced() {
+        let interval = Duration::from_millis(10);
+        let src = iter::once(Ok(0))
+            .chain((0..100).map(|_| Err(())))
+            .chain(vec![Ok(1), Err(()), Ok(2)]);
+        let start = Instant::now();
+        let mut oks = Vec::new();
+        let mut errors = 0;
+        for item in Ticker::ok_rate_limited(src, interval) {
+            match item {
+                Ok(_) => oks.push(start.elapsed()),
+                Err(()) => errors += 1,
+            }
+        }
+        assert_eq!(errors, 101);
+        assert_eq!(oks.len(), 3);
+        for (n, arrival) in oks.iter().enumerate() {
+            assert!(*arrival + Duration::from_millis(1) >= interval * (n as u32 + 1), "{:?}", oks);
+        }
+    }
+
+    #[test]
+    fn ends_with_the_ticker() {
+        let mut iter = Ticker::new((0..).map(|i| if i % 2 == 0 { Ok(i) } else { Err(i) }), Duration::ZERO)
+            .max_ticks(2)
+            .rate_limit_only_ok();
+        let items: Vec<_> = iter.by_ref().collect();
+        assert_eq!(items, [Ok(0), Err(1), Ok(2)]);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn free_items_do_not_wait() {
+        let interval = Duration::from_millis(20);
+        let src = (0..12).map(|i| if i % 4 == 3 { Some(i) } else { None });
+        let start = Instant::now();
+        let arrivals: Vec<_> = Ticker::some_rate_limited(src, interval)
+            .map(|item| (item, start.elapsed()))
+            .collect();
+        assert_eq!(arrivals.len(), 12);
+        let paced: Vec<_> = arrivals.iter().filter(|a| a.0.is_some()).map(|a| a.1).collect();
+        for (n, arrival) in paced.iter().enumerate() {
+            assert!(*arrival + Duration::from_millis(1) >= interval * (n as u32 + 1), "{:?}", arrivals);
+        }
+        // Nine free items and three ticks take three intervals, not twelve.
+        assert!(start.elapsed() < interval * 8, "{:?}", arrivals);
+    }
+
+    #[test]
+    fn floor_spaces_free_items() {
+        let floor = Duration::from_millis(10);
+        let clock = JumpClock::default();
+        let start = clock.now();
+        let arrivals: Vec<_> = Ticker::new_with_clock(0..5, Duration::from_secs(3600), clock.clone())
+            .inline(true)
+            .rate_limit_if(|_| false)
+            .floor(floor)
+            .map(|_| clock.now() - start)
+            .collect();
+        assert_eq!(arrivals, [Duration::ZERO, floor, floor * 2, floor * 3, floor * 4]);
+
+        // The floor follows paced items too, on the Ticker's clock.
+        let interval = Duration::from_millis(30);
+        let start = clock.now();
+        let arrivals: Vec<_> = Ticker::new_with_clock(0..4, interval, clock.clone())
+            .inline(true)
+            .rate_limit_if(|i| i % 2 == 1)
+            .floor(floor)
+            .map(|_| clock.now() - start)
+            .collect();
+        assert_eq!(arrivals, [Duration::ZERO, interval, interval + floor, interval * 2]);
+    }
+
+    #[test]
+    fn paced_items_keep_their_cost() {
+        let interval = Duration::from_secs(1);
+        let clock = JumpClock::default();
+        let start = clock.now();
+        let src = vec![Some(3), None, Some(1), Some(1)];
+        let arrivals: Vec<_> = Ticker::with_pacer_cost(src.into_iter(), FixedInterval::new(interval), |c: &Option<u32>| c.unwrap_or(0))
+            .clock(clock.clone())
+            .rate_limit_only_some()
+            .map(|item| (item, clock.now() - start))
+            .collect();
+        assert_eq!(arrivals,
+                   [(Some(3), interval), (None, interval), (Some(1), interval * 4), (Some(1), interval * 5)]);
+    }
+}
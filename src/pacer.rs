@@ -0,0 +1,433 @@
+//! Rate limiting by a pluggable strategy.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::pace;
+use crate::MissedTickBehavior;
+
+/// Pacer is a rate limiting strategy: it decides how long each item waits.
+/// Rate limit an Iterator with one using ````Ticker::with_pacer````, which
+/// makes a Ticker like any other, on its Clock, with its handle, stats and
+/// hooks, inline or on a worker thread. The strategies here are
+/// FixedInterval, which ````Ticker::new```` uses, TokenBucket and Gcra;
+/// implement Pacer to plug in another.
+///
+/// ````no_run
+/// # use ticker::{Pacer, Ticker};
+/// # use std::time::{Duration, Instant};
+/// # fn send(_: i32) {}
+/// /// Backoff doubles the gap after every item, up to a minute.
+/// struct Backoff(Duration);
+///
+/// impl Pacer for Backoff {
+///     fn next_delay(&mut self, _: Instant, cost: u32) -> Duration {
+///         let delay = self.0 * cost;
+///         self.0 = (self.0 * 2).min(Duration::from_secs(60));
+///         delay
+///     }
+/// }
+///
+/// for i in Ticker::with_pacer(0..10, Backoff(Duration::from_millis(10))) {
+///     send(i)
+/// }
+/// ````
+pub trait Pacer {
+    /// next_delay is how long after ````now```` the next item may be
+    /// returned, for an item costing ````cost````, which counts as returned
+    /// once the delay is up. An inline Ticker asks once per item, as it
+    /// starts waiting for it; a worker thread asks once per tick, at a cost
+    /// of 1, as soon as there is room for another tick to wait.
+    fn next_delay(&mut self, now: Instant, cost: u32) -> Duration;
+
+    /// on_idle is told when the consumer comes back for another item, before
+    /// it is pulled from the source, which may take a while, or waited for. A Pacer that
+    /// treats time spent away specially can use it; the default does
+    /// nothing.
+    fn on_idle(&mut self, now: Instant) {
+        let _ = now;
+    }
+
+    /// set_interval is told each interval given to
+    /// ````TickerHandle::set_interval````, for a Pacer with a rate to change.
+    /// The default ignores it.
+    fn set_interval(&mut self, interval: Duration) {
+        let _ = interval;
+    }
+
+    /// fixed is the interval of a Pacer that lets an item through once
+    /// every interval, which a Ticker keeps on its own schedule instead of
+    /// asking about each item, with its missed tick behavior and other
+    /// settings. FixedInterval returns its interval; the default is None.
+    fn fixed(&self) -> Option<Duration> {
+        None
+    }
+}
+
+impl<P: Pacer + ?Sized> Pacer for Box<P> {
+    fn next_delay(&mut self, now: Instant, cost: u32) -> Duration {
+        (**self).next_delay(now, cost)
+    }
+
+    fn on_idle(&mut self, now: Instant) {
+        (**self).on_idle(now)
+    }
+
+    fn set_interval(&mut self, interval: Duration) {
+        (**self).set_interval(interval)
+    }
+
+    fn fixed(&self) -> Option<Duration> {
+        (**self).fixed()
+    }
+}
+
+/// SharedPacer is a Ticker's Pacer, as its schedule or worker asks it.
+pub(crate) type SharedPacer = Arc<Mutex<dyn Pacer + Send>>;
+
+/// lock locks a Pacer, even if a panic left it poisoned.
+pub(crate) fn lock<P: ?Sized>(pacer: &Mutex<P>) -> MutexGuard<'_, P> {
+    pacer.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// FixedInterval paces items to once every interval. It is what
+/// ````Ticker::new```` runs: a Ticker keeps a FixedInterval on its own
+/// schedule, with the missed tick behavior, jitter and other settings
+/// described there, boxed or not, and with item costs too.
+///
+/// Asked on its own, the first item comes one interval after it is first
+/// asked for, and a late consumer gets its item at once, with the next on
+/// the original schedule, as under ````MissedTickBehavior::Skip````. An item
+/// costing ````n```` takes ````n```` intervals.
+#[derive(Clone, Debug)]
+pub struct FixedInterval {
+    interval: Duration,
+    started: bool,
+    /// next is when the next item is due once started, or None if that is
+    /// too far off to represent.
+    next: Option<Instant>,
+}
+
+impl FixedInterval {
+    /// new creates a FixedInterval returning an item every ````interval````.
+    pub fn new(interval: Duration) -> Self {
+        FixedInterval {
+            interval,
+            started: false,
+            next: None,
+        }
+    }
+}
+
+impl Pacer for FixedInterval {
+    fn next_delay(&mut self, now: Instant, cost: u32) -> Duration {
+        if !self.started {
+            self.started = true;
+            self.next = now.checked_add(self.interval);
+        }
+        let due = match self.next {
+            Some(due) => due,
+            None => return Duration::MAX,
+        };
+        // A late item takes the latest slot that has come, as the tick a
+        // Ticker skipping its missed ticks would return.
+        let at = if now > due && self.interval > Duration::ZERO && now.checked_add(self.interval).is_some() {
+            let (after, _) = pace::catch_up(MissedTickBehavior::Skip, 1, self.interval, due, now);
+            after - self.interval
+        } else {
+            due
+        };
+        self.next = self.interval.checked_mul(cost).and_then(|taken| at.checked_add(taken));
+        at.saturating_duration_since(now)
+    }
+
+    /// set_interval moves the next item to ````interval```` after the one
+    /// before it, as a Ticker's handle does.
+    fn set_interval(&mut self, interval: Duration) {
+        self.next = self.next.and_then(|next| crate::rebase(next, self.interval, interval));
+        self.interval = interval;
+    }
+
+    fn fixed(&self) -> Option<Duration> {
+        Some(self.interval)
+    }
+}
+
+/// TokenBucket paces items with a bucket of ````capacity```` tokens, which
+/// starts full and gains one every ````refill````. Each item takes as many
+/// tokens as it costs, waiting for them if the bucket is short, so up to
+/// ````capacity```` items can go back to back after a quiet spell while the
+/// long run rate stays one per ````refill````. An item costing more than the
+/// capacity waits for a full bucket.
+#[derive(Clone, Debug)]
+pub struct TokenBucket {
+    capacity: u32,
+    refill: Duration,
+    tokens: u32,
+    /// filled is when the tokens were last counted, once the first item has
+    /// been asked for; time since then is still to be added.
+    filled: Option<Instant>,
+}
+
+impl TokenBucket {
+    /// new creates a full TokenBucket holding up to ````capacity```` tokens,
+    /// gaining one every ````refill````.
+    ///
+    /// Panics if ````capacity```` is 0.
+    pub fn new(capacity: u32, refill: Duration) -> Self {
+        assert!(capacity > 0, "a token bucket needs room for at least 1 token");
+        TokenBucket {
+            capacity,
+            refill,
+            tokens: capacity,
+            filled: None,
+        }
+    }
+
+    /// fill adds the tokens gained by ````now````.
+    fn fill(&mut self, now: Instant) {
+        let filled = *self.filled.get_or_insert(now);
+        let elapsed = now.saturating_duration_since(filled);
+        if self.refill == Duration::ZERO {
+            self.tokens = self.capacity;
+            self.filled = Some(now);
+            return;
+        }
+        let gained = (elapsed.as_nanos() / self.refill.as_nanos()).min(u128::from(self.capacity)) as u32;
+        self.tokens = self.tokens.saturating_add(gained).min(self.capacity);
+        self.filled = Some(if self.tokens == self.capacity { now } else { filled + self.refill * gained });
+    }
+}
+
+impl Pacer for TokenBucket {
+    fn next_delay(&mut self, now: Instant, cost: u32) -> Duration {
+        self.fill(now);
+        let cost = cost.min(self.capacity);
+        if self.tokens >= cost {
+            self.tokens -= cost;
+            return Duration::ZERO;
+        }
+        // Wait for the missing tokens, which are spent as they come.
+        let short = cost - self.tokens;
+        let filled = self.filled.unwrap_or(now);
+        let ready = self.refill.checked_mul(short).and_then(|wait| filled.checked_add(wait));
+        self.tokens = 0;
+        match ready {
+            Some(ready) => {
+                self.filled = Some(ready);
+                ready.saturating_duration_since(now)
+            }
+            None => Duration::MAX,
+        }
+    }
+
+    /// set_interval makes ````interval```` the time to gain a token.
+    fn set_interval(&mut self, interval: Duration) {
+        self.refill = interval;
+    }
+}
+
+/// Gcra paces items with the generic cell rate algorithm, a leaky bucket
+/// kept as a single timestamp: one item every ````interval````, with up to
+/// ````burst```` allowed back to back after a quiet spell. An item costing
+/// ````n```` counts as ````n```` items, up to ````burst````. It allows what a
+/// TokenBucket of the same size and rate does, but remembers only when the
+/// bucket drains.
+#[derive(Clone, Debug)]
+pub struct Gcra {
+    interval: Duration,
+    burst: u32,
+    /// tolerance is how far ahead of the schedule items may run.
+    tolerance: Duration,
+    /// tat is the theoretical arrival time: when the bucket drains empty.
+    tat: Option<Instant>,
+}
+
+impl Gcra {
+    /// new creates a Gcra returning one item every ````interval````, in
+    /// bursts of up to ````burst````.
+    ///
+    /// Panics if ````burst```` is 0.
+    pub fn new(interval: Duration, burst: u32) -> Self {
+        assert!(burst > 0, "gcra needs a burst of at least 1");
+        Gcra {
+            interval,
+            burst,
+            tolerance: interval.checked_mul(burst - 1).unwrap_or(Duration::MAX),
+            tat: None,
+        }
+    }
+}
+
+impl Pacer for Gcra {
+    fn next_delay(&mut self, now: Instant, cost: u32) -> Duration {
+        if cost == 0 {
+            return Duration::ZERO;
+        }
+        let cost = cost.min(self.burst);
+        let tat = self.tat.map_or(now, |tat| tat.max(now));
+        let ahead = tat - now;
+        let increment = self.interval.checked_mul(cost).unwrap_or(Duration::MAX);
+        let delay = ahead.saturating_add(increment).saturating_sub(self.tolerance.saturating_add(self.interval));
+        self.tat = Some(tat.checked_add(increment).unwrap_or(tat));
+        delay
+    }
+
+    /// set_interval makes ````interval```` the time between items, keeping
+    /// the burst.
+    fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+        self.tolerance = interval.checked_mul(self.burst - 1).unwrap_or(Duration::MAX);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{Clock, JumpClock};
+    use crate::Ticker;
+
+    /// delays asks ````pacer```` for the delays of items arriving at each of
+    /// ````arrivals````, in milliseconds, each costing 1, and returns them in
+    /// milliseconds.
+    fn delays<P: Pacer>(pacer: &mut P, arrivals: &[u64]) -> Vec<u64> {
+        let start = Instant::now();
+        arrivals.iter()
+            .map(|&at| pacer.next_delay(start + Duration::from_millis(at), 1).as_millis() as u64)
+            .collect()
+    }
+
+    /// fired runs a Ticker paced by ````pacer```` over 5 items on a
+    /// JumpClock, inline or on its worker thread, and returns when each tick
+    /// fired, in milliseconds from the start.
+    fn fired<P: Pacer + Send + 'static>(pacer: P, inline: bool) -> Vec<u64> {
+        let clock = JumpClock::default();
+        let start = clock.now();
+        Ticker::with_pacer(0..5, pacer)
+            .clock(clock)
+            .inline(inline)
+            .timestamped()
+            .map(|(fired, _)| (fired - start).as_millis() as u64)
+            .collect()
+    }
+
+    /// late returns when each of four ticks fired, from a Ticker with
+    /// ````pacer```` whose consumer comes back 45 ms late after the first.
+    fn late<P: Pacer + Send + 'static>(pacer: P, costed: bool) -> Vec<u64> {
+        let clock = JumpClock::default();
+        let start = clock.now();
+        let ticker = if costed {
+            Ticker::with_pacer_cost(0..4, pacer, |_| 1)
+        } else {
+            Ticker::with_pacer(0..4, pacer)
+        };
+        let ticks = ticker.clock(clock.clone()).inline(true).max_pending(2).timestamped();
+        let mut fired = Vec::new();
+        for (at, _) in ticks {
+            fired.push((at - start).as_millis() as u64);
+            if fired.len() == 1 {
+                clock.0.advance(Duration::from_millis(45));
+            }
+        }
+        fired
+    }
+
+    #[test]
+    fn fixed_interval_keeps_its_schedule() {
+        let mut fixed = FixedInterval::new(Duration::from_millis(100));
+        // Items asked for at once wait their turn; one asked for 250 ms late
+        // goes at once, and the next keeps to the schedule.
+        assert_eq!(delays(&mut fixed, &[0, 100, 250, 650, 650, 820]), [100, 100, 50, 0, 50, 0]);
+
+        let mut weighted = FixedInterval::new(Duration::from_millis(10));
+        let start = Instant::now();
+        assert_eq!(weighted.next_delay(start, 3), Duration::from_millis(10));
+        assert_eq!(weighted.next_delay(start, 0), Duration::from_millis(40));
+        assert_eq!(weighted.next_delay(start, 1), Duration::from_millis(40));
+
+        let mut huge = FixedInterval::new(Duration::from_secs(1 << 40));
+        assert_eq!(huge.next_delay(start, u32::MAX), Duration::from_secs(1 << 40));
+        assert_eq!(huge.next_delay(start, 1), Duration::MAX);
+    }
+
+    #[test]
+    fn token_bucket_bursts_then_refills() {
+        let mut bucket = TokenBucket::new(3, Duration::from_millis(100));
+        // Three go at once, then one per refill; a quiet spell refills the
+        // bucket, but never past its capacity.
+        assert_eq!(delays(&mut bucket, &[0, 0, 0, 0, 100, 250, 1000, 1000, 1000, 1000]),
+                   [0, 0, 0, 100, 100, 50, 0, 0, 0, 100]);
+
+        let mut bucket = TokenBucket::new(2, Duration::from_millis(10));
+        let start = Instant::now();
+        assert_eq!(bucket.next_delay(start, 5), Duration::ZERO);
+        assert_eq!(bucket.next_delay(start, 1), Duration::from_millis(10));
+        assert!(std::panic::catch_unwind(|| TokenBucket::new(0, Duration::from_secs(1))).is_err());
+    }
+
+    #[test]
+    fn gcra_matches_a_token_bucket() {
+        let arrivals = [0, 0, 0, 0, 100, 250, 1000, 1000, 1000, 1000, 1010, 1500];
+        let mut gcra = Gcra::new(Duration::from_millis(100), 3);
+        let mut bucket = TokenBucket::new(3, Duration::from_millis(100));
+        assert_eq!(delays(&mut gcra, &arrivals), delays(&mut bucket, &arrivals));
+
+        let mut strict = Gcra::new(Duration::from_millis(10), 1);
+        let start = Instant::now();
+        assert_eq!(strict.next_delay(start, 1), Duration::ZERO);
+        assert_eq!(strict.next_delay(start, 4), Duration::from_millis(10));
+        assert_eq!(strict.next_delay(start, 0), Duration::ZERO);
+        assert_eq!(strict.next_delay(start, 1), Duration::from_millis(20));
+        assert!(std::panic::catch_unwind(|| Gcra::new(Duration::from_secs(1), 0)).is_err());
+    }
+
+    #[test]
+    fn pacers_pace_end_to_end() {
+        let ms = Duration::from_millis;
+        // Ticker::new as a Pacer, on its own schedule.
+        assert_eq!(fired(FixedInterval::new(ms(10)), true), [10, 20, 30, 40, 50]);
+        // 2 at once, then 3 at one per refill, whether asked for inline or
+        // ahead of the consumer by a worker.
+        for &inline in &[true, false] {
+            assert_eq!(fired(TokenBucket::new(2, ms(10)), inline), [0, 0, 10, 20, 30]);
+            assert_eq!(fired(Gcra::new(ms(10), 2), inline), [0, 0, 10, 20, 30]);
+        }
+
+        // Boxed, or with costs, a FixedInterval is still kept on the
+        // Ticker's schedule, bursting to catch up as it does by default.
+        let boxed: Box<dyn Pacer + Send> = Box::new(FixedInterval::new(ms(10)));
+        assert_eq!(late(FixedInterval::new(ms(10)), false), [10, 20, 50, 60]);
+        assert_eq!(late(boxed, false), [10, 20, 50, 60]);
+        assert_eq!(late(FixedInterval::new(ms(10)), true), [10, 20, 50, 60]);
+
+        // One costing 2 takes two intervals, and one costing 0 none.
+        let boxed: Box<dyn Pacer + Send> = Box::new(FixedInterval::new(ms(5)));
+        let clock = JumpClock::default();
+        let start = clock.now();
+        let costs = Ticker::with_pacer_cost(vec![2u32, 0, 1].into_iter(), boxed, |c| *c)
+            .clock(clock)
+            .timestamped()
+            .map(|(fired, c)| (c, (fired - start).as_millis() as u64));
+        assert_eq!(costs.collect::<Vec<_>>(), [(2, 5), (0, 15), (1, 15)]);
+    }
+
+    #[test]
+    fn pacers_take_the_ticker_handle() {
+        let ms = Duration::from_millis;
+        let clock = JumpClock::default();
+        let start = clock.now();
+        let mut ticker = Ticker::with_pacer(0.., TokenBucket::new(1, ms(10)))
+            .clock(clock.clone())
+            .inline(true)
+            .timestamped();
+        let handle = ticker.handle();
+        let mut at = || ticker.next().map(|(fired, _)| (fired - start).as_millis() as u64);
+        assert_eq!((at(), at()), (Some(0), Some(10)));
+        // Tokens come at the new rate from the next item on.
+        handle.set_interval(ms(50));
+        assert_eq!((at(), at()), (Some(60), Some(110)));
+        assert_eq!(handle.stats().ticks, 4);
+        handle.stop();
+        assert_eq!(at(), None);
+    }
+}
@@ -0,0 +1,171 @@
+//! Rate limiting with an interval that changes over time.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::wait::{clock_setter, Due, Pull};
+use crate::Clock;
+
+/// Easing decides how a Ramp's delay moves from its start to its target.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Easing {
+    /// Linear changes the delay by the same amount each second. This is the
+    /// default.
+    #[default]
+    Linear,
+    /// Exponential changes the delay by the same factor each second, so the
+    /// rate moves as quickly at the slow end as at the fast one. A ramp with
+    /// a zero delay at either end has no factor to change by, and is linear.
+    Exponential,
+}
+
+/// Ramp rate limits an Iterator with a delay that moves from ````from```` to
+/// ````to```` over the first ````over```` after it is created, then stays at
+/// ````to````. Create one with ````Ticker::ramp````. Either end may be the
+/// larger, so a Ramp can speed up or slow down.
+///
+/// The delay before each item is the ramp's value when the item before it was
+/// returned, so the first item comes ````from```` after the Ramp is created.
+/// Time the consumer spends away is treated as the crate docs describe.
+pub struct Ramp<I> {
+    pull: Pull<I>,
+    from: Duration,
+    to: Duration,
+    over: Duration,
+    easing: Easing,
+    start: Instant,
+    due: Due,
+}
+
+impl<I: Iterator> Ramp<I> {
+    pub(crate) fn new(src: I, from: Duration, to: Duration, over: Duration) -> Self {
+        let pull = Pull::new(src);
+        let mut ramp = Ramp {
+            start: pull.clock().now(),
+            pull,
+            from,
+            to,
+            over,
+            easing: Easing::default(),
+            due: Due::Now,
+        };
+        ramp.restart();
+        ramp
+    }
+
+    clock_setter!(Ramp);
+
+    /// set_clock moves onto ````clock````, starting the ramp over.
+    fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.pull.set_clock(clock);
+        self.restart();
+    }
+
+    /// restart starts the ramp from now on its clock.
+    fn restart(&mut self) {
+        self.start = self.pull.clock().now();
+        self.due = Due::since(self.start, self.delay_at(Duration::ZERO));
+    }
+
+    /// easing sets how the delay moves between its ends. Defaults to
+    /// ````Easing::Linear````.
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// delay_at is the delay ````elapsed```` after the Ramp was created.
+    fn delay_at(&self, elapsed: Duration) -> Duration {
+        if elapsed >= self.over {
+            return self.to;
+        }
+        let t = elapsed.as_secs_f64() / self.over.as_secs_f64();
+        let (from, to) = (self.from.as_secs_f64(), self.to.as_secs_f64());
+        let secs = match self.easing {
+            Easing::Exponential if from > 0.0 && to > 0.0 => from * (to / from).powf(t),
+            _ => from + (to - from) * t,
+        };
+        // Clamped to the ends so that rounding can never leave the range, or
+        // overflow converting back near Duration::MAX.
+        let (lo, hi) = (self.from.min(self.to), self.from.max(self.to));
+        if secs <= lo.as_secs_f64() {
+            lo
+        } else if secs >= hi.as_secs_f64() {
+            hi
+        } else {
+            Duration::from_secs_f64(secs)
+        }
+    }
+}
+
+impl<I: Iterator> Iterator for Ramp<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (due, start) = (self.due, self.start);
+        let item = self.pull.next(|_, clock| due.wait(clock))?;
+        let now = self.pull.clock().now();
+        self.due = Due::after(self.pull.clock(), self.delay_at(now.saturating_duration_since(start)));
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pull.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::JumpClock;
+    use crate::Ticker;
+
+    #[test]
+    fn delay_follows_the_easing() {
+        let ms = Duration::from_millis;
+        let secs = Duration::from_secs;
+        let down = Ticker::ramp(0..1, secs(2), ms(100), secs(60));
+        assert_eq!(down.delay_at(Duration::ZERO), secs(2));
+        assert_eq!(down.delay_at(secs(30)), ms(1050));
+        assert_eq!(down.delay_at(secs(60)), ms(100));
+        assert_eq!(down.delay_at(secs(3600)), ms(100));
+
+        let up = Ticker::ramp(0..1, ms(100), secs(2), secs(60));
+        assert_eq!(up.delay_at(secs(15)), ms(575));
+        assert_eq!(up.delay_at(secs(90)), secs(2));
+
+        let exp = Ticker::ramp(0..1, ms(100), ms(1600), secs(4)).easing(Easing::Exponential);
+        for (at, want) in [(0, 100), (1, 200), (2, 400), (3, 800), (4, 1600)].iter() {
+            let got = exp.delay_at(secs(*at)).as_secs_f64() * 1000.0;
+            assert!((got - *want as f64).abs() < 1e-6, "{}s: {}", at, got);
+        }
+        let from_zero = Ticker::ramp(0..1, Duration::ZERO, secs(4), secs(4)).easing(Easing::Exponential);
+        assert_eq!(from_zero.delay_at(secs(1)), secs(1));
+
+        let jump = Ticker::ramp(0..1, secs(2), ms(100), Duration::ZERO);
+        assert_eq!(jump.delay_at(Duration::ZERO), ms(100));
+
+        let huge = Ticker::ramp(0..1, Duration::ZERO, Duration::MAX, secs(1));
+        assert!(huge.delay_at(ms(999)) <= Duration::MAX);
+    }
+
+    #[test]
+    fn ramps_down_to_the_target() {
+        let ms = Duration::from_millis;
+        let clock = JumpClock::default();
+        let start = clock.now();
+        let arrivals: Vec<_> = Ticker::ramp(0..8, ms(40), ms(5), ms(80))
+            .clock(clock.clone())
+            .map(|_| clock.now() - start)
+            .collect();
+        assert_eq!(arrivals.len(), 8);
+        assert_eq!(arrivals[0], ms(40));
+        // Each gap is the ramp's delay when the item before it came, down to
+        // the target once the ramp is over.
+        let ramp = Ticker::ramp(0..1, ms(40), ms(5), ms(80));
+        for pair in arrivals.windows(2) {
+            assert_eq!(pair[1] - pair[0], ramp.delay_at(pair[0]), "{:?}", arrivals);
+        }
+        assert_eq!(arrivals[7] - arrivals[6], ms(5), "{:?}", arrivals);
+    }
+}
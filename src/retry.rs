@@ -0,0 +1,195 @@
+//! Retrying a fallible operation on a backoff schedule.
+
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use crate::Ticker;
+
+/// RetryPolicy is how ````retry()```` paces its attempts: at most
+/// ````max_attempts````, the first at once, the second after ````initial````,
+/// and each wait after that ````factor```` times the last, up to ````max````,
+/// as for ````Ticker::backoff````.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: usize,
+    initial: Duration,
+    factor: f64,
+    max: Duration,
+}
+
+impl RetryPolicy {
+    /// new creates a RetryPolicy.
+    ///
+    /// Panics if ````max_attempts```` is 0, or if ````factor```` is not
+    /// finite or is less than 1.
+    pub fn new(max_attempts: usize, initial: Duration, factor: f64, max: Duration) -> Self {
+        assert!(max_attempts > 0, "max_attempts must be at least 1");
+        assert!(factor.is_finite() && factor >= 1.0,
+                "backoff factor must be finite and at least 1, got {}",
+                factor);
+        RetryPolicy {
+            max_attempts,
+            initial,
+            factor,
+            max,
+        }
+    }
+}
+
+/// retry calls ````op```` until it succeeds, pacing the attempts by
+/// ````policy````, and returns its first success. Every error is retried;
+/// see ````retry_if()```` to give up on some at once. Once the last attempt
+/// fails, retry returns without waiting again.
+///
+/// ````no_run
+/// # use std::net::TcpStream;
+/// # use std::time::Duration;
+/// use ticker::{retry, RetryPolicy};
+///
+/// let policy = RetryPolicy::new(5, Duration::from_millis(100), 2.0, Duration::from_secs(2));
+/// match retry(policy, || TcpStream::connect("db.internal:5432")) {
+///     Ok(conn) => println!("connected: {:?}", conn),
+///     Err(e) => println!("{}", e),
+/// }
+/// ````
+pub fn retry<T, E, F>(policy: RetryPolicy, op: F) -> Result<T, RetryError<E>>
+where
+    F: FnMut() -> Result<T, E>,
+{
+    retry_if(policy, op, |_| true)
+}
+
+/// retry_if is ````retry()````, retrying only the errors ````retryable````
+/// returns true for. Any other error is returned at once as
+/// ````RetryError::Terminal````.
+///
+/// ````no_run
+/// # use std::io::{self, ErrorKind, Read};
+/// # use std::fs::File;
+/// # use std::time::Duration;
+/// use ticker::{retry_if, RetryPolicy};
+///
+/// let policy = RetryPolicy::new(3, Duration::from_millis(50), 2.0, Duration::from_secs(1));
+/// let config = retry_if(policy,
+///                       || std::fs::read_to_string("/etc/app.toml"),
+///                       |e: &io::Error| e.kind() != ErrorKind::NotFound);
+/// ````
+pub fn retry_if<T, E, F, C>(policy: RetryPolicy, mut op: F, mut retryable: C) -> Result<T, RetryError<E>>
+where
+    F: FnMut() -> Result<T, E>,
+    C: FnMut(&E) -> bool,
+{
+    let mut last = None;
+    let attempts = Ticker::backoff(1..=policy.max_attempts, policy.initial, policy.factor, policy.max);
+    for attempt in attempts {
+        match op() {
+            Ok(t) => return Ok(t),
+            Err(error) if !retryable(&error) => return Err(RetryError::Terminal { error, attempts: attempt }),
+            Err(error) => last = Some(error),
+        }
+    }
+    Err(RetryError::Exhausted {
+        error: last.expect("at least one attempt"),
+        attempts: policy.max_attempts,
+    })
+}
+
+/// RetryError is why ````retry()```` gave up, with the last error and how
+/// many attempts were made.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RetryError<E> {
+    /// Exhausted means every attempt the policy allowed failed.
+    Exhausted { error: E, attempts: usize },
+    /// Terminal means an attempt failed with an error that is not retried.
+    Terminal { error: E, attempts: usize },
+}
+
+impl<E> RetryError<E> {
+    /// attempts is how many times the operation was tried.
+    pub fn attempts(&self) -> usize {
+        match *self {
+            RetryError::Exhausted { attempts, .. } | RetryError::Terminal { attempts, .. } => attempts,
+        }
+    }
+
+    /// into_inner returns the last error.
+    pub fn into_inner(self) -> E {
+        match self {
+            RetryError::Exhausted { error, .. } | RetryError::Terminal { error, .. } => error,
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for RetryError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RetryError::Exhausted { ref error, attempts } => {
+                write!(f, "gave up after {} attempts: {}", attempts, error)
+            }
+            RetryError::Terminal { ref error, attempts } => {
+                write!(f, "failed on attempt {}: {}", attempts, error)
+            }
+        }
+    }
+}
+
+impl<E: Error + 'static> Error for RetryError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            RetryError::Exhausted { ref error, .. } | RetryError::Terminal { ref error, .. } => Some(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn policy(max_attempts: usize) -> RetryPolicy {
+        RetryPolicy::new(max_attempts, Duration::from_millis(20), 2.0, Duration::from_secs(1))
+    }
+
+    #[test]
+    fn succeeds_on_third_call() {
+        let mut calls = 0;
+        let start = Instant::now();
+        let result: Result<i32, RetryError<&str>> = retry(policy(5), || {
+            calls += 1;
+            if calls == 3 { Ok(calls) } else { Err("not yet") }
+        });
+        assert_eq!(result, Ok(3));
+        assert!(start.elapsed() + Duration::from_millis(1) >= Duration::from_millis(60), "{:?}", start.elapsed());
+    }
+
+    #[test]
+    fn terminal_error_stops_at_once() {
+        let mut calls = 0;
+        let start = Instant::now();
+        let result: Result<(), _> = retry_if(policy(5),
+                                             || {
+                                                 calls += 1;
+                                                 Err(if calls < 2 { "busy" } else { "denied" })
+                                             },
+                                             |e| *e == "busy");
+        assert_eq!(result, Err(RetryError::Terminal { error: "denied", attempts: 2 }));
+        assert_eq!(calls, 2);
+        assert!(start.elapsed() < Duration::from_millis(40), "{:?}", start.elapsed());
+    }
+
+    #[test]
+    fn exhaustion_does_not_wait_after_the_last_attempt() {
+        let start = Instant::now();
+        let result: Result<(), _> = retry(policy(3), || Err("down"));
+        let elapsed = start.elapsed();
+        let error = result.unwrap_err();
+        assert_eq!(error.attempts(), 3);
+        assert_eq!(error.to_string(), "gave up after 3 attempts: down");
+        assert_eq!(error.into_inner(), "down");
+        // Waits of 20ms and 40ms, and none of 80ms after the last attempt.
+        assert!(elapsed + Duration::from_millis(1) >= Duration::from_millis(60), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(120), "{:?}", elapsed);
+        assert!(std::panic::catch_unwind(|| policy(0)).is_err());
+    }
+}
@@ -0,0 +1,249 @@
+//! Rate limiting on a schedule of varying gaps.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::wait::{clock_setter, Due, Pull};
+use crate::Clock;
+
+/// Scheduled rate limits an Iterator with a gap per tick, taken in turn from a
+/// schedule of Durations. Create one with ````Ticker::with_schedule````.
+///
+/// Ticking starts when the Scheduled is created, and the nth item is returned
+/// once the first n gaps of the schedule have passed, on absolute deadlines so
+/// the schedule does not drift. Missed ticks are not saved up; the schedule
+/// carries on from a late tick, as the crate docs describe.
+///
+/// Iteration ends when either the source or the schedule runs out. To keep
+/// repeating the final gap instead, chain it on:
+/// ````schedule.chain(iter::repeat(last))````.
+pub struct Scheduled<I, S> {
+    pull: Pull<I>,
+    schedule: S,
+    /// ended is whether the schedule has run out.
+    ended: bool,
+    /// deadline is when the last item was due, or was returned if late.
+    deadline: Instant,
+}
+
+impl<I, S> Scheduled<I, S>
+where
+    I: Iterator,
+    S: Iterator<Item = Duration>,
+{
+    pub(crate) fn new(src: I, schedule: S) -> Self {
+        let pull = Pull::new(src);
+        let deadline = pull.clock().now();
+        Scheduled {
+            pull,
+            schedule,
+            ended: false,
+            deadline,
+        }
+    }
+
+    clock_setter!(Scheduled);
+
+    /// set_clock moves onto ````clock````, starting the schedule from now.
+    fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.pull.set_clock(clock);
+        self.deadline = self.pull.clock().now();
+    }
+}
+
+impl<I, S> Iterator for Scheduled<I, S>
+where
+    I: Iterator,
+    S: Iterator<Item = Duration>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.ended || self.pull.is_done() {
+            return None;
+        }
+        // The gap is taken first so that an item is never pulled from the
+        // source only to be dropped when the schedule has run out.
+        let gap = match self.schedule.next() {
+            Some(gap) => gap,
+            None => {
+                self.ended = true;
+                return None;
+            }
+        };
+        let deadline = &mut self.deadline;
+        self.pull.next(|_, clock| {
+            let due = Due::since(*deadline, gap);
+            match due {
+                Due::At(at) if at > clock.now() => {
+                    due.wait(clock);
+                    *deadline = at;
+                }
+                Due::At(_) => *deadline = clock.now(),
+                _ => due.wait(clock),
+            }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.ended {
+            return (0, Some(0));
+        }
+        let (src_lo, src_hi) = self.pull.size_hint();
+        let (sched_lo, sched_hi) = self.schedule.size_hint();
+        let hi = match (src_hi, sched_hi) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        (src_lo.min(sched_lo), hi)
+    }
+}
+
+/// Backoff is the schedule of an exponential backoff: no wait before the first
+/// attempt, ````initial```` before the second, and from then on each gap is
+/// ````factor```` times the last, up to ````max````. It never ends; bound the
+/// number of attempts with the source or with ````.take()````. Use it with
+/// ````Ticker::with_schedule````, or use ````Ticker::backoff````.
+#[derive(Clone, Debug)]
+pub struct Backoff {
+    initial: Duration,
+    factor: f64,
+    max: Duration,
+    last: Option<Duration>,
+}
+
+impl Backoff {
+    /// new creates a Backoff. Gaps saturate at ````max```` rather than
+    /// overflowing, however large the factor.
+    ///
+    /// Panics if ````factor```` is not finite or is less than 1.
+    pub fn new(initial: Duration, factor: f64, max: Duration) -> Self {
+        assert!(factor.is_finite() && factor >= 1.0,
+                "backoff factor must be finite and at least 1, got {}",
+                factor);
+        Backoff {
+            initial: initial.min(max),
+            factor,
+            max,
+            last: None,
+        }
+    }
+
+    /// grow is the gap after ````gap````.
+    fn grow(&self, gap: Duration) -> Duration {
+        let nanos = gap.as_nanos() as f64 * self.factor;
+        if nanos >= self.max.as_nanos() as f64 {
+            return self.max;
+        }
+        let nanos = nanos.round() as u128;
+        Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32)
+    }
+}
+
+impl Iterator for Backoff {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let (gap, last) = match self.last {
+            None => (Duration::ZERO, self.initial),
+            Some(last) => (last, self.grow(last)),
+        };
+        self.last = Some(last);
+        Some(gap)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::JumpClock;
+    use crate::Ticker;
+    use std::iter;
+
+    /// assert_on_schedule checks each item of ````iter```` arrives just as
+    /// the gaps before it add up, on a JumpClock.
+    fn assert_on_schedule<I, S>(iter: Scheduled<I, S>, gaps: &[Duration])
+    where
+        I: Iterator,
+        S: Iterator<Item = Duration>,
+    {
+        let clock = JumpClock::default();
+        let start = clock.now();
+        let arrivals: Vec<Duration> = iter.clock(clock.clone()).map(|_| clock.now() - start).collect();
+        let due: Vec<Duration> = gaps
+            .iter()
+            .scan(Duration::ZERO, |due, gap| {
+                *due += *gap;
+                Some(*due)
+            })
+            .collect();
+        assert_eq!(arrivals, due);
+    }
+
+    #[test]
+    fn finite_schedule_ends_iteration() {
+        let ms = Duration::from_millis;
+        let schedule = vec![ms(30), ms(10), ms(20)];
+        assert_on_schedule(Ticker::with_schedule(0.., schedule.clone()), &schedule);
+    }
+
+    #[test]
+    fn cycling_schedule() {
+        let ms = Duration::from_millis;
+        let schedule = [ms(10), ms(25)];
+        let iter = Ticker::with_schedule(0..6, schedule.iter().copied().cycle());
+        assert_on_schedule(iter, &[ms(10), ms(25), ms(10), ms(25), ms(10), ms(25)]);
+    }
+
+    #[test]
+    fn repeat_last_gap() {
+        let ms = Duration::from_millis;
+        let schedule = iter::once(ms(30)).chain(iter::repeat(ms(10)));
+        let iter = Ticker::with_schedule(0..4, schedule);
+        assert_eq!(iter.size_hint(), (4, Some(4)));
+        assert_on_schedule(iter, &[ms(30), ms(10), ms(10), ms(10)]);
+    }
+
+    #[test]
+    fn late_consumer_does_not_burst() {
+        let interval = Duration::from_millis(10);
+        let clock = JumpClock::default();
+        let mut iter = Ticker::with_schedule(0.., iter::repeat(interval)).clock(clock.clone());
+        iter.next();
+        clock.0.advance(interval * 5);
+        let start = clock.now();
+        iter.next();
+        assert_eq!(clock.now(), start);
+        iter.next();
+        assert_eq!(clock.now() - start, interval);
+    }
+
+    #[test]
+    fn backoff_gaps() {
+        let ms = Duration::from_millis;
+        let gaps: Vec<_> = Backoff::new(ms(10), 2.0, ms(50)).take(6).collect();
+        assert_eq!(gaps, [ms(0), ms(10), ms(20), ms(40), ms(50), ms(50)]);
+
+        let gaps: Vec<_> = Backoff::new(ms(10), 1.5, ms(100)).take(4).collect();
+        assert_eq!(gaps, [ms(0), ms(10), ms(15), Duration::from_micros(22_500)]);
+    }
+
+    #[test]
+    fn backoff_saturates() {
+        let mut backoff = Backoff::new(Duration::from_secs(1), f64::MAX, Duration::MAX);
+        backoff.nth(2);
+        assert_eq!(backoff.next(), Some(Duration::MAX));
+        assert!(std::panic::catch_unwind(|| Backoff::new(Duration::ZERO, 0.5, Duration::MAX)).is_err());
+    }
+
+    #[test]
+    fn backoff_attempts() {
+        let ms = Duration::from_millis;
+        let attempts = Ticker::backoff(0..5, ms(5), 2.0, ms(20));
+        assert_on_schedule(attempts, &[ms(0), ms(5), ms(10), ms(20), ms(20)]);
+    }
+}
@@ -0,0 +1,236 @@
+//! Rate limiting on the producer side of a channel.
+
+use std::error::Error;
+use std::fmt;
+use std::iter::{self, Repeat};
+use std::sync::mpsc::{self, SendError, Sender, SyncSender};
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::time::Duration;
+
+use crate::{MissedTickBehavior, Ticker};
+
+/// ThrottledSender wraps the Sender of a channel so that messages go out at
+/// most once every ````interval````: ````send()```` blocks until the next tick
+/// before forwarding its message. The first message goes out immediately, and
+/// time spent not sending is not saved up, so consecutive messages are always
+/// at least ````interval```` apart.
+///
+/// Clones share one budget. However many threads send through clones of a
+/// ThrottledSender, together they send no faster than ````interval````, and
+/// they take turns in whatever order they are woken.
+///
+/// ````no_run
+/// # use ticker::ThrottledSender;
+/// # use std::sync::mpsc::channel;
+/// # use std::thread;
+/// # use std::time::Duration;
+/// let (send, recv) = channel();
+/// let send = ThrottledSender::new(send, Duration::from_millis(100));
+/// thread::spawn(move || {
+///     for i in 0.. {
+///         if send.send(i).is_err() {
+///             break;
+///         }
+///     }
+/// });
+/// for i in recv.iter().take(10) {
+///     println!("{}", i)
+/// }
+/// ````
+pub struct ThrottledSender<T> {
+    sender: Channel<T>,
+    ticker: Arc<Mutex<Ticker<Repeat<()>>>>,
+}
+
+/// Channel is the Sender a ThrottledSender forwards to.
+enum Channel<T> {
+    Unbounded(Sender<T>),
+    Bounded(SyncSender<T>),
+}
+
+impl<T> ThrottledSender<T> {
+    /// new throttles ````sender```` to one message every ````interval````.
+    pub fn new(sender: Sender<T>, interval: Duration) -> Self {
+        ThrottledSender::with_channel(Channel::Unbounded(sender), interval)
+    }
+
+    /// new_sync throttles the sender of a bounded channel to one message
+    /// every ````interval````. A message waits for its tick first, and then
+    /// for room in the channel.
+    pub fn new_sync(sender: SyncSender<T>, interval: Duration) -> Self {
+        ThrottledSender::with_channel(Channel::Bounded(sender), interval)
+    }
+
+    fn with_channel(sender: Channel<T>, interval: Duration) -> Self {
+        let ticker = Ticker::new(iter::repeat(()), interval)
+            .first_tick_immediately(true)
+            .missed_tick_behavior(MissedTickBehavior::Delay)
+            .inline(true);
+        ThrottledSender {
+            sender,
+            ticker: Arc::new(Mutex::new(ticker)),
+        }
+    }
+
+    /// send waits for the next tick, then sends ````t```` on the channel. Like
+    /// ````Sender::send````, it fails only if the receiver is gone, handing
+    /// ````t```` back.
+    pub fn send(&self, t: T) -> Result<(), SendError<T>> {
+        self.lock().next_tick();
+        match self.sender {
+            Channel::Unbounded(ref sender) => sender.send(t),
+            Channel::Bounded(ref sender) => sender.send(t),
+        }
+    }
+
+    /// try_send sends ````t```` if a tick is due now, without blocking. It
+    /// fails with ````TrySendError::RateExceeded```` if no tick is due yet, or
+    /// if another clone is already waiting for it. The tick is used up once
+    /// a send is attempted, even if a bounded channel turns out to be full.
+    pub fn try_send(&self, t: T) -> Result<(), TrySendError<T>> {
+        let due = match self.ticker.try_lock() {
+            Ok(mut ticker) => ticker.try_next().is_ok(),
+            Err(TryLockError::Poisoned(e)) => e.into_inner().try_next().is_ok(),
+            Err(TryLockError::WouldBlock) => false,
+        };
+        if !due {
+            return Err(TrySendError::RateExceeded(t));
+        }
+        match self.sender {
+            Channel::Unbounded(ref sender) => sender.send(t).map_err(|e| TrySendError::Disconnected(e.0)),
+            Channel::Bounded(ref sender) => {
+                sender.try_send(t).map_err(|e| match e {
+                    mpsc::TrySendError::Full(t) => TrySendError::Full(t),
+                    mpsc::TrySendError::Disconnected(t) => TrySendError::Disconnected(t),
+                })
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Ticker<Repeat<()>>> {
+        self.ticker.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<T> Clone for ThrottledSender<T> {
+    fn clone(&self) -> Self {
+        ThrottledSender {
+            sender: match self.sender {
+                Channel::Unbounded(ref sender) => Channel::Unbounded(sender.clone()),
+                Channel::Bounded(ref sender) => Channel::Bounded(sender.clone()),
+            },
+            ticker: self.ticker.clone(),
+        }
+    }
+}
+
+/// TrySendError is why ````ThrottledSender::try_send()```` did not send its
+/// message, which it hands back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrySendError<T> {
+    /// RateExceeded means no tick was due: sending now would go over the
+    /// rate.
+    RateExceeded(T),
+    /// Full means a bounded channel had no room.
+    Full(T),
+    /// Disconnected means the receiver is gone.
+    Disconnected(T),
+}
+
+impl<T> TrySendError<T> {
+    /// into_inner returns the message that was not sent.
+    pub fn into_inner(self) -> T {
+        match self {
+            TrySendError::RateExceeded(t) | TrySendError::Full(t) | TrySendError::Disconnected(t) => t,
+        }
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            TrySendError::RateExceeded(_) => "rate exceeded",
+            TrySendError::Full(_) => "sending on a full channel",
+            TrySendError::Disconnected(_) => "sending on a closed channel",
+        })
+    }
+}
+
+impl<T: fmt::Debug> Error for TrySendError<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::{channel, sync_channel, Receiver};
+    use std::thread;
+    use std::time::Instant;
+
+    /// arrivals receives everything sent on ````recv````, with how long after
+    /// ````start```` each message arrived.
+    fn arrivals<T>(recv: Receiver<T>, start: Instant) -> Vec<Duration> {
+        recv.iter().map(|_| start.elapsed()).collect()
+    }
+
+    /// assert_paced checks that the nth message arrived no sooner than n
+    /// intervals after the sender was created. Arrivals are only seen once
+    /// the receiver wakes, so two can land closer together than they were
+    /// sent.
+    fn assert_paced(arrivals: &[Duration], interval: Duration) {
+        for (n, arrival) in arrivals.iter().enumerate() {
+            assert!(*arrival + Duration::from_millis(1) >= interval * n as u32, "{:?}", arrivals);
+        }
+    }
+
+    #[test]
+    fn caps_a_blasting_producer() {
+        let interval = Duration::from_millis(10);
+        let (send, recv) = channel();
+        let start = Instant::now();
+        let send = ThrottledSender::new(send, interval);
+        thread::spawn(move || {
+            for i in 0..6 {
+                send.send(i).unwrap();
+            }
+        });
+        let arrivals = arrivals(recv, start);
+        assert_eq!(arrivals.len(), 6);
+        assert!(arrivals[0] < interval, "{:?}", arrivals);
+        assert_paced(&arrivals, interval);
+    }
+
+    #[test]
+    fn clones_share_a_budget() {
+        let interval = Duration::from_millis(10);
+        let (send, recv) = sync_channel(100);
+        let start = Instant::now();
+        let send = ThrottledSender::new_sync(send, interval);
+        for _ in 0..3 {
+            let send = send.clone();
+            thread::spawn(move || {
+                for i in 0..3 {
+                    send.send(i).unwrap();
+                }
+            });
+        }
+        drop(send);
+        let arrivals = arrivals(recv, start);
+        assert_eq!(arrivals.len(), 9);
+        assert_paced(&arrivals, interval);
+    }
+
+    #[test]
+    fn try_send_rate_exceeded() {
+        let (send, recv) = channel();
+        let send = ThrottledSender::new(send, Duration::from_secs(10));
+        assert_eq!(send.try_send(1), Ok(()));
+        assert_eq!(send.try_send(2), Err(TrySendError::RateExceeded(2)));
+        assert_eq!(send.clone().try_send(3).map_err(TrySendError::into_inner), Err(3));
+        assert_eq!(recv.try_iter().collect::<Vec<_>>(), [1]);
+
+        drop(recv);
+        let (send, recv) = sync_channel(0);
+        drop(recv);
+        let send = ThrottledSender::new_sync(send, Duration::from_secs(10));
+        assert_eq!(send.try_send(1), Err(TrySendError::Disconnected(1)));
+    }
+}
@@ -0,0 +1,253 @@
+//! One rate shared by many consumers.
+
+use std::collections::BTreeSet;
+use std::iter::{FusedIterator, Repeat};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::wait::Pull;
+use crate::{front, TimedOut, Ticker, TickerHandle, Timeout};
+
+/// SharedTicker is one Ticker's ticks handed out among any number of
+/// consumers, so together they go no faster than its interval. Create one
+/// with ````Ticker::shared````, then rate limit each consumer's source with
+/// ````.wrap()````; clones share the same ticks. Each tick goes to exactly one
+/// waiting consumer.
+///
+/// Consumers take ticks in the order they started waiting for them, so a
+/// busy consumer cannot starve the others: with ````n```` consumers always
+/// waiting, each gets every ````n````th tick.
+///
+/// ````no_run
+/// # use ticker::Ticker;
+/// # use std::thread;
+/// # use std::time::Duration;
+/// # fn call_api(_: i32) {}
+/// let budget = Ticker::shared(Duration::from_millis(100));
+/// let workers: Vec<_> = (0..4)
+///     .map(|w| {
+///         let jobs = budget.wrap((0..).map(move |i| w * 1000 + i));
+///         thread::spawn(move || jobs.for_each(call_api))
+///     })
+///     .collect();
+/// # drop(workers);
+/// ````
+#[derive(Clone)]
+pub struct SharedTicker {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    ticker: Mutex<Ticker<Repeat<()>>>,
+    /// handle is kept apart from the Ticker, which is locked for as long as
+    /// a consumer waits for its tick.
+    handle: TickerHandle,
+    turns: Mutex<Turns>,
+    turn: Condvar,
+}
+
+/// Turns is a ticket lock: each waiting consumer takes the next ticket, and
+/// waits until it is served.
+struct Turns {
+    next: u64,
+    serving: u64,
+    /// abandoned are the tickets of consumers that gave up waiting, which
+    /// are skipped when their turn comes.
+    abandoned: BTreeSet<u64>,
+}
+
+impl SharedTicker {
+    pub(crate) fn new(ticker: Ticker<Repeat<()>>) -> Self {
+        SharedTicker {
+            inner: Arc::new(Inner {
+                handle: ticker.handle(),
+                ticker: Mutex::new(ticker),
+                turns: Mutex::new(Turns {
+                    next: 0,
+                    serving: 0,
+                    abandoned: BTreeSet::new(),
+                }),
+                turn: Condvar::new(),
+            }),
+        }
+    }
+
+    /// wrap rate limits ````src```` with this SharedTicker's ticks, shared
+    /// with everything else it wraps.
+    pub fn wrap<I: Iterator>(&self, src: I) -> SharedIter<I> {
+        SharedIter {
+            pull: Pull::new(src),
+            shared: self.clone(),
+        }
+    }
+
+    /// handle returns a TickerHandle to adjust the shared Ticker, for every
+    /// consumer at once.
+    pub fn handle(&self) -> TickerHandle {
+        self.inner.handle.clone()
+    }
+
+    /// tick waits for this consumer's turn, then for the next tick, and
+    /// returns when it fired. It returns None once the Ticker is stopped.
+    pub fn tick(&self) -> Option<Instant> {
+        self.tick_until(None).unwrap_or(None)
+    }
+
+    /// tick_timeout is ````tick()````, giving up with ````Err(Timeout)```` if
+    /// the tick has not come within ````timeout````, whether this consumer
+    /// was still waiting for its turn or for the tick itself.
+    pub fn tick_timeout(&self, timeout: Duration) -> Result<Option<Instant>, Timeout> {
+        self.tick_until(Instant::now().checked_add(timeout)).map_err(|TimedOut| Timeout)
+    }
+
+    fn tick_until(&self, deadline: Option<Instant>) -> Result<Option<Instant>, TimedOut> {
+        let mut turns = lock(&self.inner.turns);
+        let ticket = turns.next;
+        turns.next += 1;
+        while turns.serving != ticket {
+            turns = match deadline.map(|deadline| deadline.checked_duration_since(Instant::now())) {
+                None => self.inner.turn.wait(turns).unwrap_or_else(|e| e.into_inner()),
+                Some(Some(left)) => self.inner.turn.wait_timeout(turns, left).unwrap_or_else(|e| e.into_inner()).0,
+                Some(None) => {
+                    turns.abandoned.insert(ticket);
+                    return Err(TimedOut);
+                }
+            };
+        }
+        drop(turns);
+        let _served = Served(&self.inner);
+        let mut ticker = lock(&self.inner.ticker);
+        // The Ticker may keep time on a clock of its own.
+        let deadline = deadline.and_then(|deadline| {
+            let left = deadline.saturating_duration_since(Instant::now());
+            ticker.shared.clock.now().checked_add(left)
+        });
+        ticker.tick_until(front, deadline).map(|tick| tick.map(|(_, fired)| fired))
+    }
+
+    /// try_tick takes a tick if one is due and no other consumer is already
+    /// waiting for it, without blocking. It returns whether it took one.
+    pub fn try_tick(&self) -> bool {
+        {
+            let mut turns = lock(&self.inner.turns);
+            if turns.serving != turns.next {
+                return false;
+            }
+            turns.next += 1;
+        }
+        let _served = Served(&self.inner);
+        matches!(lock(&self.inner.ticker).try_next(), Ok(Some(())))
+    }
+}
+
+/// Served passes the turn on when a consumer is done with it, even if it
+/// panicked while waiting.
+struct Served<'a>(&'a Inner);
+
+impl Drop for Served<'_> {
+    fn drop(&mut self) {
+        {
+            let turns = &mut *lock(&self.0.turns);
+            turns.serving += 1;
+            while turns.abandoned.remove(&turns.serving) {
+                turns.serving += 1;
+            }
+        }
+        self.0.turn.notify_all();
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// SharedIter is an Iterator rate limited by a SharedTicker, as returned by
+/// ````SharedTicker::wrap````. Once its source is exhausted, it returns None
+/// without taking a tick from anyone else.
+pub struct SharedIter<I> {
+    pull: Pull<I>,
+    shared: SharedTicker,
+}
+
+impl<I> SharedIter<I> {
+    /// handle is ````SharedTicker::handle````.
+    pub fn handle(&self) -> TickerHandle {
+        self.shared.handle()
+    }
+}
+
+impl<I: Iterator> Iterator for SharedIter<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let SharedIter { pull, shared } = self;
+        pull.try_next(|item, _| shared.tick().map(|_| item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.pull.size_hint().1)
+    }
+}
+
+impl<I: Iterator> FusedIterator for SharedIter<I> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn consumers_share_one_rate() {
+        let interval = Duration::from_millis(10);
+        let shared = Ticker::shared(interval);
+        let start = Instant::now();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let consumers: Vec<_> = (0..4)
+            .map(|c| {
+                let (jobs, log) = (shared.wrap(0..5), log.clone());
+                thread::spawn(move || {
+                    for job in jobs {
+                        log.lock().unwrap().push((Instant::now(), c, job));
+                    }
+                })
+            })
+            .collect();
+        for consumer in consumers {
+            consumer.join().unwrap();
+        }
+
+        let mut log = log.lock().unwrap().clone();
+        log.sort();
+        assert_eq!(log.len(), 20);
+        for (n, entry) in log.iter().enumerate() {
+            // The nth tick anyone takes is due n + 1 intervals in, and is
+            // logged after that.
+            let due = interval * (n as u32 + 1);
+            assert!(entry.0 - start + Duration::from_millis(1) >= due, "tick {}: {:?}", n, log);
+        }
+        for c in 0..4 {
+            let jobs: Vec<_> = log.iter().filter(|e| e.1 == c).map(|e| e.2).collect();
+            assert_eq!(jobs, [0, 1, 2, 3, 4]);
+        }
+        // Together the consumers went no faster than one rate.
+        let total = log[19].0 - start;
+        assert!(total + Duration::from_millis(1) >= interval * 20, "{:?}", total);
+    }
+
+    #[test]
+    fn stop_ends_every_consumer() {
+        let shared = Ticker::shared(Duration::from_secs(3600));
+        let (a, b) = (shared.wrap(0..), shared.clone().wrap(0..));
+        let waiting: Vec<_> = vec![a, b]
+            .into_iter()
+            .map(|mut jobs| thread::spawn(move || jobs.next()))
+            .collect();
+        thread::sleep(Duration::from_millis(10));
+        shared.handle().stop();
+        for consumer in waiting {
+            assert_eq!(consumer.join().unwrap(), None);
+        }
+        assert_eq!(shared.tick(), None);
+    }
+}
@@ -0,0 +1,136 @@
+//! Counting what a Ticker has done, to tell a late ticker from a slow
+//! consumer.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// TickerStats is a snapshot of what a Ticker has done so far. Get one with
+/// ````Ticker::stats()```` or ````TickerHandle::stats()````, from any thread.
+///
+/// A consumer that keeps up finds no tick waiting when it calls
+/// ````.next()````, so ````late```` stays at zero; if it climbs, the consumer
+/// is the bottleneck. Gaps between ticks well over the interval with
+/// ````late```` at zero mean the ticks themselves are coming late.
+///
+/// ````no_run
+/// # use ticker::Ticker;
+/// # use std::time::Duration;
+/// # fn work(_: i32) {}
+/// let mut ticker = Ticker::new((0..), Duration::from_millis(100));
+/// let handle = ticker.handle();
+/// for i in ticker.by_ref().take(100) {
+///     work(i)
+/// }
+/// let stats = handle.stats();
+/// println!("{} of {} items late; gaps average {:?}, up to {:?}",
+///          stats.late, stats.items, stats.mean_gap, stats.max_gap);
+/// ````
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TickerStats {
+    /// ticks is how many ticks have fired, including any that were dropped
+    /// or not yet taken.
+    pub ticks: u64,
+    /// items is how many items the Ticker has returned.
+    pub items: u64,
+    /// late is how many items had their tick already waiting when they were
+    /// asked for.
+    pub late: u64,
+    /// mean_gap is the average time between the ticks of consecutive items.
+    pub mean_gap: Duration,
+    /// max_gap is the longest time between the ticks of consecutive items.
+    pub max_gap: Duration,
+    /// last_tick is when the tick of the latest item fired.
+    pub last_tick: Option<Instant>,
+}
+
+/// Stats is the counters behind TickerStats. Ticks are counted by whatever
+/// fires them, and items by the Ticker that returns them.
+#[derive(Debug)]
+pub(crate) struct Stats {
+    /// base is what tick times are stored relative to.
+    base: Instant,
+    ticks: AtomicU64,
+    items: AtomicU64,
+    late: AtomicU64,
+    /// gaps is the sum of the gaps, in nanoseconds.
+    gaps: AtomicU64,
+    max_gap: AtomicU64,
+    /// last_tick is one more than the nanoseconds from base to the last
+    /// item's tick, or zero before the first.
+    last_tick: AtomicU64,
+    /// last_fired is the same for the last tick fired, taken or not.
+    last_fired: AtomicU64,
+}
+
+impl Stats {
+    pub(crate) fn new(base: Instant) -> Self {
+        Stats {
+            base,
+            ticks: AtomicU64::new(0),
+            items: AtomicU64::new(0),
+            late: AtomicU64::new(0),
+            gaps: AtomicU64::new(0),
+            max_gap: AtomicU64::new(0),
+            last_tick: AtomicU64::new(0),
+            last_fired: AtomicU64::new(0),
+        }
+    }
+
+    /// fired counts a tick that fired at ````at````.
+    pub(crate) fn fired(&self, at: Instant) {
+        self.ticks.fetch_add(1, Ordering::Relaxed);
+        self.last_fired.store(self.stamp(at), Ordering::Relaxed);
+    }
+
+    /// last_fired is when the latest tick fired.
+    pub(crate) fn last_fired(&self) -> Option<Instant> {
+        self.unstamp(self.last_fired.load(Ordering::Relaxed))
+    }
+
+    /// stamp is how ````at```` is stored: one more than the nanoseconds since
+    /// base, so that zero can mean never.
+    fn stamp(&self, at: Instant) -> u64 {
+        let since_base = at.saturating_duration_since(self.base).as_nanos();
+        since_base.min(u128::from(u64::MAX - 1)) as u64 + 1
+    }
+
+    fn unstamp(&self, stamp: u64) -> Option<Instant> {
+        match stamp {
+            0 => None,
+            at => Some(self.base + Duration::from_nanos(at - 1)),
+        }
+    }
+
+    /// returned counts an item whose tick fired at ````fired````, asked for
+    /// at ````asked````.
+    pub(crate) fn returned(&self, fired: Instant, asked: Instant) {
+        self.items.fetch_add(1, Ordering::Relaxed);
+        if fired < asked {
+            self.late.fetch_add(1, Ordering::Relaxed);
+        }
+        let at = self.stamp(fired);
+        let previous = self.last_tick.swap(at, Ordering::Relaxed);
+        if previous != 0 {
+            let gap = at.saturating_sub(previous);
+            self.gaps.fetch_add(gap, Ordering::Relaxed);
+            self.max_gap.fetch_max(gap, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> TickerStats {
+        let items = self.items.load(Ordering::Relaxed);
+        let last_tick = self.last_tick.load(Ordering::Relaxed);
+        let mean_gap = match items {
+            0 | 1 => Duration::ZERO,
+            n => Duration::from_nanos(self.gaps.load(Ordering::Relaxed) / (n - 1)),
+        };
+        TickerStats {
+            ticks: self.ticks.load(Ordering::Relaxed),
+            items,
+            late: self.late.load(Ordering::Relaxed),
+            mean_gap,
+            max_gap: Duration::from_nanos(self.max_gap.load(Ordering::Relaxed)),
+            last_tick: self.unstamp(last_tick),
+        }
+    }
+}
@@ -0,0 +1,303 @@
+//! Rate limited Streams, for async code that must not block an executor thread.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use futures_core::Stream;
+use futures_timer::Delay;
+
+use crate::{Config, MissedTickBehavior, Schedule, SystemClock};
+
+/// AsyncTimer is what a TickStream sleeps on between ticks, so that it can
+/// use whichever timer the application's runtime already drives. The default,
+/// ````futures_timer::Delay````, runs its own timer thread and works on any
+/// executor. With the ````async-std```` feature ````async_io::Timer```` is an
+/// AsyncTimer too, driven by the reactor async-std and smol share. Choose one
+/// with ````TickStream::timer````.
+pub trait AsyncTimer: Unpin {
+    /// after creates a timer that fires once ````duration```` has passed.
+    fn after(duration: Duration) -> Self;
+
+    /// poll_fired returns whether the timer has fired, and if not arranges
+    /// for ````cx```` to be woken when it does.
+    fn poll_fired(&mut self, cx: &mut Context) -> Poll<()>;
+}
+
+impl AsyncTimer for Delay {
+    fn after(duration: Duration) -> Self {
+        Delay::new(duration)
+    }
+
+    fn poll_fired(&mut self, cx: &mut Context) -> Poll<()> {
+        Pin::new(self).poll(cx)
+    }
+}
+
+#[cfg(feature = "async-std")]
+impl AsyncTimer for async_io::Timer {
+    fn after(duration: Duration) -> Self {
+        async_io::Timer::after(duration)
+    }
+
+    fn poll_fired(&mut self, cx: &mut Context) -> Poll<()> {
+        Pin::new(self).poll(cx).map(|_| ())
+    }
+}
+
+/// TickStream rate limits a Stream, yielding at most once per interval. It
+/// sleeps on a timer future rather than a thread, so it runs on any executor.
+///
+/// Print 0-9, one number per second:
+///
+/// ````no_run
+/// # use std::time::Duration;
+/// use futures::executor::block_on;
+/// use futures::stream::{self, StreamExt};
+/// use ticker::TickStream;
+///
+/// let ticks = TickStream::new(stream::iter(0..10), Duration::from_secs(1));
+/// block_on(ticks.for_each(|i| {
+///     println!("{:?}", i);
+///     futures::future::ready(())
+/// }));
+/// ````
+///
+/// Like a Ticker set to ````pull_first(true)````, an item is taken from the
+/// source before waiting for its tick, and the TickStream ends as soon as the
+/// source does. A timer is armed once
+/// per tick and the stream is only polled again when it fires. Dropping a
+/// TickStream cancels its pending timer. Sources that are not Unpin can be
+/// wrapped with ````Box::pin````.
+pub struct TickStream<S: Stream, T = Delay> {
+    src: S,
+    item: Option<S::Item>,
+    done: bool,
+    start: Instant,
+    schedule: Schedule,
+    delay: Option<T>,
+}
+
+impl<S: Stream> TickStream<S> {
+    /// new creates a TickStream which will rate limit ````src````, yielding at
+    /// most once every ````interval````. The first item is yielded one
+    /// ````interval```` after construction. An item due too far off to
+    /// represent as an Instant, as with an interval of ````Duration::MAX````,
+    /// is never yielded.
+    pub fn new(src: S, interval: Duration) -> Self {
+        let start = Instant::now();
+        TickStream {
+            src,
+            item: None,
+            done: false,
+            start,
+            schedule: Schedule::new(&Config::new(interval), Arc::new(SystemClock), start.checked_add(interval), None),
+            delay: None,
+        }
+    }
+}
+
+impl<S: Stream, T: AsyncTimer> TickStream<S, T> {
+    /// timer sets the AsyncTimer this TickStream sleeps on. Defaults to
+    /// ````futures_timer::Delay````.
+    ///
+    /// ````no_run
+    /// # use std::time::Duration;
+    /// # #[cfg(feature = "async-std")]
+    /// # fn run() {
+    /// use futures::stream::{self, StreamExt};
+    /// use ticker::TickStream;
+    ///
+    /// async_std::task::block_on(async {
+    ///     let mut ticks = TickStream::new(stream::iter(0..10), Duration::from_secs(1))
+    ///         .timer::<async_io::Timer>();
+    ///     while let Some(i) = ticks.next().await {
+    ///         println!("{:?}", i)
+    ///     }
+    /// });
+    /// # }
+    /// ````
+    pub fn timer<U: AsyncTimer>(self) -> TickStream<S, U> {
+        TickStream {
+            src: self.src,
+            item: self.item,
+            done: self.done,
+            start: self.start,
+            schedule: self.schedule,
+            delay: None,
+        }
+    }
+
+    /// first_tick_immediately sets whether the first item is yielded as soon as
+    /// the source has it instead of one ````interval```` after construction.
+    pub fn first_tick_immediately(mut self, immediate: bool) -> Self {
+        self.schedule.next = if immediate {
+            Some(self.start)
+        } else {
+            self.start.checked_add(self.schedule.interval)
+        };
+        self
+    }
+
+    /// initial_delay sets how long after construction the first item is
+    /// yielded, separately from the interval between later items.
+    pub fn initial_delay(mut self, delay: Duration) -> Self {
+        self.schedule.next = self.start.checked_add(delay);
+        self
+    }
+
+    /// missed_tick_behavior sets what happens to ticks that come due while
+    /// nobody polls the TickStream. Defaults to ````MissedTickBehavior::Burst````.
+    pub fn missed_tick_behavior(mut self, behavior: MissedTickBehavior) -> Self {
+        self.schedule.behavior = behavior;
+        self
+    }
+}
+
+// The buffered item is never pinned, so only the source decides whether a
+// TickStream can move.
+impl<S: Stream + Unpin, T: AsyncTimer> Unpin for TickStream<S, T> {}
+
+impl<S: Stream + Unpin, T: AsyncTimer> Stream for TickStream<S, T> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+
+        if this.item.is_none() {
+            match Pin::new(&mut this.src).poll_next(cx) {
+                Poll::Ready(Some(item)) => this.item = Some(item),
+                Poll::Ready(None) => {
+                    this.done = true;
+                    return Poll::Ready(None);
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        if this.delay.is_none() {
+            let now = Instant::now();
+            match this.schedule.tick_at(now) {
+                Some(due) if due > now => this.delay = Some(T::after(due - now)),
+                Some(_) => {}
+                // The tick is too far off for an Instant to hold, so the item
+                // is never yielded.
+                None => return Poll::Pending,
+            }
+        }
+        if let Some(ref mut delay) = this.delay {
+            if delay.poll_fired(cx).is_pending() {
+                return Poll::Pending;
+            }
+        }
+        this.delay = None;
+        Poll::Ready(this.item.take())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            return (0, Some(0));
+        }
+        let buffered = self.item.is_some() as usize;
+        let (lower, upper) = self.src.size_hint();
+        (lower.saturating_add(buffered), upper.and_then(|u| u.checked_add(buffered)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use futures::stream::{self, StreamExt};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn paces_items() {
+        let interval = Duration::from_millis(20);
+        let start = Instant::now();
+        let ticks = TickStream::new(stream::iter(0..5), interval);
+        let items: Vec<i32> = block_on(ticks.collect());
+        assert_eq!(items, vec![0, 1, 2, 3, 4]);
+        assert!(start.elapsed() >= interval * 5);
+        assert!(start.elapsed() < interval * 6);
+    }
+
+    #[test]
+    fn spaces_each_item() {
+        let interval = Duration::from_millis(30);
+        let mut ticks = TickStream::new(stream::iter(0..3), interval).first_tick_immediately(true);
+
+        let start = Instant::now();
+        assert_eq!(block_on(ticks.next()), Some(0));
+        assert!(start.elapsed() < interval / 2);
+        assert_eq!(block_on(ticks.next()), Some(1));
+        assert!(start.elapsed() >= interval);
+        assert_eq!(block_on(ticks.next()), Some(2));
+        assert!(start.elapsed() >= interval * 2);
+        assert_eq!(block_on(ticks.next()), None);
+        assert!(start.elapsed() < interval * 3);
+    }
+
+    #[test]
+    fn max_interval_never_yields() {
+        use futures::FutureExt;
+
+        let mut ticks = TickStream::new(stream::iter(0..3), Duration::MAX);
+        assert_eq!(ticks.next().now_or_never(), None);
+        let mut ticks = TickStream::new(stream::iter(0..3), Duration::MAX).first_tick_immediately(true);
+        assert_eq!(ticks.next().now_or_never(), Some(Some(0)));
+        assert_eq!(ticks.next().now_or_never(), None);
+        let mut ticks = TickStream::new(stream::iter(0..3), Duration::from_millis(10)).initial_delay(Duration::MAX);
+        assert_eq!(ticks.next().now_or_never(), None);
+    }
+
+    /// Counted is a Delay that counts how many times it is armed and polled.
+    struct Counted(Delay);
+
+    static ARMED: AtomicUsize = AtomicUsize::new(0);
+    static POLLED: AtomicUsize = AtomicUsize::new(0);
+
+    impl AsyncTimer for Counted {
+        fn after(duration: Duration) -> Self {
+            ARMED.fetch_add(1, Ordering::SeqCst);
+            Counted(Delay::new(duration))
+        }
+
+        fn poll_fired(&mut self, cx: &mut Context) -> Poll<()> {
+            POLLED.fetch_add(1, Ordering::SeqCst);
+            self.0.poll_fired(cx)
+        }
+    }
+
+    #[test]
+    fn arms_one_timer_per_tick() {
+        let interval = Duration::from_millis(10);
+        let ticks = TickStream::new(stream::iter(0..3), interval).timer::<Counted>();
+        let items: Vec<i32> = block_on(ticks.collect());
+        assert_eq!(items, vec![0, 1, 2]);
+        assert_eq!(ARMED.load(Ordering::SeqCst), 3);
+        // Polled once to arm it and once when woken, give or take a spurious
+        // wake.
+        assert!(POLLED.load(Ordering::SeqCst) <= 9);
+    }
+
+    #[cfg(feature = "async-std")]
+    #[async_std::test]
+    async fn paces_on_async_io() {
+        let interval = Duration::from_millis(20);
+        let start = Instant::now();
+        let mut ticks = TickStream::new(stream::iter(0..3), interval).timer::<async_io::Timer>();
+        let mut n = 0;
+        while let Some(i) = ticks.next().await {
+            assert_eq!(i, n);
+            n += 1;
+            assert!(start.elapsed() >= interval * n as u32, "{:?}", start.elapsed());
+        }
+        assert_eq!(n, 3);
+    }
+}
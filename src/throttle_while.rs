@@ -0,0 +1,147 @@
+//! Rate limiting that can be switched on and off from outside.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::wait::{clock_setter, Due, Pull};
+use crate::Clock;
+
+/// FLAG_POLL is how often a waiting ThrottleWhile checks whether throttling
+/// has been switched off.
+const FLAG_POLL: Duration = Duration::from_millis(10);
+
+/// ThrottleWhile rate limits an Iterator only while a flag is set, so that it
+/// runs flat out until something, such as a monitor that notices the system
+/// is under pressure, asks it to slow down. Create one with
+/// ````Ticker::throttle_while````.
+///
+/// While the flag is clear, items pass straight through. Once it is set, the
+/// first item comes a full interval after the ThrottleWhile first sees it, and
+/// the rest once per interval after that. Missed ticks are not saved up, as
+/// for every adapter paced on the consumer's thread. Clearing the flag
+/// releases a ````.next()```` that is waiting for a tick within a few
+/// milliseconds, and setting it again starts a fresh schedule.
+pub struct ThrottleWhile<I> {
+    pull: Pull<I>,
+    interval: Duration,
+    flag: Arc<AtomicBool>,
+    /// next is when the next tick is due while throttling, and None while
+    /// not.
+    next: Option<Due>,
+}
+
+impl<I: Iterator> ThrottleWhile<I> {
+    pub(crate) fn new(src: I, interval: Duration, flag: Arc<AtomicBool>) -> Self {
+        ThrottleWhile {
+            pull: Pull::new(src),
+            interval,
+            flag,
+            next: None,
+        }
+    }
+
+    clock_setter!(ThrottleWhile);
+
+    /// set_clock moves onto ````clock````, dropping any schedule already started.
+    fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.pull.set_clock(clock);
+        self.next = None;
+    }
+
+    /// flag returns the flag this ThrottleWhile throttles while set.
+    pub fn flag(&self) -> Arc<AtomicBool> {
+        self.flag.clone()
+    }
+}
+
+impl<I: Iterator> Iterator for ThrottleWhile<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ThrottleWhile { pull, interval, flag, next } = self;
+        pull.next(|_, clock| {
+            let mut late = true;
+            loop {
+                if !flag.load(Ordering::SeqCst) {
+                    *next = None;
+                    return;
+                }
+                let now = clock.now();
+                let timeout = match *next.get_or_insert_with(|| Due::since(now, *interval)) {
+                    Due::At(due) if due > now => clock.timeout(due),
+                    Due::Never => FLAG_POLL,
+                    Due::Now | Due::At(_) => {
+                        let from = match *next {
+                            Some(Due::At(due)) if !late => due,
+                            _ => now,
+                        };
+                        *next = Some(Due::since(from, *interval));
+                        return;
+                    }
+                };
+                late = false;
+                thread::sleep(timeout.min(FLAG_POLL));
+            }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pull.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::JumpClock;
+    use crate::{ManualClock, Ticker};
+    use std::time::Instant;
+
+    #[test]
+    fn paces_only_while_flagged() {
+        let interval = Duration::from_millis(20);
+        let flag = Arc::new(AtomicBool::new(false));
+        let clock = JumpClock::default();
+        let mut iter = Ticker::throttle_while(0.., interval, flag.clone()).clock(clock.clone());
+
+        let start = clock.now();
+        assert_eq!(iter.by_ref().take(100).count(), 100);
+        assert_eq!(clock.now(), start);
+
+        flag.store(true, Ordering::SeqCst);
+        assert_eq!(iter.by_ref().take(4).count(), 4);
+        assert_eq!(clock.now() - start, interval * 4);
+
+        flag.store(false, Ordering::SeqCst);
+        let start = clock.now();
+        assert_eq!(iter.by_ref().take(100).count(), 100);
+        assert_eq!(clock.now(), start);
+
+        // Throttling again starts from when it is seen, not from the old
+        // schedule.
+        clock.0.advance(interval * 2);
+        flag.store(true, Ordering::SeqCst);
+        let start = clock.now();
+        iter.next();
+        assert_eq!(clock.now() - start, interval);
+    }
+
+    #[test]
+    fn clearing_the_flag_releases_a_wait() {
+        let flag = Arc::new(AtomicBool::new(true));
+        let mut iter = Ticker::throttle_while(0.., Duration::from_secs(60), flag.clone()).clock(ManualClock::new());
+        let clear = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(30));
+            flag.store(false, Ordering::SeqCst);
+        });
+        let start = Instant::now();
+        assert_eq!(iter.next(), Some(0));
+        assert!(start.elapsed() < Duration::from_secs(1), "{:?}", start.elapsed());
+        clear.join().unwrap();
+
+        let never = Ticker::throttle_while(0.., Duration::MAX, iter.flag());
+        assert_eq!(never.take(3).count(), 3);
+    }
+}
@@ -0,0 +1,248 @@
+//! Rate limiting on ticks from outside, rather than a timer.
+
+use std::iter::FusedIterator;
+use std::sync::mpsc::Receiver;
+
+use crate::wait::Pull;
+use crate::{Every, MissedTickBehavior};
+
+/// TickSource is anything that says when an item may go: a channel fed by a
+/// file watcher, a semaphore, another service, or a timer like Every. Gate an
+/// Iterator on one with ````Ticker::from_ticks````.
+///
+/// It is implemented for mpsc and crossbeam Receivers of any message, where
+/// each message received is a tick and disconnecting ends the ticks, and for
+/// Every.
+pub trait TickSource {
+    /// wait blocks until the next tick, and returns false once there will be
+    /// no more.
+    fn wait(&mut self) -> bool;
+
+    /// skip_pending takes every tick that has already arrived without
+    /// waiting, and returns how many there were. By default there are none,
+    /// for a source that cannot fall behind.
+    fn skip_pending(&mut self) -> usize {
+        0
+    }
+}
+
+impl<S: TickSource + ?Sized> TickSource for &mut S {
+    fn wait(&mut self) -> bool {
+        (**self).wait()
+    }
+
+    fn skip_pending(&mut self) -> usize {
+        (**self).skip_pending()
+    }
+}
+
+impl<S: TickSource + ?Sized> TickSource for Box<S> {
+    fn wait(&mut self) -> bool {
+        (**self).wait()
+    }
+
+    fn skip_pending(&mut self) -> usize {
+        (**self).skip_pending()
+    }
+}
+
+impl<T> TickSource for Receiver<T> {
+    fn wait(&mut self) -> bool {
+        self.recv().is_ok()
+    }
+
+    fn skip_pending(&mut self) -> usize {
+        let mut n = 0;
+        while self.try_recv().is_ok() {
+            n += 1;
+        }
+        n
+    }
+}
+
+#[cfg(feature = "crossbeam")]
+impl<T> TickSource for crossbeam_channel::Receiver<T> {
+    fn wait(&mut self) -> bool {
+        self.recv().is_ok()
+    }
+
+    fn skip_pending(&mut self) -> usize {
+        self.try_iter().count()
+    }
+}
+
+/// Every is the timer as a TickSource, so ````Ticker::from_ticks(src,
+/// Ticker::every(interval))```` paces much as ````Ticker::new(src,
+/// interval)```` does. Its own missed tick behavior decides what is pending.
+///
+/// This is only an adapter: a Ticker keeps its own schedule rather than
+/// waiting on a TickSource, so that its handle, stats and hooks can reach
+/// into it.
+impl TickSource for Every {
+    fn wait(&mut self) -> bool {
+        self.next().is_some()
+    }
+}
+
+/// FromTicks rate limits an Iterator to one item per tick from a
+/// TickSource. Create one with ````Ticker::from_ticks````.
+///
+/// Ticks that arrive while no one is waiting are kept, and release as many
+/// items back to back later, as with ````MissedTickBehavior::Burst````. Under
+/// ````MissedTickBehavior::Skip```` they collapse into one instead; Delay is
+/// the same as Burst, since ticks from outside have no schedule to delay.
+///
+/// Iteration ends when either the source or the TickSource does. An item
+/// pulled for a tick that never came is kept, and handed back by
+/// ````into_inner()````.
+pub struct FromTicks<I: Iterator, T> {
+    pull: Pull<I>,
+    ticks: T,
+    behavior: MissedTickBehavior,
+    /// held is the item pulled for a tick that never came.
+    held: Option<I::Item>,
+}
+
+impl<I: Iterator, T: TickSource> FromTicks<I, T> {
+    pub(crate) fn new(src: I, ticks: T) -> Self {
+        FromTicks {
+            pull: Pull::new(src),
+            ticks,
+            behavior: MissedTickBehavior::Burst,
+            held: None,
+        }
+    }
+
+    /// missed_tick_behavior sets what becomes of ticks that arrive while no
+    /// one is waiting. The default is Burst.
+    pub fn missed_tick_behavior(mut self, behavior: MissedTickBehavior) -> Self {
+        self.behavior = behavior;
+        self
+    }
+
+    /// into_inner returns the source, the item pulled from it for a tick
+    /// that never came if the TickSource ended, and the TickSource.
+    pub fn into_inner(self) -> (I, Option<I::Item>, T) {
+        (self.pull.into_inner(), self.held, self.ticks)
+    }
+}
+
+impl<I: Iterator, T: TickSource> Iterator for FromTicks<I, T> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let FromTicks { pull, ticks, behavior, held } = self;
+        pull.try_next(|item, _| {
+            let ticked = match behavior {
+                MissedTickBehavior::Skip => ticks.skip_pending() > 0 || ticks.wait(),
+                _ => ticks.wait(),
+            };
+            if ticked {
+                Some(item)
+            } else {
+                *held = Some(item);
+                None
+            }
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.pull.size_hint().1)
+    }
+}
+
+impl<I: Iterator, T: TickSource> FusedIterator for FromTicks<I, T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Ticker;
+    use std::sync::mpsc::{channel, RecvTimeoutError};
+    use std::thread;
+    use std::time::Duration;
+
+    /// consume forwards every item of ````iter```` from a thread, then
+    /// closes the returned Receiver.
+    fn consume<T: Iterator<Item = u32> + Send + 'static>(iter: T) -> Receiver<u32> {
+        let (send, recv) = channel();
+        thread::spawn(move || {
+            for i in iter {
+                let _ = send.send(i);
+            }
+        });
+        recv
+    }
+
+    /// quiet is whether nothing more arrives on ````items```` for a while.
+    fn quiet(items: &Receiver<u32>) -> bool {
+        items.recv_timeout(Duration::from_millis(20)) == Err(RecvTimeoutError::Timeout)
+    }
+
+    #[test]
+    fn one_item_per_tick() {
+        let (tick, ticks) = channel::<()>();
+        let items = consume(Ticker::from_ticks(0.., ticks));
+        assert!(quiet(&items));
+        for i in 0..3 {
+            tick.send(()).unwrap();
+            assert_eq!(items.recv_timeout(Duration::from_secs(5)), Ok(i));
+            assert!(quiet(&items));
+        }
+
+        // Ticks sent ahead are kept.
+        for _ in 0..3 {
+            tick.send(()).unwrap();
+        }
+        for i in 3..6 {
+            assert_eq!(items.recv_timeout(Duration::from_secs(5)), Ok(i));
+        }
+        assert!(quiet(&items));
+
+        drop(tick);
+        assert_eq!(items.recv_timeout(Duration::from_secs(5)), Err(RecvTimeoutError::Disconnected));
+    }
+
+    #[test]
+    fn skip_collapses_pending_ticks() {
+        let (tick, ticks) = channel::<()>();
+        for _ in 0..3 {
+            tick.send(()).unwrap();
+        }
+        let items = consume(Ticker::from_ticks(0.., ticks).missed_tick_behavior(MissedTickBehavior::Skip));
+        assert_eq!(items.recv_timeout(Duration::from_secs(5)), Ok(0));
+        assert!(quiet(&items));
+        tick.send(()).unwrap();
+        assert_eq!(items.recv_timeout(Duration::from_secs(5)), Ok(1));
+        drop(tick);
+        assert_eq!(items.recv_timeout(Duration::from_secs(5)), Err(RecvTimeoutError::Disconnected));
+    }
+
+    #[test]
+    fn ends_with_either_side() {
+        let (tick, ticks) = channel::<()>();
+        tick.send(()).unwrap();
+        tick.send(()).unwrap();
+        let mut gated = Ticker::from_ticks(0..1, ticks);
+        assert_eq!(gated.next(), Some(0));
+        assert_eq!(gated.next(), None);
+        assert_eq!(gated.size_hint(), (0, Some(0)));
+        let (_, held, mut ticks) = gated.into_inner();
+        assert_eq!(held, None);
+        assert_eq!(ticks.skip_pending(), 1);
+
+        // The item pulled for a tick that never came is kept.
+        let (tick, ticks) = channel::<()>();
+        tick.send(()).unwrap();
+        drop(tick);
+        let mut gated = Ticker::from_ticks(0.., ticks);
+        assert_eq!(gated.next(), Some(0));
+        assert_eq!(gated.next(), None);
+        assert_eq!(gated.next(), None);
+        let (mut src, held, _) = gated.into_inner();
+        assert_eq!(held, Some(1));
+        assert_eq!(src.next(), Some(2));
+
+        let every = Ticker::every(Duration::from_millis(1));
+        assert_eq!(Ticker::from_ticks(0..3, every).collect::<Vec<_>>(), [0, 1, 2]);
+    }
+}
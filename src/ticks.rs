@@ -0,0 +1,156 @@
+//! Ticks on a crossbeam channel, to wait on in a ````select!```` alongside
+//! other channels.
+
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+
+use crossbeam_channel::{self, Receiver, RecvTimeoutError, TrySendError};
+
+use crate::{forward, Backend, Clock, Every, TickIter, Ticker, Wait, FORWARD_PENDING_CAP};
+
+impl<I: Iterator> Ticker<I> {
+    /// ticks returns a crossbeam Receiver of this Ticker's ticks, each the
+    /// Instant it fired, to wait on in a ````select!```` with other channels.
+    ///
+    /// The Receiver and ````.next()```` take from the same ticks, and each
+    /// tick goes to only one of them: a tick received from the channel does
+    /// not also release an item. Up to ````max_pending```` ticks wait on the
+    /// channel (at most 1024) and later ones are dropped, as with
+    /// ````MissedTickBehavior::Burst````; other behaviors keep one. Once this
+    /// is called, ticks are forwarded from a thread that runs as long as
+    /// a Receiver or the Ticker is held, and ````stop()```` disconnects the
+    /// channel.
+    ///
+    /// ````no_run
+    /// # use crossbeam_channel::{select, unbounded};
+    /// # use ticker::Ticker;
+    /// # use std::time::Duration;
+    /// # fn handle(_: String) {}
+    /// # fn heartbeat() {}
+    /// let (_events, events_recv) = unbounded::<String>();
+    /// let mut ticker = Ticker::every(Duration::from_secs(1));
+    /// let ticks = ticker.ticks();
+    /// loop {
+    ///     select! {
+    ///         recv(events_recv) -> event => match event {
+    ///             Ok(event) => handle(event),
+    ///             Err(_) => break,
+    ///         },
+    ///         recv(ticks) -> _ => heartbeat(),
+    ///     }
+    /// }
+    /// ````
+    pub fn ticks(&mut self) -> Receiver<Instant> {
+        if let Some(Backend::Ticks { ref recv, .. }) = self.backend {
+            return recv.clone();
+        }
+        let (send, recv) = crossbeam_channel::bounded(self.config.pending_cap().min(FORWARD_PENDING_CAP));
+        if self.done || self.shared.stopped.load(Ordering::SeqCst) {
+            return recv;
+        }
+        if self.backend.is_none() {
+            self.config.inline = true;
+        }
+        self.backend();
+        let backend = self.backend.take().expect("ticker to have started");
+        forward(backend, self.ticks_left(), move |fired| !matches!(send.try_send(fired), Err(TrySendError::Disconnected(_))));
+        self.backend = Some(Backend::Ticks {
+            recv: recv.clone(),
+            clock: self.shared.clock.clone(),
+        });
+        recv
+    }
+}
+
+impl Every {
+    /// ticks is ````Ticker::ticks````.
+    pub fn ticks(&mut self) -> Receiver<Instant> {
+        self.ticker.ticks()
+    }
+}
+
+impl<I: Iterator> TickIter<I> {
+    /// ticks is ````Ticker::ticks````.
+    pub fn ticks(&mut self) -> Receiver<Instant> {
+        self.ticker.ticks()
+    }
+}
+
+/// wait_until blocks until a tick arrives on ````recv````, or ````deadline````
+/// if there is one.
+pub(crate) fn wait_until(recv: &Receiver<Instant>, clock: &dyn Clock, deadline: Option<Instant>) -> Wait {
+    let deadline = match deadline {
+        None => return recv.recv().map_or(Wait::Ended, Wait::Tick),
+        Some(deadline) => deadline,
+    };
+    loop {
+        match recv.recv_timeout(clock.timeout(deadline)) {
+            Ok(fired) => return Wait::Tick(fired),
+            Err(RecvTimeoutError::Disconnected) => return Wait::Ended,
+            Err(RecvTimeoutError::Timeout) => {
+                if clock.now() >= deadline {
+                    return Wait::TimedOut;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Ticker;
+    use crossbeam_channel::{select, unbounded};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn select_ticks_and_data() {
+        let interval = Duration::from_millis(20);
+        let (data, data_recv) = unbounded();
+        thread::spawn(move || {
+            for i in 0..10 {
+                data.send(i).unwrap();
+                thread::sleep(interval / 4);
+            }
+        });
+        let mut ticker = Ticker::every(interval);
+        let ticks = ticker.ticks();
+        let (mut received, mut ticked) = (Vec::new(), 0);
+        loop {
+            select! {
+                recv(data_recv) -> msg => match msg {
+                    Ok(i) => received.push(i),
+                    Err(_) => break,
+                },
+                recv(ticks) -> tick => {
+                    assert!(tick.is_ok());
+                    ticked += 1;
+                }
+            }
+        }
+        assert_eq!(received, (0..10).collect::<Vec<_>>());
+        assert!((1..=3).contains(&ticked), "{}", ticked);
+    }
+
+    #[test]
+    fn next_shares_ticks() {
+        let interval = Duration::from_millis(10);
+        let mut ticker = Ticker::new(0..4, interval);
+        assert_eq!(ticker.next(), Some(0));
+        let ticks = ticker.ticks();
+        let start = Instant::now();
+        assert!(ticks.recv().is_ok());
+        assert_eq!(ticker.next(), Some(1));
+        assert!(start.elapsed() + Duration::from_millis(1) >= interval * 2, "{:?}", start.elapsed());
+
+        thread::sleep(interval * 4);
+        assert_eq!(ticks.len(), 1);
+        let handle = ticker.handle();
+        handle.stop();
+        assert_eq!(ticker.next(), None);
+        let start = Instant::now();
+        while ticks.recv().is_ok() {
+            assert!(start.elapsed() < Duration::from_secs(1));
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! Rate limited Streams driven by tokio's timer.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures_core::Stream;
+use tokio::time::{self, Instant, Interval};
+
+use crate::{MissedTickBehavior, Ticker};
+
+/// NEVER is how far off tokio itself puts a timer that should never fire.
+/// Longer periods and delays are cut to it, since tokio panics adding them
+/// to an Instant.
+const NEVER: Duration = Duration::from_secs(86400 * 365 * 30);
+
+/// TokioTicker is a Ticker whose ticks come from a ````tokio::time::Interval````
+/// instead of a worker thread; derive this from Ticker with
+/// ````.into_tokio()````. It follows tokio's clock, so
+/// ````tokio::time::pause()```` and ````advance()```` control it in tests.
+///
+/// Print 0-9, one number per second:
+///
+/// ````no_run
+/// # use std::time::Duration;
+/// use futures::stream::StreamExt;
+/// use ticker::Ticker;
+///
+/// # async fn run() {
+/// let mut ticks = Ticker::new(0..10, Duration::from_secs(1)).into_tokio();
+/// while let Some(i) = ticks.next().await {
+///     println!("{:?}", i)
+/// }
+/// # }
+/// ````
+///
+/// The Interval is created the first time the stream is polled, which must
+/// happen inside a tokio runtime, and the schedule starts from then. Missed
+/// ticks follow tokio's own ````MissedTickBehavior````, so under
+/// ````Burst```` the Ticker's ````max_pending```` cap does not apply. Jitter,
+/// ````start_full````, clock alignment, ````spin_threshold````,
+/// ````for_duration````, ````max_ticks```` and ````Pacing::MinGap```` are not
+/// applied.
+pub struct TokioTicker<I: Iterator> {
+    src: I,
+    item: Option<I::Item>,
+    done: bool,
+    period: Duration,
+    /// first is how long after the first poll the first tick comes.
+    first: Duration,
+    behavior: time::MissedTickBehavior,
+    interval: Option<Interval>,
+}
+
+impl<I: Iterator> Ticker<I> {
+    /// into_tokio turns this Ticker into a Stream paced by tokio's timer. It
+    /// keeps the Ticker's interval, first tick, initial delay and missed tick
+    /// settings.
+    pub fn into_tokio(self) -> TokioTicker<I> {
+        let behavior = match self.config.missed_tick_behavior {
+            MissedTickBehavior::Burst => time::MissedTickBehavior::Burst,
+            MissedTickBehavior::Delay => time::MissedTickBehavior::Delay,
+            MissedTickBehavior::Skip => time::MissedTickBehavior::Skip,
+        };
+        TokioTicker {
+            src: self.src,
+            item: None,
+            done: false,
+            period: self.config.interval.min(NEVER),
+            first: self.config.first_delay().min(NEVER),
+            behavior,
+            interval: None,
+        }
+    }
+}
+
+// The buffered item is never pinned, and Interval is Unpin.
+impl<I: Iterator> Unpin for TokioTicker<I> {}
+
+impl<I: Iterator> Stream for TokioTicker<I> {
+    type Item = I::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+        if this.item.is_none() {
+            match this.src.next() {
+                Some(item) => this.item = Some(item),
+                None => {
+                    this.done = true;
+                    return Poll::Ready(None);
+                }
+            }
+        }
+
+        if this.period == Duration::ZERO {
+            return Poll::Ready(this.item.take());
+        }
+        let period = this.period;
+        let first = this.first;
+        let behavior = this.behavior;
+        let interval = this.interval.get_or_insert_with(|| {
+            let mut interval = time::interval_at(Instant::now() + first, period);
+            interval.set_missed_tick_behavior(behavior);
+            interval
+        });
+        match interval.poll_tick(cx) {
+            Poll::Ready(_) => Poll::Ready(this.item.take()),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            return (0, Some(0));
+        }
+        let buffered = self.item.is_some() as usize;
+        let (lower, upper) = self.src.size_hint();
+        (lower.saturating_add(buffered), upper.and_then(|u| u.checked_add(buffered)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future::FutureExt;
+    use futures::stream::StreamExt;
+
+    #[tokio::test(start_paused = true)]
+    async fn paces_on_paused_clock() {
+        let start = Instant::now();
+        let items: Vec<i32> = Ticker::new(0..3, Duration::from_secs(60))
+            .into_tokio()
+            .collect()
+            .await;
+        assert_eq!(items, vec![0, 1, 2]);
+        assert_eq!(start.elapsed(), Duration::from_secs(180));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waits_for_advance() {
+        let mut ticks = Ticker::new(0..3, Duration::from_secs(60)).into_tokio();
+        assert_eq!(ticks.next().now_or_never(), None);
+
+        time::advance(Duration::from_secs(59)).await;
+        assert_eq!(ticks.next().now_or_never(), None);
+        time::advance(Duration::from_secs(1)).await;
+        assert_eq!(ticks.next().now_or_never(), Some(Some(0)));
+        assert_eq!(ticks.next().now_or_never(), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn first_tick_immediately() {
+        let start = Instant::now();
+        let mut ticks = Ticker::new(0..3, Duration::from_secs(60))
+            .first_tick_immediately(true)
+            .into_tokio();
+        assert_eq!(ticks.next().await, Some(0));
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        assert_eq!(ticks.next().await, Some(1));
+        assert_eq!(start.elapsed(), Duration::from_secs(60));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn initial_delay() {
+        let start = Instant::now();
+        let mut ticks = Ticker::with_delay(0..3, Duration::from_secs(5), Duration::from_secs(1)).into_tokio();
+        assert_eq!(ticks.next().await, Some(0));
+        assert_eq!(start.elapsed(), Duration::from_secs(5));
+        assert_eq!(ticks.next().await, Some(1));
+        assert_eq!(start.elapsed(), Duration::from_secs(6));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn max_period_never_ticks() {
+        let year = Duration::from_secs(86400 * 365);
+        let mut ticks = Ticker::with_delay(0..3, Duration::from_secs(1), Duration::MAX).into_tokio();
+        assert_eq!(ticks.next().now_or_never(), None);
+        // Late for the first tick, so tokio works out the next one from now.
+        time::advance(Duration::from_secs(2)).await;
+        assert_eq!(ticks.next().now_or_never(), Some(Some(0)));
+        time::advance(year).await;
+        assert_eq!(ticks.next().now_or_never(), None);
+
+        let mut ticks = Ticker::with_delay(0..3, Duration::MAX, Duration::from_secs(1)).into_tokio();
+        assert_eq!(ticks.next().now_or_never(), None);
+        time::advance(year).await;
+        assert_eq!(ticks.next().now_or_never(), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn zero_period_passes_through() {
+        let mut ticks = Ticker::new(0..3, Duration::ZERO).into_tokio();
+        for i in 0..3 {
+            assert_eq!(ticks.next().now_or_never(), Some(Some(i)));
+        }
+        assert_eq!(ticks.next().now_or_never(), Some(None));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn missed_ticks_skip() {
+        let mut ticks = Ticker::new(0.., Duration::from_secs(10))
+            .missed_tick_behavior(MissedTickBehavior::Skip)
+            .into_tokio();
+        ticks.next().await;
+        time::advance(Duration::from_secs(25)).await;
+
+        let start = Instant::now();
+        ticks.next().await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        ticks.next().await;
+        assert_eq!(start.elapsed(), Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,134 @@
+//! Waiting on the consumer's thread, for the adapters that pace an Iterator
+//! without a worker.
+
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::clock::{self, Clock, SystemClock};
+
+/// Due is when the next item may be returned.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Due {
+    Now,
+    At(Instant),
+    /// Never is a deadline too far off to represent as an Instant.
+    Never,
+}
+
+impl Due {
+    /// after is ````wait```` from now on ````clock````.
+    pub(crate) fn after(clock: &dyn Clock, wait: Duration) -> Due {
+        if wait == Duration::ZERO {
+            Due::Now
+        } else {
+            Due::since(clock.now(), wait)
+        }
+    }
+
+    /// since is ````wait```` after ````from````.
+    pub(crate) fn since(from: Instant, wait: Duration) -> Due {
+        from.checked_add(wait).map_or(Due::Never, Due::At)
+    }
+
+    /// wait blocks until it is due on ````clock````. Never blocks forever.
+    pub(crate) fn wait(self, clock: &dyn Clock) {
+        match self {
+            Due::Now => {}
+            Due::At(due) => clock::sleep_until(clock, due),
+            Due::Never => loop {
+                thread::park();
+            },
+        }
+    }
+}
+
+/// Pull is the source of an Iterator paced on its consumer's thread, and the
+/// Clock it waits on. It pulls each item before waiting for it, as a Ticker
+/// set to ````pull_first(true)```` does, and never polls the source again
+/// once it has ended.
+pub(crate) struct Pull<I> {
+    src: I,
+    done: bool,
+    clock: Arc<dyn Clock>,
+}
+
+impl<I: Iterator> Pull<I> {
+    pub(crate) fn new(src: I) -> Self {
+        Pull {
+            src,
+            done: false,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// clock is the Clock waits are timed on.
+    pub(crate) fn clock(&self) -> &dyn Clock {
+        &*self.clock
+    }
+
+    pub(crate) fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// next pulls an item, then calls ````wait```` with it to wait until it
+    /// may be returned.
+    pub(crate) fn next<F>(&mut self, wait: F) -> Option<I::Item>
+    where
+        F: FnOnce(&I::Item, &dyn Clock),
+    {
+        self.try_next(|item, clock| {
+            wait(&item, clock);
+            Some(item)
+        })
+    }
+
+    /// try_next is ````next()```` for waits that can end iteration:
+    /// ````wait```` returns the item once it may be returned, or None if it
+    /// never may.
+    pub(crate) fn try_next<F>(&mut self, wait: F) -> Option<I::Item>
+    where
+        F: FnOnce(I::Item, &dyn Clock) -> Option<I::Item>,
+    {
+        if self.done {
+            return None;
+        }
+        let item = self.src.next().and_then(|item| wait(item, &*self.clock));
+        self.done = item.is_none();
+        item
+    }
+
+    /// is_done is whether the source has ended.
+    pub(crate) fn is_done(&self) -> bool {
+        self.done
+    }
+
+    pub(crate) fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            (0, Some(0))
+        } else {
+            self.src.size_hint()
+        }
+    }
+
+    pub(crate) fn into_inner(self) -> I {
+        self.src
+    }
+}
+
+/// clock_setter defines the ````.clock()```` setter of a limiter that waits on
+/// its caller's thread. The limiter's own ````set_clock```` moves it onto the
+/// new Clock and starts its schedule over there.
+macro_rules! clock_setter {
+    ($name:ident) => {
+        #[doc = concat!("clock sets the Clock this ", stringify!($name), " waits on, as\n",
+                        "````Ticker::new_with_clock```` does, and starts its schedule over on it.\n",
+                        "Defaults to the system clock.")]
+        pub fn clock<C: $crate::Clock + 'static>(mut self, clock: C) -> Self {
+            self.set_clock(::std::sync::Arc::new(clock));
+            self
+        }
+    };
+}
+
+pub(crate) use clock_setter;
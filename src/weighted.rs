@@ -0,0 +1,122 @@
+//! Rate limiting by cost, for sources whose items are not all equal.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::wait::{clock_setter, Due, Pull};
+use crate::Clock;
+
+/// Weighted rate limits an Iterator by the cost of its items: after yielding an
+/// item that costs ````n````, the next item is returned no sooner than
+/// ````n * interval_per_unit```` later. Create one with ````Ticker::weighted````.
+///
+/// The cost is paid after its item, so the first item is returned immediately
+/// and zero cost items add no wait at all. Like every adapter paced on the
+/// consumer's thread, it banks no time spent away; see the crate docs.
+pub struct Weighted<I, F> {
+    pull: Pull<I>,
+    unit: Duration,
+    cost: F,
+    due: Due,
+}
+
+impl<I, F> Weighted<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> u64,
+{
+    pub(crate) fn new(src: I, unit: Duration, cost: F) -> Self {
+        Weighted {
+            pull: Pull::new(src),
+            unit,
+            cost,
+            due: Due::Now,
+        }
+    }
+
+    clock_setter!(Weighted);
+
+    /// set_clock moves onto ````clock````, with the next item due at once.
+    fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.pull.set_clock(clock);
+        self.due = Due::Now;
+    }
+}
+
+/// scaled is ````unit * cost````, saturating at ````Duration::MAX````.
+fn scaled(unit: Duration, cost: u64) -> Duration {
+    let nanos = unit.as_nanos().saturating_mul(u128::from(cost));
+    let secs = nanos / 1_000_000_000;
+    if secs > u128::from(u64::MAX) {
+        return Duration::MAX;
+    }
+    Duration::new(secs as u64, (nanos % 1_000_000_000) as u32)
+}
+
+impl<I, F> Iterator for Weighted<I, F>
+where
+    I: Iterator,
+    F: FnMut(&I::Item) -> u64,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Weighted { pull, unit, cost, due } = self;
+        pull.next(|item, clock| {
+            due.wait(clock);
+            *due = Due::after(clock, scaled(*unit, cost(item)));
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pull.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::JumpClock;
+    use crate::Ticker;
+
+    #[test]
+    fn gaps_follow_cost() {
+        let unit = Duration::from_millis(10);
+        let costs = [1u64, 4, 0, 2, 1];
+        let clock = JumpClock::default();
+        let start = clock.now();
+        let arrivals: Vec<_> = Ticker::weighted(costs.iter().copied(), unit, |c| *c)
+            .clock(clock.clone())
+            .map(|_| (clock.now() - start).as_millis())
+            .collect();
+        assert_eq!(arrivals, [0, 10, 50, 50, 70]);
+
+        // Time away counts towards the wait, but is not banked.
+        let mut iter = Ticker::weighted(0.., unit, |_| 2).clock(clock.clone());
+        iter.next();
+        clock.0.advance(unit * 5);
+        let start = clock.now();
+        iter.next();
+        assert_eq!(clock.now(), start);
+        iter.next();
+        assert_eq!(clock.now() - start, unit * 2);
+    }
+
+    #[test]
+    fn zero_cost_does_not_wait() {
+        let clock = JumpClock::default();
+        let start = clock.now();
+        let count = Ticker::weighted(0..100, Duration::from_secs(1), |_| 0).clock(clock.clone()).count();
+        assert_eq!(count, 100);
+        assert_eq!(clock.now(), start);
+    }
+
+    #[test]
+    fn enormous_cost_does_not_overflow() {
+        assert_eq!(scaled(Duration::MAX, u64::MAX), Duration::MAX);
+        assert_eq!(scaled(Duration::from_millis(3), 5), Duration::from_millis(15));
+
+        let mut iter = Ticker::weighted(0.., Duration::from_secs(u64::MAX), |_| u64::MAX);
+        assert_eq!(iter.next(), Some(0));
+    }
+}
@@ -0,0 +1,105 @@
+//! Rate limiting by a quota of items per sliding window.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::wait::{clock_setter, Due, Pull};
+use crate::Clock;
+
+/// Windowed rate limits an Iterator to at most ````n```` items in any
+/// ````window````, the way most API quotas are written: "10 per second" lets
+/// ten items through back to back, then holds the eleventh until a full
+/// second after the first. Create one with ````Ticker::per_window````.
+///
+/// The window slides, so however the items clump, no span of ````window````
+/// ever holds more than ````n````. Only the times of the last ````n```` items
+/// are kept.
+pub struct Windowed<I> {
+    pull: Pull<I>,
+    n: usize,
+    window: Duration,
+    /// returned holds when each of the last ````n```` items was returned,
+    /// oldest first.
+    returned: VecDeque<Instant>,
+}
+
+impl<I: Iterator> Windowed<I> {
+    pub(crate) fn new(src: I, n: usize, window: Duration) -> Self {
+        assert!(n > 0, "per_window needs room for at least 1 item");
+        Windowed {
+            pull: Pull::new(src),
+            n,
+            window,
+            returned: VecDeque::with_capacity(n.min(1024)),
+        }
+    }
+
+    clock_setter!(Windowed);
+
+    /// set_clock moves onto ````clock````, with an empty window.
+    fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.pull.set_clock(clock);
+        self.returned.clear();
+    }
+}
+
+impl<I: Iterator> Iterator for Windowed<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Windowed { pull, n, window, returned } = self;
+        pull.next(|_, clock| {
+            if returned.len() == *n {
+                if let Some(oldest) = returned.pop_front() {
+                    Due::since(oldest, *window).wait(clock);
+                }
+            }
+            returned.push_back(clock.now());
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.pull.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::JumpClock;
+    use crate::Ticker;
+
+    #[test]
+    fn no_window_holds_more_than_n() {
+        let window = Duration::from_millis(50);
+        let clock = JumpClock::default();
+        let start = clock.now();
+        let arrivals: Vec<_> = Ticker::per_window(0..17, 4, window)
+            .clock(clock.clone())
+            .map(|_| clock.now() - start)
+            .collect();
+        // The first 4 clump together, then each 4 more wait a window.
+        let expected: Vec<_> = (0..17).map(|i| window * (i / 4)).collect();
+        assert_eq!(arrivals, expected);
+
+        // Each item waits until a window after the one n before it, however
+        // the items before it were spread.
+        let mut iter = Ticker::per_window(0.., 2, window).clock(clock.clone());
+        iter.next();
+        clock.0.advance(window / 2);
+        iter.next();
+        let start = clock.now();
+        iter.next();
+        assert_eq!(clock.now() - start, window / 2);
+        iter.next();
+        assert_eq!(clock.now() - start, window);
+    }
+
+    #[test]
+    fn zero_items_per_window_panics() {
+        assert!(std::panic::catch_unwind(|| Ticker::per_window(0..1, 0, Duration::from_secs(1))).is_err());
+        let mut never = Ticker::per_window(0.., 1, Duration::MAX);
+        assert_eq!(never.next(), Some(0));
+    }
+}